@@ -2,7 +2,7 @@ use turubai::{
     color::Color,
     composition::VStack,
     elements::{Modifiers, Text},
-    postprocessing::{background_color, padding},
+    postprocessing::{background_color, corner_radius, padding},
     runtime::WindowTemplate,
     Application,
     Unit::Em,
@@ -28,6 +28,7 @@ impl Application for MyApplication {
                 }
                 .padding(all: Em(1.0))
                 .background_color(black)
+                .corner_radius(12.0)
             }
         )
     }
@@ -39,7 +39,7 @@ impl Application for MyApplication {
             WindowTemplate(title: "Hello, World!") {
                 VStack(spacing: Em(0.0), alignment: HorizontalAlignment::Center, text::color: white.clone()) {
                     Spacer()
-                    HStack(spacing: Em(0.0), text::font: inter_font.clone(), alignment: VerticalAlignment::Center) {
+                    HStack(spacing: Em(0.0), text::font: Some(inter_font.clone()), alignment: VerticalAlignment::Center) {
                         Spacer()
                         Text("CRAYON", color: white.clone())
                         HStack(spacing: Pixels(-1.0), text::decoration: thick_decoration) {
@@ -55,7 +55,7 @@ impl Application for MyApplication {
                         .padding(all: Em(0.25))
                         Spacer()
                     }
-                    Text("Nevermind, I hate commas.", font: courier_font.clone())
+                    Text("Nevermind, I hate commas.", font: Some(courier_font.clone()))
                         .padding(all: Em(0.25))
                     Spacer()
                 }
@@ -2,7 +2,7 @@ use turubai::{
     color::Color,
     composition::{HStack, HorizontalAlignment, VStack},
     elements::{Element, Modifiers, Text, TextDecoration, TextDecorationLine, TextLineStyle},
-    font::{Font, FontWeight},
+    font::{Font, FontWeight, TextStyle},
     runtime::WindowTemplate,
     Application,
     Unit::Em,
@@ -54,27 +54,31 @@ impl Application for TextDemoApp {
         turubai!(
             WindowTemplate(title: "GTK Text Demo") {
                 VStack(spacing: Em(1.0), alignment: HorizontalAlignment::Center) {
-                    Text("Standard Text", font: base_font.clone()),
+                    Text("Title", style: TextStyle::Title),
 
-                    Text("Bold Text", font: bold_font.clone()),
+                    Text("Headline", style: TextStyle::Headline),
 
-                    Text("Italic Text", font: italic_font.clone()),
+                    Text("Standard Text", font: Some(base_font.clone())),
 
-                    Text("Large Serif", font: large_font.clone()),
+                    Text("Bold Text", font: Some(bold_font.clone())),
 
-                    Text("Red Text", color: red.clone(), font: base_font.clone()),
+                    Text("Italic Text", font: Some(italic_font.clone())),
 
-                    Text("Underlined", decoration: underline.clone(), font: base_font.clone()),
+                    Text("Large Serif", font: Some(large_font.clone())),
 
-                    Text("Strikethrough (Red Line)", decoration: strike.clone(), font: base_font.clone()),
+                    Text("Red Text", color: red.clone(), font: Some(base_font.clone())),
 
-                    Text("Double Blue Underline", decoration: colored_underline.clone(), font: base_font.clone()),
+                    Text("Underlined", decoration: underline.clone(), font: Some(base_font.clone())),
+
+                    Text("Strikethrough (Red Line)", decoration: strike.clone(), font: Some(base_font.clone())),
+
+                    Text("Double Blue Underline", decoration: colored_underline.clone(), font: Some(base_font.clone())),
 
                     HStack(spacing: Em(1.0)) {
-                        Text("Mixed: ", font: base_font.clone()),
-                        Text("Red", color: red.clone(), font: bold_font.clone()),
-                        Text(" & ", font: base_font.clone()),
-                        Text("Blue", color: blue.clone(), font: italic_font.clone()),
+                        Text("Mixed: ", font: Some(base_font.clone())),
+                        Text("Red", color: red.clone(), font: Some(bold_font.clone())),
+                        Text(" & ", font: Some(base_font.clone())),
+                        Text("Blue", color: blue.clone(), font: Some(italic_font.clone())),
                     }
                 }
             },
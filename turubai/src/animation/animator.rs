@@ -0,0 +1,410 @@
+use std::{collections::HashMap, rc::Rc, time::Duration};
+
+use taffy::Layout;
+
+use crate::{
+    animation::{Animation, Edge, Transition},
+    color::Color,
+    fill::{Fill, UnitPoint},
+    postprocessing::{TransformModifiers, TransformOp},
+    shadow::{NodeIdentity, NodeKind, ShadowNode, ShadowTree},
+};
+
+/// The animatable values of a node as they are on screen.
+#[derive(Debug, Clone, PartialEq)]
+struct Presentation {
+    layout: Option<Layout>,
+    opacity: f32,
+    transform: TransformModifiers,
+    color: Option<Color>,
+}
+
+impl Presentation {
+    fn of(node: &ShadowNode, tree: &ShadowTree) -> Self {
+        Self {
+            layout: tree.get_layout(node.taffy_id).copied(),
+            opacity: node.effects.opacity,
+            transform: node.transform.clone(),
+            color: node_color(&node.kind),
+        }
+    }
+
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        let layout = match (&self.layout, &to.layout) {
+            (Some(from), Some(to)) => Some(lerp_layout(from, to, progress as f32)),
+            (_, to) => *to,
+        };
+        let color = match (&self.color, &to.color) {
+            (Some(from), Some(to)) => Some(from.mix(to, progress as f32)),
+            (_, to) => *to,
+        };
+        Self {
+            layout,
+            opacity: lerp(self.opacity, to.opacity, progress as f32).clamp(0.0, 1.0),
+            transform: lerp_transform(&self.transform, &to.transform, progress),
+            color,
+        }
+    }
+
+    /// Write everything but the layout into `node`.
+    fn apply(&self, node: &mut ShadowNode) {
+        node.effects.opacity = self.opacity;
+        node.transform = self.transform.clone();
+        if let Some(color) = self.color {
+            set_node_color(&mut node.kind, color);
+        }
+    }
+
+    /// What `transition` makes of this presentation while the node is
+    /// inserted or removed.
+    fn transitioned(&self, transition: Transition) -> Self {
+        let mut presentation = self.clone();
+        let (width, height) = self
+            .layout
+            .map(|layout| (layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        // Appended, so it applies on top of the node's own transform
+        let op = match transition {
+            Transition::Identity => return presentation,
+            Transition::Fade => {
+                presentation.opacity = 0.0;
+                return presentation;
+            }
+            Transition::Slide(edge) => match edge {
+                Edge::Top => TransformOp::Offset { x: 0.0, y: -height },
+                Edge::Leading => TransformOp::Offset { x: -width, y: 0.0 },
+                Edge::Bottom => TransformOp::Offset { x: 0.0, y: height },
+                Edge::Trailing => TransformOp::Offset { x: width, y: 0.0 },
+            },
+            Transition::Scale => TransformOp::Scale {
+                x: 0.0,
+                y: 0.0,
+                anchor: UnitPoint::CENTER,
+            },
+        };
+        presentation.transform.ops.push(op);
+        presentation
+    }
+}
+
+struct Track {
+    /// Child indices leading to the node in the tree on screen
+    path: Vec<usize>,
+    from: Presentation,
+    to: Presentation,
+    animation: Animation,
+    start: Duration,
+}
+
+/// A node a change removed, kept on screen while its removal transition
+/// runs. Its layout is frozen where it was when it was removed.
+pub struct Removal {
+    /// Unique among the animator's removals, so backends can tell which of
+    /// the views they keep for removals are done
+    pub id: u64,
+    /// Child indices leading to the parent the node was removed from
+    pub parent: Vec<usize>,
+    /// The node's index among its parent's children before the change
+    pub index: usize,
+    pub node: ShadowNode,
+    /// The tree the node was laid out in
+    pub tree: Rc<ShadowTree>,
+    track: Track,
+}
+
+/// Interpolates between the shadow tree on screen and the one a state
+/// change produced.
+///
+/// Nodes are matched by their [`NodeIdentity`]: a node is the same node
+/// after a change if it was built from the same element with the same key,
+/// or at the same child index without one, below the same parent. Whatever
+/// has no counterpart was inserted or removed.
+#[derive(Default)]
+pub struct Animator {
+    tracks: HashMap<NodeIdentity, Track>,
+    removals: Vec<Removal>,
+    next_removal: u64,
+}
+
+impl Animator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether anything still moves.
+    pub fn is_animating(&self) -> bool {
+        !self.tracks.is_empty() || !self.removals.is_empty()
+    }
+
+    /// Nodes that were removed but are still transitioning out.
+    pub fn removals(&self) -> &[Removal] {
+        &self.removals
+    }
+
+    /// Start animating from `old_root`, as presented in `old_tree`, to
+    /// `new_root`, laid out in `new_tree`. Without an animation every
+    /// change jumps, and so do running animations of what changed.
+    pub fn begin(
+        &mut self,
+        old_root: &ShadowNode,
+        old_tree: ShadowTree,
+        new_root: &ShadowNode,
+        new_tree: &ShadowTree,
+        animation: Option<&Animation>,
+        now: Duration,
+    ) {
+        let old_tree = Rc::new(old_tree);
+        let mut tracks = HashMap::new();
+        self.diff(
+            old_root,
+            &old_tree,
+            new_root,
+            new_tree,
+            &mut vec![],
+            animation,
+            now,
+            &mut tracks,
+        );
+        self.tracks = tracks;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn diff(
+        &mut self,
+        old: &ShadowNode,
+        old_tree: &Rc<ShadowTree>,
+        new: &ShadowNode,
+        new_tree: &ShadowTree,
+        path: &mut Vec<usize>,
+        animation: Option<&Animation>,
+        now: Duration,
+        tracks: &mut HashMap<NodeIdentity, Track>,
+    ) {
+        let from = Presentation::of(old, old_tree);
+        let to = Presentation::of(new, new_tree);
+        let running = self.tracks.remove(&new.identity);
+        match (animation, running) {
+            // Still heading where it was going, wherever it moved
+            (_, Some(running)) if running.to == to => {
+                let running = Track {
+                    path: path.clone(),
+                    ..running
+                };
+                tracks.insert(new.identity, running);
+            }
+            (Some(animation), _) if from != to => {
+                let track = Track {
+                    path: path.clone(),
+                    from,
+                    to,
+                    animation: animation.clone(),
+                    start: now,
+                };
+                tracks.insert(new.identity, track);
+            }
+            _ => {}
+        }
+
+        // Children that moved are matched up too, if they have keys
+        let mut old_children: Vec<Option<&ShadowNode>> = old.children.iter().map(Some).collect();
+        for (index, new_child) in new.children.iter().enumerate() {
+            path.push(index);
+            let old_child = old_children
+                .iter_mut()
+                .find(|old_child| old_child.is_some_and(|old_child| old_child.identity == new_child.identity))
+                .and_then(Option::take);
+            match old_child {
+                Some(old_child) => {
+                    self.diff(old_child, old_tree, new_child, new_tree, path, animation, now, tracks);
+                }
+                None => self.insert(new_child, new_tree, path, animation, now, tracks),
+            }
+            path.pop();
+        }
+        for (old_index, old_child) in old_children.into_iter().enumerate() {
+            if let Some(old_child) = old_child {
+                path.push(old_index);
+                self.remove(old_child, old_tree, path, old_index, animation, now);
+                path.pop();
+            }
+        }
+    }
+
+    fn insert(
+        &mut self,
+        node: &ShadowNode,
+        tree: &ShadowTree,
+        path: &[usize],
+        animation: Option<&Animation>,
+        now: Duration,
+        tracks: &mut HashMap<NodeIdentity, Track>,
+    ) {
+        let Some(animation) = animation else {
+            return;
+        };
+        let to = Presentation::of(node, tree);
+        let from = to.transitioned(node.transition.insertion);
+        if from != to {
+            let track = Track {
+                path: path.to_vec(),
+                from,
+                to,
+                animation: animation.clone(),
+                start: now,
+            };
+            tracks.insert(node.identity, track);
+        }
+    }
+
+    fn remove(
+        &mut self,
+        node: &ShadowNode,
+        tree: &Rc<ShadowTree>,
+        path: &[usize],
+        index: usize,
+        animation: Option<&Animation>,
+        now: Duration,
+    ) {
+        let Some(animation) = animation else {
+            return;
+        };
+        let from = Presentation::of(node, tree);
+        let to = from.transitioned(node.transition.removal);
+        if from != to {
+            self.next_removal += 1;
+            self.removals.push(Removal {
+                id: self.next_removal,
+                parent: path[..path.len() - 1].to_vec(),
+                index,
+                node: node.clone(),
+                tree: tree.clone(),
+                track: Track {
+                    path: path.to_vec(),
+                    from,
+                    to,
+                    animation: animation.clone(),
+                    start: now,
+                },
+            });
+        }
+    }
+
+    /// Write the values for `now` into `root` and `tree`. Returns whether
+    /// anything is still animating.
+    pub fn tick(&mut self, root: &mut ShadowNode, tree: &mut ShadowTree, now: Duration) -> bool {
+        self.tracks.retain(|_, track| {
+            let Some(node) = node_at_mut(root, &track.path) else {
+                return false;
+            };
+            let elapsed = now.saturating_sub(track.start);
+            // Follow the layout if it changed since, e.g. on resize
+            track.to.layout = tree.get_computed_layout(node.taffy_id).copied();
+
+            let finished = track.animation.is_finished(elapsed);
+            let presentation = match finished {
+                true => track.to.clone(),
+                false => track.from.interpolate(&track.to, track.animation.progress(elapsed)),
+            };
+            presentation.apply(node);
+            tree.set_presented_layout(node.taffy_id, presentation.layout.filter(|_| !finished));
+            !finished
+        });
+
+        self.removals.retain_mut(|removal| {
+            let elapsed = now.saturating_sub(removal.track.start);
+            let track = &removal.track;
+            let presentation = track.from.interpolate(&track.to, track.animation.progress(elapsed));
+            presentation.apply(&mut removal.node);
+            !track.animation.is_finished(elapsed)
+        });
+
+        self.is_animating()
+    }
+}
+
+fn node_at_mut<'a>(node: &'a mut ShadowNode, path: &[usize]) -> Option<&'a mut ShadowNode> {
+    match path.split_first() {
+        Some((index, rest)) => node_at_mut(node.children.get_mut(*index)?, rest),
+        None => Some(node),
+    }
+}
+
+fn node_color(kind: &NodeKind) -> Option<Color> {
+    match kind {
+        NodeKind::Text { color, .. }
+        | NodeKind::BackgroundColor { color }
+        | NodeKind::Border { color, .. }
+        | NodeKind::Shape {
+            fill: Some(Fill::Color(color)),
+            ..
+        } => Some(*color),
+        _ => None,
+    }
+}
+
+fn set_node_color(kind: &mut NodeKind, value: Color) {
+    match kind {
+        NodeKind::Text { color, .. }
+        | NodeKind::BackgroundColor { color }
+        | NodeKind::Border { color, .. }
+        | NodeKind::Shape {
+            fill: Some(Fill::Color(color)),
+            ..
+        } => *color = value,
+        _ => {}
+    }
+}
+
+fn lerp(from: f32, to: f32, progress: f32) -> f32 {
+    from + (to - from) * progress
+}
+
+fn lerp_layout(from: &Layout, to: &Layout, progress: f32) -> Layout {
+    Layout {
+        location: taffy::Point {
+            x: lerp(from.location.x, to.location.x, progress),
+            y: lerp(from.location.y, to.location.y, progress),
+        },
+        size: taffy::Size {
+            width: lerp(from.size.width, to.size.width, progress).max(0.0),
+            height: lerp(from.size.height, to.size.height, progress).max(0.0),
+        },
+        ..*to
+    }
+}
+
+/// Interpolate op by op. A list that is shorter than the other is padded
+/// with operations that do nothing; where the kinds differ it jumps.
+fn lerp_transform(from: &TransformModifiers, to: &TransformModifiers, progress: f64) -> TransformModifiers {
+    let identity = |op: &TransformOp| match *op {
+        TransformOp::Offset { .. } => TransformOp::Offset { x: 0.0, y: 0.0 },
+        TransformOp::Scale { anchor, .. } => TransformOp::Scale { x: 1.0, y: 1.0, anchor },
+        TransformOp::Rotate { anchor, .. } => TransformOp::Rotate { angle: 0.0, anchor },
+    };
+    let lerp = |from: f64, to: f64| from + (to - from) * progress;
+
+    let len = from.ops.len().max(to.ops.len());
+    let ops = (0..len)
+        .map(|i| {
+            let (a, b) = match (from.ops.get(i), to.ops.get(i)) {
+                (Some(a), Some(b)) => (*a, *b),
+                (Some(a), None) => (*a, identity(a)),
+                (None, Some(b)) => (identity(b), *b),
+                (None, None) => unreachable!(),
+            };
+            match (a, b) {
+                (TransformOp::Offset { x: x0, y: y0 }, TransformOp::Offset { x, y }) => {
+                    TransformOp::Offset { x: lerp(x0, x), y: lerp(y0, y) }
+                }
+                (TransformOp::Scale { x: x0, y: y0, .. }, TransformOp::Scale { x, y, anchor }) => {
+                    TransformOp::Scale { x: lerp(x0, x), y: lerp(y0, y), anchor }
+                }
+                (TransformOp::Rotate { angle: a0, .. }, TransformOp::Rotate { angle, anchor }) => {
+                    TransformOp::Rotate { angle: lerp(a0, angle), anchor }
+                }
+                (_, b) => b,
+            }
+        })
+        .collect();
+    TransformModifiers { ops }
+}
@@ -0,0 +1,131 @@
+/// The shape of an animation's progress over time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    Linear,
+    /// CSS `ease-in`
+    EaseIn,
+    /// CSS `ease-out`
+    EaseOut,
+    /// CSS `ease-in-out`
+    EaseInOut,
+    /// A cubic Bézier from `(0, 0)` to `(1, 1)` through two control points,
+    /// like CSS `cubic-bezier()`
+    CubicBezier(f64, f64, f64, f64),
+    /// A damped spring, see [`Animation::spring`](super::Animation::spring)
+    Spring { response: f64, damping: f64 },
+    /// `(fraction of the duration, progress)` pairs sorted by time, from
+    /// `(0, 0)` to `(1, 1)`
+    Keyframes(Vec<(f64, f64)>),
+}
+
+/// How close to rest a spring must be to count as settled.
+const SPRING_EPSILON: f64 = 1e-3;
+
+impl Curve {
+    /// Sort `keyframes` and pin the start and end if they are missing.
+    pub fn keyframes(keyframes: &[(f64, f64)]) -> Self {
+        let mut keyframes: Vec<(f64, f64)> = keyframes
+            .iter()
+            .map(|&(time, progress)| (time.clamp(0.0, 1.0), progress))
+            .collect();
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if keyframes.first().is_none_or(|first| first.0 > 0.0) {
+            keyframes.insert(0, (0.0, 0.0));
+        }
+        if keyframes.last().is_none_or(|last| last.0 < 1.0) {
+            keyframes.push((1.0, 1.0));
+        }
+        Curve::Keyframes(keyframes)
+    }
+
+    /// Progress at `t`, a fraction of the duration for every curve but
+    /// springs, which take seconds.
+    pub fn value(&self, t: f64) -> f64 {
+        match self {
+            Curve::Linear => t,
+            Curve::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Curve::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Curve::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Curve::CubicBezier(x1, y1, x2, y2) => cubic_bezier(*x1, *y1, *x2, *y2, t),
+            Curve::Spring { response, damping } => spring(*response, *damping, t).0,
+            Curve::Keyframes(keyframes) => {
+                let next = keyframes
+                    .iter()
+                    .position(|&(time, _)| time >= t)
+                    .unwrap_or(keyframes.len() - 1);
+                if next == 0 {
+                    return keyframes[0].1;
+                }
+                let (t0, p0) = keyframes[next - 1];
+                let (t1, p1) = keyframes[next];
+                if t1 <= t0 {
+                    return p1;
+                }
+                p0 + (p1 - p0) * (t - t0) / (t1 - t0)
+            }
+        }
+    }
+
+    /// Seconds until a spring stays within [`SPRING_EPSILON`] of rest.
+    /// Zero for every other curve.
+    pub(crate) fn settling_time(&self) -> f64 {
+        let Curve::Spring { response, damping } = self else {
+            return 0.0;
+        };
+        let step = 1.0 / 240.0;
+        let mut t = 0.0;
+        // Capped in case of a spring that never comes to rest
+        while t < 10.0 {
+            if spring(*response, *damping, t).1 < SPRING_EPSILON {
+                return t;
+            }
+            t += step;
+        }
+        t
+    }
+}
+
+/// `(progress, bound)` of a spring released from 0 towards 1 after `t`
+/// seconds. `bound` is an upper limit on how far from 1 it can get from
+/// then on.
+fn spring(response: f64, damping: f64, t: f64) -> (f64, f64) {
+    let omega = std::f64::consts::TAU / response.max(1e-3);
+    let damping = damping.max(0.0);
+    if damping < 1.0 {
+        let decay = (-damping * omega * t).exp();
+        let omega_d = omega * (1.0 - damping * damping).sqrt();
+        let ratio = damping * omega / omega_d;
+        let progress = 1.0 - decay * ((omega_d * t).cos() + ratio * (omega_d * t).sin());
+        (progress, decay * (1.0 + ratio))
+    } else {
+        // Critically damped, approaches 1 without ever passing it
+        let decay = (-omega * t).exp();
+        let progress = 1.0 - decay * (1.0 + omega * t);
+        (progress, 1.0 - progress)
+    }
+}
+
+/// Solve the Bézier for the `x` equal to `t` and return its `y`.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, t: f64) -> f64 {
+    let bezier = |a: f64, b: f64, s: f64| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * a + 3.0 * inv * s * s * b + s * s * s
+    };
+
+    // x is monotonic in s, so bisection always converges
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut s = t;
+    for _ in 0..32 {
+        let x = bezier(x1, x2, s);
+        if (x - t).abs() < 1e-7 {
+            break;
+        }
+        if x < t {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    bezier(y1, y2, s)
+}
@@ -0,0 +1,299 @@
+//! Interpolating changes over time instead of jumping to them.
+//!
+//! A state change made inside [`with_animation`] animates every animatable
+//! property it affects: layout positions and sizes, opacity, transforms and
+//! colors. Elements inserted or removed by the change run their
+//! [`Transition`].
+//!
+//! ```ignore
+//! with_animation(Animation::spring(0.5, 0.7), || expanded.set(true));
+//! ```
+
+mod animator;
+mod curve;
+
+pub use animator::{Animator, Removal};
+pub use curve::Curve;
+
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// How long a change takes and how it progresses over that time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    curve: Curve,
+    duration: Duration,
+    delay: Duration,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::ease_in_out(0.25)
+    }
+}
+
+impl Animation {
+    pub fn new(curve: Curve, seconds: f64) -> Self {
+        Self {
+            curve,
+            duration: Duration::from_secs_f64(seconds.max(0.0)),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn linear(seconds: f64) -> Self {
+        Self::new(Curve::Linear, seconds)
+    }
+
+    pub fn ease_in(seconds: f64) -> Self {
+        Self::new(Curve::EaseIn, seconds)
+    }
+
+    pub fn ease_out(seconds: f64) -> Self {
+        Self::new(Curve::EaseOut, seconds)
+    }
+
+    pub fn ease_in_out(seconds: f64) -> Self {
+        Self::new(Curve::EaseInOut, seconds)
+    }
+
+    /// A spring that oscillates once every `response` seconds when
+    /// undamped. `damping` of `1.0` settles without overshooting, lower
+    /// values bounce. The duration is however long the spring takes to
+    /// settle.
+    pub fn spring(response: f64, damping: f64) -> Self {
+        let curve = Curve::Spring { response, damping };
+        let seconds = curve.settling_time();
+        Self::new(curve, seconds)
+    }
+
+    /// Progress given at fractions of the duration, e.g.
+    /// `[(0.0, 0.0), (0.6, 1.1), (1.0, 1.0)]`, joined by straight lines.
+    pub fn keyframes(seconds: f64, keyframes: &[(f64, f64)]) -> Self {
+        Self::new(Curve::keyframes(keyframes), seconds)
+    }
+
+    /// Start `seconds` after the change instead of right away.
+    pub fn with_delay(mut self, seconds: f64) -> Self {
+        self.delay = Duration::from_secs_f64(seconds.max(0.0));
+        self
+    }
+
+    pub fn curve(&self) -> &Curve {
+        &self.curve
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Progress `elapsed` after the change: `0.0` before it starts and
+    /// `1.0` once it is over. Springs and keyframes may go past `1.0` in
+    /// between.
+    pub fn progress(&self, elapsed: Duration) -> f64 {
+        let Some(elapsed) = elapsed.checked_sub(self.delay) else {
+            return 0.0;
+        };
+        if elapsed >= self.duration {
+            return 1.0;
+        }
+        match &self.curve {
+            // Springs run on absolute time, not a fraction of it
+            Curve::Spring { .. } => self.curve.value(elapsed.as_secs_f64()),
+            curve => curve.value(elapsed.as_secs_f64() / self.duration.as_secs_f64()),
+        }
+    }
+
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        elapsed >= self.delay + self.duration
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Animation>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, animating every state change it makes with `animation`.
+pub fn with_animation<R>(animation: Animation, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT.with(|current| current.replace(Some(animation)));
+    let result = f();
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// The animation of the innermost [`with_animation`] on this thread.
+pub(crate) fn current() -> Option<Animation> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// A clock that only moves when told to, so tests can step through an
+/// animation frame by frame.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+/// The side of a container an element slides in from or out to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edge {
+    Top,
+    #[default]
+    Leading,
+    Bottom,
+    Trailing,
+}
+
+/// How an element appears when a change inserts it and disappears when a
+/// change removes it. Only changes made inside [`with_animation`] run
+/// transitions.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transition {
+    /// Appear and disappear at once
+    #[default]
+    Identity,
+    /// Fade in and out
+    Fade,
+    /// Move in from and out to `Edge` by the element's own size
+    Slide(Edge),
+    /// Grow from and shrink to nothing around the center
+    Scale,
+}
+
+/// The transitions of an element, set with `.transition()`. Not inherited.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransitionModifiers {
+    pub insertion: Transition,
+    pub removal: Transition,
+}
+
+#[test]
+fn test_curves() {
+    let linear = Animation::linear(1.0);
+    assert_eq!(linear.progress(Duration::from_millis(250)), 0.25);
+    assert_eq!(linear.progress(Duration::from_secs(2)), 1.0);
+
+    let eased = Animation::ease_in_out(1.0);
+    let quarter = eased.progress(Duration::from_millis(250));
+    assert!(quarter < 0.25 && quarter > 0.0);
+    assert!((eased.progress(Duration::from_millis(500)) - 0.5).abs() < 1e-3);
+
+    let delayed = Animation::linear(1.0).with_delay(0.5);
+    assert_eq!(delayed.progress(Duration::from_millis(250)), 0.0);
+    assert!(!delayed.is_finished(Duration::from_millis(1250)));
+
+    // An underdamped spring overshoots before it settles
+    let spring = Animation::spring(0.5, 0.5);
+    let peak = (1..100)
+        .map(|ms| spring.progress(Duration::from_millis(ms * 10)))
+        .fold(0.0, f64::max);
+    assert!(peak > 1.0);
+    assert!(spring.duration() > Duration::from_millis(500));
+    assert_eq!(spring.progress(spring.duration()), 1.0);
+
+    let bounce = Animation::keyframes(1.0, &[(0.5, 1.2)]);
+    assert!((bounce.progress(Duration::from_millis(250)) - 0.6).abs() < 1e-9);
+    assert!((bounce.progress(Duration::from_millis(750)) - 1.1).abs() < 1e-9);
+}
+
+#[test]
+fn test_animation() {
+    use std::time::Duration;
+
+    use crate::{
+        animation::{with_animation, Animation, Transition},
+        color::ColorScheme,
+        composition::VStack,
+        elements::{Element, Modifiers, Text},
+        pal::headless::{Context, Window},
+        postprocessing::{opacity, transition},
+        state::State,
+    };
+
+    let expanded = State::new(false);
+    let markup = {
+        let expanded = expanded.clone();
+        move || -> Box<dyn Element> {
+            let modifiers = Modifiers::default();
+            let title = if expanded.get() { "A much longer title" } else { "Title" };
+            let mut children: Vec<Box<dyn Element>> = vec![opacity(
+                if expanded.get() { 1.0 } else { 0.5 },
+                Box::new(Text::new(title, modifiers.fork())),
+                modifiers.fork(),
+            )];
+            if expanded.get() {
+                let details = Box::new(Text::new("Details", modifiers.fork()));
+                children.push(transition(Transition::Fade, details, modifiers.fork()));
+            }
+            Box::new(VStack::new(modifiers, children))
+        }
+    };
+    let mut window = Context::new(ColorScheme::Light).render_markup(markup, 400.0, 300.0);
+    let frame = Duration::from_millis(100);
+    let title_width = |window: &Window| window.layout(&window.root.children[0]).unwrap().size.width;
+    let collapsed_width = title_width(&window);
+
+    with_animation(Animation::linear(0.4), || expanded.set(true));
+    window.update();
+    // The tree already holds the expanded layout, the screen does not yet
+    assert_eq!(title_width(&window), collapsed_width);
+    assert_eq!(window.root.children[0].effects.opacity, 0.5);
+    assert_eq!(window.root.children[1].effects.opacity, 0.0);
+    let expanded_width = window
+        .tree
+        .get_computed_layout(window.root.children[0].taffy_id)
+        .unwrap()
+        .size
+        .width;
+    assert!(expanded_width > collapsed_width);
+
+    window.advance(frame * 2);
+    let halfway = title_width(&window);
+    assert!((halfway - (collapsed_width + expanded_width) / 2.0).abs() < 0.01);
+    assert_eq!(window.root.children[0].effects.opacity, 0.75);
+    assert_eq!(window.root.children[1].effects.opacity, 0.5);
+
+    window.advance(frame * 2);
+    assert_eq!(title_width(&window), expanded_width);
+    assert_eq!(window.root.children[1].effects.opacity, 1.0);
+    assert!(!window.animator().is_animating());
+
+    // Removed nodes fade out after they left the tree
+    with_animation(Animation::linear(0.4), || expanded.set(false));
+    window.update();
+    assert_eq!(window.root.children.len(), 1);
+    window.advance(frame);
+    let removal = &window.animator().removals()[0];
+    assert_eq!(removal.node.effects.opacity, 0.75);
+    window.advance(frame * 3);
+    assert!(window.animator().removals().is_empty());
+
+    // Without an animation changes jump
+    expanded.set(true);
+    window.advance(frame);
+    assert_eq!(title_width(&window), expanded_width);
+    assert_eq!(window.root.children[1].effects.opacity, 1.0);
+}
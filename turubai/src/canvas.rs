@@ -0,0 +1,294 @@
+//! Immediate-mode drawing for the [`Canvas`](crate::elements::Canvas)
+//! element.
+//!
+//! The canvas' closure is called with a [`DrawContext`] sized to the
+//! canvas' layout whenever it is painted. It records [`DrawCommand`]s that
+//! each backend replays with its own renderer.
+//!
+//! ```ignore
+//! Canvas(move |cx| {
+//!     let (width, height) = cx.size();
+//!     cx.fill(&Path::new().rect(0.0, 0.0, width * progress.get(), height), Color::Accent);
+//! })
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use crate::{
+    color::Color,
+    elements::Interpolation,
+    environment::Environment,
+    fill::Fill,
+    font::{Font, TextStyle},
+    image::{Bitmap, ImageSource},
+    pal::headless,
+    postprocessing::AffineTransform,
+    shape::{Path, Stroke},
+};
+
+/// One recorded drawing operation. Paths and positions are in the
+/// coordinates set up by the transforms before it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    /// Remember the transform and clip
+    Save,
+    /// Go back to the transform and clip of the matching `Save`
+    Restore,
+    /// Apply `transform` before the current transform
+    Transform(AffineTransform),
+    /// Limit drawing to the inside of the path, on top of the current clip
+    Clip(Path),
+    /// Paint the inside of the path. Gradients span the path's bounds
+    Fill { path: Path, paint: Fill },
+    Stroke { path: Path, stroke: Stroke },
+    /// A single line of text whose top leading corner is at `origin`
+    Text {
+        content: String,
+        font: Font,
+        color: Color,
+        origin: (f64, f64),
+    },
+    /// A picture stretched to `rect`, given as `(x, y, width, height)`
+    Image {
+        bitmap: Arc<Bitmap>,
+        rect: (f64, f64, f64, f64),
+        interpolation: Interpolation,
+    },
+}
+
+/// What a canvas draws with. Colors and fonts go through the window's
+/// theme as they are recorded.
+pub struct DrawContext {
+    width: f64,
+    height: f64,
+    environment: Environment,
+    commands: Vec<DrawCommand>,
+}
+
+impl DrawContext {
+    pub fn new(width: f64, height: f64, environment: Environment) -> Self {
+        Self {
+            width,
+            height,
+            environment,
+            commands: vec![],
+        }
+    }
+
+    /// The canvas' laid out size in logical pixels.
+    pub fn size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    pub fn into_commands(self) -> Vec<DrawCommand> {
+        self.commands
+    }
+
+    pub fn fill(&mut self, path: &Path, paint: impl Into<Fill>) {
+        let paint = self.themed(&paint.into());
+        self.commands.push(DrawCommand::Fill {
+            path: path.clone(),
+            paint,
+        });
+    }
+
+    pub fn stroke(&mut self, path: &Path, stroke: &Stroke) {
+        let stroke = Stroke {
+            paint: self.themed(&stroke.paint),
+            ..stroke.clone()
+        };
+        self.commands.push(DrawCommand::Stroke {
+            path: path.clone(),
+            stroke,
+        });
+    }
+
+    /// Draw one line of `content` with its top leading corner at `(x, y)`.
+    pub fn fill_text(&mut self, content: &str, font: &Font, color: Color, x: f64, y: f64) {
+        self.commands.push(DrawCommand::Text {
+            content: content.to_string(),
+            font: self.environment.resolve_font(TextStyle::Custom, Some(font)),
+            color: self.environment.themed_color(&color),
+            origin: (x, y),
+        });
+    }
+
+    /// The size `content` takes up in `font`, in logical pixels. Measured
+    /// with the built-in metrics, which native text may differ from
+    /// slightly.
+    pub fn measure_text(&self, content: &str, font: &Font) -> (f64, f64) {
+        let font = self.environment.resolve_font(TextStyle::Custom, Some(font));
+        headless::measure_text(content, &font)
+    }
+
+    /// Draw the picture from `source` stretched to `width` x `height` at
+    /// `(x, y)`. Nothing is drawn if it fails to load.
+    pub fn draw_image(
+        &mut self,
+        source: &ImageSource,
+        (x, y, width, height): (f64, f64, f64, f64),
+        interpolation: Interpolation,
+    ) {
+        match source.load(self.environment.scale_factor()) {
+            Ok(bitmap) => self.commands.push(DrawCommand::Image {
+                bitmap,
+                rect: (x, y, width, height),
+                interpolation,
+            }),
+            Err(err) => eprintln!("[turubai] canvas can't draw image: {err}"),
+        }
+    }
+
+    pub fn save(&mut self) {
+        self.commands.push(DrawCommand::Save);
+    }
+
+    pub fn restore(&mut self) {
+        self.commands.push(DrawCommand::Restore);
+    }
+
+    /// Run `f` and undo the transforms and clips it made afterwards.
+    pub fn saved(&mut self, f: impl FnOnce(&mut Self)) {
+        self.save();
+        f(self);
+        self.restore();
+    }
+
+    pub fn transform(&mut self, transform: &AffineTransform) {
+        self.commands.push(DrawCommand::Transform(*transform));
+    }
+
+    pub fn translate(&mut self, x: f64, y: f64) {
+        self.transform(&AffineTransform::translation(x as f32, y as f32));
+    }
+
+    pub fn scale(&mut self, x: f64, y: f64) {
+        self.transform(&AffineTransform::scaling(x as f32, y as f32));
+    }
+
+    /// Rotate clockwise by `degrees` around the current origin.
+    pub fn rotate(&mut self, degrees: f64) {
+        self.transform(&AffineTransform::rotation(degrees as f32));
+    }
+
+    pub fn clip(&mut self, path: &Path) {
+        self.commands.push(DrawCommand::Clip(path.clone()));
+    }
+
+    fn themed(&self, fill: &Fill) -> Fill {
+        fill.map_colors(|color| self.environment.themed_color(color))
+    }
+}
+
+/// The closure of a canvas, shared between its element and shadow node.
+#[derive(Clone)]
+pub struct Drawing(Arc<dyn Fn(&mut DrawContext) + Send + Sync>);
+
+impl Drawing {
+    pub fn new(draw: impl Fn(&mut DrawContext) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(draw))
+    }
+
+    /// Run the closure for a canvas of `width` x `height`.
+    pub fn record(&self, width: f64, height: f64, environment: &Environment) -> Vec<DrawCommand> {
+        let mut context = DrawContext::new(width, height, environment.clone());
+        (self.0)(&mut context);
+        context.into_commands()
+    }
+}
+
+impl fmt::Debug for Drawing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Drawing")
+    }
+}
+
+/// Closures can't be saved, a drawing is saved as nothing.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Drawing {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit_struct("Drawing")
+    }
+}
+
+/// A loaded drawing draws nothing.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Drawing {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <()>::deserialize(deserializer)?;
+        Ok(Drawing::new(|_| {}))
+    }
+}
+
+#[test]
+fn test_canvas() {
+    use crate::{
+        canvas::DrawCommand,
+        color::{Color, ColorScheme},
+        composition::{HStack, Spacer},
+        elements::{Canvas, Modifiers},
+        fill::Fill,
+        font::Font,
+        pal::headless::Context,
+        shape::Path,
+        state::State,
+    };
+
+    let progress = State::new(0.25);
+    let modifiers = Modifiers::default();
+    let canvas = {
+        let progress = progress.clone();
+        Canvas::new(
+            move |cx| {
+                let (width, height) = cx.size();
+                cx.fill(&Path::new().rect(0.0, 0.0, width * progress.get(), height), Color::Accent);
+                cx.saved(|cx| {
+                    cx.translate(4.0, 4.0);
+                    cx.fill_text("50%", &Font::default(), Color::Text, 0.0, 0.0);
+                });
+            },
+            modifiers.fork(),
+        )
+    };
+    let row = HStack::new(
+        modifiers.clone(),
+        vec![Box::new(canvas), Box::new(Spacer::turubai_new_with_0_args(modifiers.fork(), |_| vec![]))],
+    );
+    let mut window = Context::new(ColorScheme::Light).render(Box::new(row), 200.0, 50.0);
+
+    // Canvases grow like shapes, sharing the row with the spacer
+    let canvas = &window.root.children[0];
+    let layout = *window.layout(canvas).unwrap();
+    assert_eq!((layout.size.width, layout.size.height), (100.0, 50.0));
+
+    let commands = window.draw_canvas(canvas).unwrap();
+    assert_eq!(commands.len(), 5);
+    assert_eq!(
+        commands[0],
+        DrawCommand::Fill {
+            path: Path::new().rect(0.0, 0.0, 25.0, 50.0),
+            paint: Fill::Color(window.theme().palette.apply(&Color::Accent)),
+        }
+    );
+    assert!(matches!(commands[1], DrawCommand::Save));
+    assert!(matches!(&commands[3], DrawCommand::Text { content, origin: (0.0, 0.0), .. } if content == "50%"));
+    assert!(matches!(commands[4], DrawCommand::Restore));
+
+    // The drawing follows the state it reads
+    progress.set(1.0);
+    window.update();
+    let canvas = &window.root.children[0];
+    match &window.draw_canvas(canvas).unwrap()[0] {
+        DrawCommand::Fill { path, .. } => assert_eq!(path.bounds(), Some((0.0, 0.0, 100.0, 50.0))),
+        other => panic!("expected a fill, got {other:?}"),
+    }
+    assert!(window.draw_canvas(&window.root).is_none());
+}
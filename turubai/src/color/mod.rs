@@ -0,0 +1,428 @@
+mod css;
+mod space;
+
+pub use css::ParseColorError;
+
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// Whether the interface is drawn with light or dark appearance.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// A concrete color with channels in `0.0..=1.0`, in the sRGB color space.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rgba {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Rgba {
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    const fn opaque(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0)
+    }
+
+    /// Channels scaled to `0..=255`.
+    pub fn to_u8(&self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// `#rrggbb`, or `#rrggbbaa` when not fully opaque.
+    pub fn to_hex(&self) -> String {
+        let [r, g, b, a] = self.to_u8();
+        if a == 255 {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        }
+    }
+
+    /// (hue in degrees, saturation, lightness)
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        space::rgb_to_hsl(self.r, self.g, self.b)
+    }
+
+    /// (hue in degrees, saturation, value)
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        space::rgb_to_hsv(self.r, self.g, self.b)
+    }
+
+    /// (lightness, chroma, hue in degrees)
+    pub fn to_oklch(&self) -> (f32, f32, f32) {
+        space::rgb_to_oklch(self.r, self.g, self.b)
+    }
+
+    fn lerp(&self, other: &Rgba, t: f32) -> Rgba {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Rgba::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    Text,
+    SystemRed,
+    SystemOrange,
+    SystemYellow,
+    SystemGreen,
+    SystemBlue,
+    SystemIndigo,
+    SystemPurple,
+    SystemPink,
+    /// The background of windows and other top level surfaces
+    WindowBackground,
+    /// De-emphasized text such as captions and placeholders
+    SecondaryText,
+    /// Thin lines dividing content
+    Separator,
+    /// The tint used for selection and prominent controls
+    Accent,
+    /// The fill behind buttons and other controls
+    ControlFill,
+    /// A color that differs between light and dark appearance
+    Dynamic { light: Rgba, dark: Rgba },
+    Custom { r: f32, g: f32, b: f32, a: f32 },
+}
+
+impl Hash for Color {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        match self {
+            Color::Text => {
+                "system_text".hash(state);
+            }
+            Color::SystemRed => {
+                "system_red".hash(state);
+            }
+            Color::SystemOrange => {
+                "system_orange".hash(state);
+            }
+            Color::SystemYellow => {
+                "system_yellow".hash(state);
+            }
+            Color::SystemGreen => {
+                "system_green".hash(state);
+            }
+            Color::SystemBlue => {
+                "system_blue".hash(state);
+            }
+            Color::SystemIndigo => {
+                "system_indigo".hash(state);
+            }
+            Color::SystemPurple => {
+                "system_violet".hash(state);
+            }
+            Color::SystemPink => {
+                "system_pink".hash(state);
+            }
+            Color::WindowBackground => {
+                "window_background".hash(state);
+            }
+            Color::SecondaryText => {
+                "secondary_text".hash(state);
+            }
+            Color::Separator => {
+                "separator".hash(state);
+            }
+            Color::Accent => {
+                "accent".hash(state);
+            }
+            Color::ControlFill => {
+                "control_fill".hash(state);
+            }
+            Color::Dynamic { light, dark } => {
+                "dynamic".hash(state);
+                for channel in [light.r, light.g, light.b, light.a, dark.r, dark.g, dark.b, dark.a] {
+                    channel.to_bits().hash(state);
+                }
+            }
+            Color::Custom { r, g, b, a } => {
+                r.to_bits().hash(state);
+                g.to_bits().hash(state);
+                b.to_bits().hash(state);
+                a.to_bits().hash(state);
+            }
+        }
+    }
+}
+
+impl Eq for Color {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<Rgba> for Color {
+    fn from(rgba: Rgba) -> Self {
+        Self::from_rgba(rgba.r, rgba.g, rgba.b, rgba.a)
+    }
+}
+
+impl Color {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self::new_with_alpha(r, g, b, 1.0)
+    }
+
+    /// Build a color from channels in `0.0..=1.0`.
+    pub fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::Custom { r, g, b, a }
+    }
+
+    /// Parse `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`. The `#` is optional.
+    pub fn from_hex(hex: &str) -> Result<Self, ParseColorError> {
+        css::parse_hex(hex)
+    }
+
+    /// Parse a CSS color string such as `"#ff4356"`, `"rgb(255 67 86 / 50%)"`,
+    /// `"hsl(353, 100%, 63%)"`, `"oklch(65% 0.22 20)"` or `"tomato"`.
+    pub fn parse(css: &str) -> Result<Self, ParseColorError> {
+        css::parse_css(css)
+    }
+
+    /// A color that shows `light` under the light appearance and `dark`
+    /// under the dark one.
+    pub fn dynamic(light: Color, dark: Color) -> Self {
+        Self::Dynamic {
+            light: light.resolve(ColorScheme::Light),
+            dark: dark.resolve(ColorScheme::Dark),
+        }
+    }
+
+    /// Hue in degrees, saturation and lightness in `0.0..=1.0`.
+    pub fn hsl(h: f32, s: f32, l: f32) -> Self {
+        let (r, g, b) = space::hsl_to_rgb(h, s, l);
+        Self::from_rgba(r, g, b, 1.0)
+    }
+
+    /// Hue in degrees, saturation and value in `0.0..=1.0`.
+    pub fn hsv(h: f32, s: f32, v: f32) -> Self {
+        let (r, g, b) = space::hsv_to_rgb(h, s, v);
+        Self::from_rgba(r, g, b, 1.0)
+    }
+
+    /// Perceptual lightness in `0.0..=1.0`, chroma (about `0.0..=0.4`) and
+    /// hue in degrees. Out of gamut colors are clipped to sRGB.
+    pub fn oklch(l: f32, c: f32, h: f32) -> Self {
+        let (r, g, b) = space::oklch_to_rgb(l, c, h);
+        Self::from_rgba(r, g, b, 1.0)
+    }
+
+    /// The concrete color this resolves to under `scheme`. System and
+    /// semantic colors follow the platform palette for light and dark
+    /// appearance.
+    pub fn resolve(&self, scheme: ColorScheme) -> Rgba {
+        let dark = scheme == ColorScheme::Dark;
+        match self {
+            Color::Custom { r, g, b, a } => Rgba::new(*r, *g, *b, *a),
+            Color::Dynamic { light, dark: dark_rgba } => {
+                if dark {
+                    *dark_rgba
+                } else {
+                    *light
+                }
+            }
+            Color::WindowBackground if dark => Rgba::opaque(36, 36, 36),
+            Color::WindowBackground => Rgba::opaque(250, 250, 250),
+            Color::SecondaryText if dark => Rgba::new(0.92, 0.92, 0.96, 0.6),
+            Color::SecondaryText => Rgba::new(0.24, 0.24, 0.26, 0.6),
+            Color::Separator if dark => Rgba::new(0.33, 0.33, 0.35, 0.6),
+            Color::Separator => Rgba::new(0.24, 0.24, 0.26, 0.29),
+            Color::Accent => Color::SystemBlue.resolve(scheme),
+            Color::ControlFill if dark => Rgba::new(0.47, 0.47, 0.5, 0.36),
+            Color::ControlFill => Rgba::new(0.47, 0.47, 0.5, 0.2),
+            Color::Text if dark => Rgba::opaque(255, 255, 255),
+            Color::Text => Rgba::opaque(0, 0, 0),
+            Color::SystemRed if dark => Rgba::opaque(255, 69, 58),
+            Color::SystemRed => Rgba::opaque(255, 59, 48),
+            Color::SystemOrange if dark => Rgba::opaque(255, 159, 10),
+            Color::SystemOrange => Rgba::opaque(255, 149, 0),
+            Color::SystemYellow if dark => Rgba::opaque(255, 214, 10),
+            Color::SystemYellow => Rgba::opaque(255, 204, 0),
+            Color::SystemGreen if dark => Rgba::opaque(48, 209, 88),
+            Color::SystemGreen => Rgba::opaque(52, 199, 89),
+            Color::SystemBlue if dark => Rgba::opaque(10, 132, 255),
+            Color::SystemBlue => Rgba::opaque(0, 122, 255),
+            Color::SystemIndigo if dark => Rgba::opaque(94, 92, 230),
+            Color::SystemIndigo => Rgba::opaque(88, 86, 214),
+            Color::SystemPurple if dark => Rgba::opaque(191, 90, 242),
+            Color::SystemPurple => Rgba::opaque(175, 82, 222),
+            Color::SystemPink if dark => Rgba::opaque(255, 55, 95),
+            Color::SystemPink => Rgba::opaque(255, 45, 85),
+        }
+    }
+
+    /// Whether this color looks different under light and dark appearance.
+    pub fn is_dynamic(&self) -> bool {
+        self.resolve(ColorScheme::Light) != self.resolve(ColorScheme::Dark)
+    }
+
+    /// Apply `f` to the concrete value of this color under each appearance.
+    /// The result stays dynamic when the two differ.
+    fn map_rgba(&self, f: impl Fn(Rgba, ColorScheme) -> Rgba) -> Self {
+        let light = f(self.resolve(ColorScheme::Light), ColorScheme::Light);
+        let dark = f(self.resolve(ColorScheme::Dark), ColorScheme::Dark);
+        if light == dark {
+            light.into()
+        } else {
+            Self::Dynamic { light, dark }
+        }
+    }
+
+    /// Blend towards `other`; `amount` of `0.0` is `self`, `1.0` is `other`.
+    pub fn mix(&self, other: &Color, amount: f32) -> Self {
+        self.map_rgba(|rgba, scheme| rgba.lerp(&other.resolve(scheme), amount.clamp(0.0, 1.0)))
+    }
+
+    /// Raise HSL lightness by `amount` (`0.0..=1.0`).
+    pub fn lighten(&self, amount: f32) -> Self {
+        self.map_rgba(|rgba, _| {
+            let (h, s, l) = rgba.to_hsl();
+            let (r, g, b) = space::hsl_to_rgb(h, s, l + amount);
+            Rgba::new(r, g, b, rgba.a)
+        })
+    }
+
+    /// Lower HSL lightness by `amount` (`0.0..=1.0`).
+    pub fn darken(&self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Replace the alpha channel.
+    pub fn with_opacity(&self, opacity: f32) -> Self {
+        self.map_rgba(|rgba, _| Rgba {
+            a: opacity.clamp(0.0, 1.0),
+            ..rgba
+        })
+    }
+
+    /// Multiply the alpha channel by `factor`.
+    pub fn fade(&self, factor: f32) -> Self {
+        self.map_rgba(|rgba, _| Rgba {
+            a: (rgba.a * factor).clamp(0.0, 1.0),
+            ..rgba
+        })
+    }
+
+    pub fn new_with_alpha(r: u8, g: u8, b: u8, a: f32) -> Self {
+        Self::Custom {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a,
+        }
+    }
+}
+
+#[test]
+fn test_hex_parsing() {
+    assert_eq!(Color::from_hex("#ff4356"), Ok(Color::new(255, 67, 86)));
+    assert_eq!(Color::from_hex("0f0"), Ok(Color::new(0, 255, 0)));
+    assert_eq!(
+        Color::from_hex("#00000080").unwrap().resolve(ColorScheme::Light).to_u8(),
+        [0, 0, 0, 128]
+    );
+    assert!(Color::from_hex("#ff43").is_ok());
+    assert!(Color::from_hex("#ff435").is_err());
+    assert!(Color::from_hex("#gg4356").is_err());
+}
+
+#[test]
+fn test_css_parsing() {
+    let hex = |css: &str| Color::parse(css).unwrap().resolve(ColorScheme::Light).to_hex();
+
+    assert_eq!(hex("rgb(255, 67, 86)"), "#ff4356");
+    assert_eq!(hex("rgb(255 67 86 / 50%)"), "#ff435680");
+    assert_eq!(hex("rgba(100%, 0%, 0%, 0.5)"), "#ff000080");
+    assert_eq!(hex("hsl(120, 100%, 50%)"), "#00ff00");
+    assert_eq!(hex("hsl(0.5turn 100% 50%)"), "#00ffff");
+    assert_eq!(hex("RebeccaPurple"), "#663399");
+    assert_eq!(hex("transparent"), "#00000000");
+    assert_eq!(hex("oklch(62.8% 0.2577 29.23)"), "#ff0000");
+    assert_eq!(Color::parse("currentColor"), Ok(Color::Text));
+    assert!("not-a-color".parse::<Color>().is_err());
+    assert!(Color::parse("rgb(1, 2)").is_err());
+}
+
+#[test]
+fn test_color_math() {
+    let rgba = |color: Color| color.resolve(ColorScheme::Light).to_hex();
+    let white = Color::new(255, 255, 255);
+    let black = Color::new(0, 0, 0);
+
+    assert_eq!(rgba(black.mix(&white, 0.5)), "#808080");
+    assert_eq!(rgba(Color::hsl(0.0, 1.0, 0.5).lighten(0.25)), "#ff8080");
+    assert_eq!(rgba(Color::hsl(0.0, 1.0, 0.5).darken(0.25)), "#800000");
+    assert_eq!(rgba(white.with_opacity(0.5)), "#ffffff80");
+    assert_eq!(rgba(Color::hsv(240.0, 1.0, 1.0)), "#0000ff");
+    assert_eq!(
+        Color::SystemRed.resolve(ColorScheme::Dark).to_hex(),
+        "#ff453a"
+    );
+
+    let (l, c, h) = Color::new(255, 67, 86).resolve(ColorScheme::Light).to_oklch();
+    assert_eq!(rgba(Color::oklch(l, c, h)), "#ff4356");
+}
+
+#[test]
+fn test_dynamic_colors() {
+    let surface = Color::dynamic(Color::new(255, 255, 255), Color::new(0, 0, 0));
+    assert_eq!(surface.resolve(ColorScheme::Light).to_hex(), "#ffffff");
+    assert_eq!(surface.resolve(ColorScheme::Dark).to_hex(), "#000000");
+    assert!(surface.is_dynamic());
+
+    // Color math keeps both appearances
+    let faded = surface.with_opacity(0.5);
+    assert_eq!(faded.resolve(ColorScheme::Light).to_hex(), "#ffffff80");
+    assert_eq!(faded.resolve(ColorScheme::Dark).to_hex(), "#00000080");
+
+    assert_eq!(
+        Color::Accent.resolve(ColorScheme::Dark),
+        Color::SystemBlue.resolve(ColorScheme::Dark)
+    );
+    assert!(!Color::new(1, 2, 3).is_dynamic());
+}
+
+#[test]
+fn test_headless_color_scheme() {
+    use crate::{
+        elements::{Modifiers, Text},
+        pal::headless::Context,
+    };
+
+    let text = Text::new("Hello", Modifiers::default());
+    let mut window = Context::new(ColorScheme::Dark).render(Box::new(text), 200.0, 100.0);
+
+    assert_eq!(
+        window.resolve_color(&Color::WindowBackground),
+        Color::WindowBackground.resolve(ColorScheme::Dark)
+    );
+    window.set_color_scheme(ColorScheme::Light);
+    assert_eq!(
+        window.resolve_color(&Color::Text),
+        Rgba::new(0.0, 0.0, 0.0, 1.0)
+    );
+
+    let layout = window.layout(&window.root).unwrap();
+    assert!(layout.size.width > 0.0 && layout.size.height > 0.0);
+}
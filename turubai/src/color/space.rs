@@ -0,0 +1,126 @@
+//! Conversions between sRGB and the other color spaces `Color` can be built from.
+//! All channels are in `0.0..=1.0` except hues, which are in degrees.
+
+pub(crate) fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    hue_to_rgb(h, c, l - c / 2.0)
+}
+
+pub(crate) fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    let s = s.clamp(0.0, 1.0);
+    let v = v.clamp(0.0, 1.0);
+    let c = v * s;
+    hue_to_rgb(h, c, v - c)
+}
+
+/// Shared tail of the HSL and HSV conversions: `chroma` spread over the hue
+/// sextant, offset by `m`.
+fn hue_to_rgb(h: f32, chroma: f32, m: f32) -> (f32, f32, f32) {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    (r + m, g + m, b + m)
+}
+
+/// Returns (hue, chroma, max, min) of an RGB triple.
+fn rgb_hue(r: f32, g: f32, b: f32) -> (f32, f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+
+    let hue = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    (hue, chroma, max, min)
+}
+
+pub(crate) fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let (h, chroma, max, min) = rgb_hue(r, g, b);
+    let l = (max + min) / 2.0;
+    let s = if l == 0.0 || l == 1.0 {
+        0.0
+    } else {
+        chroma / (1.0 - (2.0 * l - 1.0).abs())
+    };
+    (h, s, l)
+}
+
+pub(crate) fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let (h, chroma, max, _) = rgb_hue(r, g, b);
+    let s = if max == 0.0 { 0.0 } else { chroma / max };
+    (h, s, max)
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// OKLCH to sRGB. `l` is perceived lightness in `0.0..=1.0`, `c` is chroma
+/// (roughly `0.0..=0.4`). Colors outside the sRGB gamut are clipped.
+pub(crate) fn oklch_to_rgb(l: f32, c: f32, h: f32) -> (f32, f32, f32) {
+    let (sin, cos) = h.to_radians().sin_cos();
+    let (a, b) = (c * cos, c * sin);
+
+    let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+    let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+    let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+    let (l3, m3, s3) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+    let r = 4.076_741_7 * l3 - 3.307_711_6 * m3 + 0.230_969_94 * s3;
+    let g = -1.268_438 * l3 + 2.609_757_4 * m3 - 0.341_319_38 * s3;
+    let b = -0.004_196_086_3 * l3 - 0.703_418_6 * m3 + 1.707_614_7 * s3;
+
+    (
+        linear_to_srgb(r).clamp(0.0, 1.0),
+        linear_to_srgb(g).clamp(0.0, 1.0),
+        linear_to_srgb(b).clamp(0.0, 1.0),
+    )
+}
+
+pub(crate) fn rgb_to_oklch(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+    let l = 0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b;
+    let m = 0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b;
+    let s = 0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b;
+    let (l_, m_, s_) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+    let lightness = 0.210_454_26 * l_ + 0.793_617_8 * m_ - 0.004_072_047 * s_;
+    let a = 1.977_998_5 * l_ - 2.428_592_2 * m_ + 0.450_593_7 * s_;
+    let b = 0.025_904_037 * l_ + 0.782_771_77 * m_ - 0.808_675_77 * s_;
+
+    let chroma = (a * a + b * b).sqrt();
+    let hue = if chroma < 1e-4 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+    (lightness, chroma, hue)
+}
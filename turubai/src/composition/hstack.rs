@@ -2,6 +2,7 @@ use std::sync::Arc;
 use std::sync::Mutex;
 
 use crate::elements::{Element, Modifiers};
+use crate::environment::Environment;
 use crate::shadow::ShadowDescriptor;
 use crate::Unit;
 
@@ -43,10 +44,15 @@ impl Element for HStack {
         "HStack"
     }
 
-    fn shadow_descriptor(&self) -> ShadowDescriptor {
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
         let inner = self.inner.lock().unwrap();
         let mods = inner.modifiers.lock().unwrap();
-        ShadowDescriptor::hstack(mods.h_stack.spacing, mods.h_stack.alignment)
+        let spacing = match mods.h_stack.spacing {
+            Unit::Auto => env.theme.spacing.stack,
+            spacing => spacing,
+        };
+        let spacing = env.resolve_unit(spacing);
+        ShadowDescriptor::hstack(spacing, mods.h_stack.alignment)
     }
 
     fn child_count(&self) -> usize {
@@ -59,10 +65,15 @@ impl Element for HStack {
             f(child.as_ref());
         }
     }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct HStackModifiers {
+    /// [`Unit::Auto`] uses the theme's stack spacing
     pub spacing: Unit,
     pub alignment: super::VerticalAlignment,
 }
@@ -70,7 +81,7 @@ pub struct HStackModifiers {
 impl Default for HStackModifiers {
     fn default() -> Self {
         Self {
-            spacing: Unit::Pixels(0.0),
+            spacing: Unit::Auto,
             alignment: super::VerticalAlignment::default(),
         }
     }
@@ -7,6 +7,7 @@ pub use spacer::*;
 pub use vstack::*;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HorizontalAlignment {
     #[default]
     Leading,
@@ -15,6 +16,7 @@ pub enum HorizontalAlignment {
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerticalAlignment {
     #[default]
     Top,
@@ -1,5 +1,6 @@
 use crate::{
     elements::{Element, Modifiers},
+    environment::Environment,
     shadow::{ShadowDescriptor, ShadowNode},
 };
 
@@ -42,7 +43,11 @@ impl Element for Spacer {
     }
     fn for_each_child(&self, _f: &mut dyn FnMut(&dyn Element)) {}
 
-    fn shadow_descriptor(&self) -> crate::shadow::ShadowDescriptor {
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.modifiers.clone())
+    }
+
+    fn shadow_descriptor(&self, _env: &Environment) -> crate::shadow::ShadowDescriptor {
         ShadowDescriptor::spacer()
     }
 }
@@ -4,6 +4,7 @@ use std::sync::Mutex;
 use crate::composition::spacer;
 use crate::composition::HorizontalAlignment;
 use crate::elements::{Element, Modifiers};
+use crate::environment::Environment;
 use crate::shadow::ShadowDescriptor;
 use crate::Unit;
 
@@ -45,10 +46,15 @@ impl Element for VStack {
         "VStack"
     }
 
-    fn shadow_descriptor(&self) -> ShadowDescriptor {
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
         let inner = self.inner.lock().unwrap();
         let mods = inner.modifiers.lock().unwrap();
-        ShadowDescriptor::vstack(mods.v_stack.spacing, mods.v_stack.alignment)
+        let spacing = match mods.v_stack.spacing {
+            Unit::Auto => env.theme.spacing.stack,
+            spacing => spacing,
+        };
+        let spacing = env.resolve_unit(spacing);
+        ShadowDescriptor::vstack(spacing, mods.v_stack.alignment)
     }
 
     fn child_count(&self) -> usize {
@@ -61,9 +67,14 @@ impl Element for VStack {
             f(child.as_ref());
         }
     }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
 }
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VStackModifiers {
+    /// [`Unit::Auto`] uses the theme's stack spacing
     pub spacing: Unit,
     pub alignment: super::HorizontalAlignment,
 }
@@ -72,7 +83,7 @@ impl Default for VStackModifiers {
     fn default() -> Self {
         Self {
             alignment: HorizontalAlignment::default(),
-            spacing: Unit::Pixels(0.0),
+            spacing: Unit::Auto,
         }
     }
 }
@@ -0,0 +1,54 @@
+use crate::canvas::{DrawContext, Drawing};
+use crate::elements::{Element, Modifiers};
+use crate::environment::Environment;
+use crate::shadow::ShadowDescriptor;
+
+/// Draws with a closure instead of elements. It grows like a shape and the
+/// closure is run whenever the canvas is painted, with a
+/// [`DrawContext`] of its laid out size.
+///
+/// Move clones of the states it reads into the closure; the canvas is
+/// painted again when they change.
+pub struct Canvas {
+    draw: Drawing,
+    modifiers: Modifiers,
+}
+
+impl Canvas {
+    pub fn new(
+        draw: impl Fn(&mut DrawContext) + Send + Sync + 'static,
+        modifiers: Modifiers,
+    ) -> Self {
+        Self {
+            draw: Drawing::new(draw),
+            modifiers,
+        }
+    }
+
+    /// Canvases can't contain anything, so `_children` is never called.
+    pub fn turubai_new_with_1_args(
+        draw: impl Fn(&mut DrawContext) + Send + Sync + 'static,
+        modifiers: Modifiers,
+        _children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        Self::new(draw, modifiers)
+    }
+}
+
+impl Element for Canvas {
+    fn name(&self) -> &'static str {
+        "canvas"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Canvas"
+    }
+
+    fn shadow_descriptor(&self, _env: &Environment) -> ShadowDescriptor {
+        ShadowDescriptor::canvas(self.draw.clone())
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.modifiers.clone())
+    }
+}
@@ -0,0 +1,209 @@
+use std::sync::{Arc, Mutex};
+
+use crate::elements::{Element, Modifiers};
+use crate::environment::Environment;
+use crate::image::{Bitmap, ImageSource};
+use crate::shadow::ShadowDescriptor;
+
+/// A picture from a file, embedded bytes or a pixel buffer.
+///
+/// It takes up its natural size unless it is `resizable`, in which case it
+/// grows like a spacer and draws the picture into whatever space it gets,
+/// as its `aspect_ratio` says.
+pub struct Image {
+    source: ImageSource,
+    modifiers: Modifiers,
+    /// The last decode and the scale factor it was made for
+    decoded: Mutex<Option<(f64, Option<Arc<Bitmap>>)>>,
+}
+
+impl Image {
+    pub fn new(source: impl Into<ImageSource>, modifiers: Modifiers) -> Self {
+        Self {
+            source: source.into(),
+            modifiers,
+            decoded: Mutex::new(None),
+        }
+    }
+
+    /// Images can't contain anything, so `_children` is never called.
+    pub fn turubai_new_with_1_args(
+        source: impl Into<ImageSource>,
+        modifiers: Modifiers,
+        _children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        Self::new(source, modifiers)
+    }
+
+    fn bitmap(&self, scale_factor: f64) -> Option<Arc<Bitmap>> {
+        let mut decoded = self.decoded.lock().unwrap();
+        if let Some((scale, bitmap)) = decoded.as_ref() {
+            if *scale == scale_factor {
+                return bitmap.clone();
+            }
+        }
+        let bitmap = match self.source.load(scale_factor) {
+            Ok(bitmap) => Some(bitmap),
+            Err(err) => {
+                eprintln!("[turubai] {err} ({})", self.source_name());
+                None
+            }
+        };
+        *decoded = Some((scale_factor, bitmap.clone()));
+        bitmap
+    }
+
+    fn source_name(&self) -> String {
+        match &self.source {
+            ImageSource::Path(path) => path.display().to_string(),
+            ImageSource::Bytes(data) => format!("{} bytes", data.len()),
+            ImageSource::Bitmap(bitmap) => format!("{}x{} bitmap", bitmap.width(), bitmap.height()),
+        }
+    }
+}
+
+impl Element for Image {
+    fn name(&self) -> &'static str {
+        "image"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Image"
+    }
+
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
+        let bitmap = self.bitmap(env.scale_factor());
+        let modifiers = self.modifiers.lock().unwrap();
+        ShadowDescriptor::image(bitmap, &modifiers.image)
+    }
+
+    fn child_count(&self) -> usize {
+        0
+    }
+
+    fn for_each_child(&self, _f: &mut dyn FnMut(&dyn Element)) {}
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.modifiers.clone())
+    }
+}
+
+/// How a resizable image fills the space it is given.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContentMode {
+    /// Stretch to the space, distorting the picture if the proportions differ
+    #[default]
+    Stretch,
+    /// Keep the proportions and fit the whole picture, leaving empty bands
+    Fit,
+    /// Keep the proportions and cover the whole space, cropping the picture
+    Fill,
+}
+
+impl ContentMode {
+    /// Where a picture of `size` is drawn in a space of `bounds`, as
+    /// `(x, y, width, height)` relative to the space. Fitted and filled
+    /// pictures are centered.
+    pub fn place(self, size: (f64, f64), bounds: (f64, f64)) -> (f64, f64, f64, f64) {
+        let (width, height) = size;
+        let (bounds_width, bounds_height) = bounds;
+        if width <= 0.0 || height <= 0.0 {
+            return (0.0, 0.0, 0.0, 0.0);
+        }
+        let scale = match self {
+            ContentMode::Stretch => return (0.0, 0.0, bounds_width, bounds_height),
+            ContentMode::Fit => (bounds_width / width).min(bounds_height / height),
+            ContentMode::Fill => (bounds_width / width).max(bounds_height / height),
+        };
+        let (width, height) = (width * scale, height * scale);
+        (
+            (bounds_width - width) / 2.0,
+            (bounds_height - height) / 2.0,
+            width,
+            height,
+        )
+    }
+}
+
+/// How pixels are sampled when an image is drawn at another size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    /// Blocky, for pixel art
+    Nearest,
+    /// Smooth
+    #[default]
+    Linear,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImageModifiers {
+    pub resizable: bool,
+    pub aspect_ratio: ContentMode,
+    pub interpolation: Interpolation,
+    /// A description of the picture for screen readers
+    pub alt: String,
+}
+
+#[test]
+fn test_images() {
+    use crate::{
+        color::ColorScheme,
+        composition::HStack,
+        elements::{ContentMode, Image, Modifiers},
+        image::ImageSource,
+        pal::headless::Context,
+    };
+
+    // 4x2, red on the left half and blue on the right
+    let pixels: Vec<u8> = (0..8)
+        .flat_map(|i| match i % 4 < 2 {
+            true => [0xff, 0, 0, 0xff],
+            false => [0, 0, 0xff, 0xff],
+        })
+        .collect();
+    let source = ImageSource::rgba(4, 2, pixels).unwrap();
+    assert_eq!(
+        source.load(1.0).unwrap().pixel(3, 1),
+        Some([0, 0, 0xff, 0xff])
+    );
+    assert!(ImageSource::rgba(4, 2, vec![0; 3]).is_err());
+
+    let modifiers = Modifiers::default();
+    let fixed = Image::new(source.clone(), modifiers.fork());
+    let resizable_modifiers = modifiers.fork();
+    {
+        let mut lock = resizable_modifiers.lock().unwrap();
+        lock.image.resizable = true;
+        lock.image.aspect_ratio = ContentMode::Fit;
+    }
+    let resizable = Image::new(source, resizable_modifiers);
+    let row = HStack::new(modifiers.clone(), vec![Box::new(fixed), Box::new(resizable)]);
+    let window = Context::new(ColorScheme::Light).render(Box::new(row), 400.0, 300.0);
+
+    // A fixed image takes up its natural size
+    let fixed = &window.root.children[0];
+    let layout = *window.layout(fixed).unwrap();
+    assert_eq!((layout.size.width, layout.size.height), (4.0, 2.0));
+
+    // A resizable one takes up the rest and fits the picture into it
+    let resizable = &window.root.children[1];
+    let layout = *window.layout(resizable).unwrap();
+    assert_eq!((layout.size.width, layout.size.height), (396.0, 300.0));
+    let bounds = window.image_bounds(resizable).unwrap();
+    assert_eq!((bounds.left, bounds.right), (4.0, 400.0));
+    assert_eq!((bounds.top, bounds.bottom), (51.0, 249.0));
+
+    // Filling covers the space and crops the picture
+    let (x, y, width, height) = ContentMode::Fill.place((4.0, 2.0), (396.0, 300.0));
+    assert_eq!((width, height), (600.0, 300.0));
+    assert_eq!((x, y), (-102.0, 0.0));
+
+    // Images that fail to load take up no space
+    let broken = Image::new("/nonexistent.png", modifiers.fork());
+    let window = Context::new(ColorScheme::Light).render(Box::new(broken), 400.0, 300.0);
+    let layout = *window.layout(&window.root).unwrap();
+    assert_eq!((layout.size.width, layout.size.height), (0.0, 0.0));
+    assert!(window.image_bounds(&window.root).is_none());
+}
@@ -1,16 +1,31 @@
+mod canvas;
+mod image;
+mod shape;
 mod text;
 
+pub use canvas::*;
+pub use image::*;
+pub use shape::*;
 pub use text::*;
 
 use std::sync::{Arc, Mutex, MutexGuard, LockResult};
-use crate::{composition::{HStackModifiers, VStackModifiers}, postprocessing::{FrameModifiers, PaddingModifiers}, runtime::WindowModifiers, shadow::ShadowDescriptor};
+use crate::{animation::TransitionModifiers, composition::{HStackModifiers, VStackModifiers}, environment::Environment, postprocessing::{BorderModifiers, CornerRadiiModifiers, EffectModifiers, FrameModifiers, PaddingModifiers, TransformModifiers}, runtime::WindowModifiers, shadow::ShadowDescriptor, stylesheet::SelectorModifiers};
 
 pub trait Element: Send + Sync {
     fn name(&self) -> &'static str;
     fn display_name(&self) -> &'static str;
 
     /// Returns the shadow descriptor for this element (used to build shadow tree)
-    fn shadow_descriptor(&self) -> ShadowDescriptor;
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor;
+
+    /// The environment this element and its descendants are built in, if it
+    /// differs from the one it was given (e.g. to install a theme).
+    fn environment(&self, _env: &Environment) -> Option<Environment> { None }
+
+    /// The modifiers this element reads while building its shadow node, if
+    /// any. Stylesheet rules are applied onto them and `.id()`/`.class()`
+    /// are recorded in them.
+    fn modifiers(&self) -> Option<Modifiers> { None }
 
     /// Build shadow tree recursively - returns descriptor with children already built
     fn build_shadow(&self, tree: &mut crate::shadow::ShadowTree) -> crate::shadow::ShadowNode
@@ -30,12 +45,21 @@ pub trait Element: Send + Sync {
 #[derive(Default, Clone, PartialEq)]
 pub struct ModifiersInner {
     pub text: TextModifiers,
+    pub image: ImageModifiers,
+    pub shape: ShapeModifiers,
     pub v_stack: VStackModifiers,
     pub h_stack: HStackModifiers,
     pub window_template: WindowModifiers,
 
     pub frame: FrameModifiers,
     pub padding: PaddingModifiers,
+    pub border: BorderModifiers,
+    pub corner_radii: CornerRadiiModifiers,
+    pub effects: EffectModifiers,
+    pub transform: TransformModifiers,
+    pub transition: TransitionModifiers,
+
+    pub selector: SelectorModifiers,
 }
 
 #[derive(Default, Clone)]
@@ -44,12 +68,49 @@ pub struct Modifiers {
 }
 
 impl Modifiers {
+    /// A copy for a child element. Everything is inherited except the id,
+    /// classes, effects, transform and transitions, which belong to the
+    /// element they were assigned to.
     pub fn fork(&self) -> Self {
-        let inner = self.inner.lock().unwrap();
-        Modifiers { inner: Arc::new(Mutex::new(inner.clone())) }
+        let mut inner = self.inner.lock().unwrap().clone();
+        inner.selector = SelectorModifiers::default();
+        inner.effects = EffectModifiers::default();
+        inner.transform = TransformModifiers::default();
+        inner.transition = TransitionModifiers::default();
+        Modifiers { inner: Arc::new(Mutex::new(inner)) }
     }
 
     pub fn lock(&self) -> LockResult<MutexGuard<'_, ModifiersInner>> {
         self.inner.lock()
     }
 }
+
+impl Element for Box<dyn Element> {
+    fn name(&self) -> &'static str {
+        self.as_ref().name()
+    }
+
+    fn display_name(&self) -> &'static str {
+        self.as_ref().display_name()
+    }
+
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
+        self.as_ref().shadow_descriptor(env)
+    }
+
+    fn child_count(&self) -> usize {
+        self.as_ref().child_count()
+    }
+
+    fn for_each_child(&self, f: &mut dyn FnMut(&dyn Element)) {
+        self.as_ref().for_each_child(f)
+    }
+
+    fn environment(&self, env: &Environment) -> Option<Environment> {
+        self.as_ref().environment(env)
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        self.as_ref().modifiers()
+    }
+}
@@ -0,0 +1,179 @@
+use crate::color::Color;
+use crate::elements::{Element, Modifiers};
+use crate::environment::Environment;
+use crate::fill::Fill;
+use crate::shadow::ShadowDescriptor;
+use crate::shape::{self, Shape, Stroke};
+
+/// What every shape element is made of.
+struct ShapeElement {
+    shape: Shape,
+    modifiers: Modifiers,
+}
+
+impl ShapeElement {
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
+        let modifiers = self.modifiers.lock().unwrap();
+        let themed = |fill: &Fill| fill.map_colors(|color| env.themed_color(color));
+        let stroke = modifiers.shape.stroke.as_ref().map(|stroke| Stroke {
+            paint: themed(&stroke.paint),
+            ..stroke.clone()
+        });
+        let fill = match (&modifiers.shape.fill, &stroke) {
+            (Some(fill), _) => Some(themed(fill)),
+            (None, Some(_)) => None,
+            (None, None) => Some(Fill::Color(env.themed_color(&Color::Text))),
+        };
+        ShadowDescriptor::shape(self.shape.clone(), fill, stroke)
+    }
+}
+
+/// Define a shape element that fills its frame, like a spacer. Shapes are
+/// styled with `.fill()` and `.stroke()`; without either they are filled
+/// with the text color. They can't contain anything, so the children of
+/// their markup are never built.
+macro_rules! shape_element {
+    ($(#[$doc:meta])* $element:ident, $name:literal) => {
+        $(#[$doc])*
+        pub struct $element(ShapeElement);
+
+        impl $element {
+            pub fn shape(&self) -> &Shape {
+                &self.0.shape
+            }
+        }
+
+        impl Element for $element {
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn display_name(&self) -> &'static str {
+                stringify!($element)
+            }
+
+            fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
+                self.0.shadow_descriptor(env)
+            }
+
+            fn modifiers(&self) -> Option<Modifiers> {
+                Some(self.0.modifiers.clone())
+            }
+        }
+    };
+}
+
+shape_element!(Rectangle, "rectangle");
+
+impl Rectangle {
+    pub fn new(modifiers: Modifiers) -> Self {
+        Self(ShapeElement {
+            shape: Shape::Rectangle,
+            modifiers,
+        })
+    }
+
+    pub fn turubai_new_with_0_args(
+        modifiers: Modifiers,
+        _children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        Self::new(modifiers)
+    }
+}
+
+shape_element!(
+    /// A rectangle with corners rounded by `radius` logical pixels
+    RoundedRectangle,
+    "rounded_rectangle"
+);
+
+impl RoundedRectangle {
+    pub fn new(radius: f64, modifiers: Modifiers) -> Self {
+        Self(ShapeElement {
+            shape: Shape::RoundedRectangle { radius },
+            modifiers,
+        })
+    }
+
+    pub fn turubai_new_with_1_args(
+        radius: f64,
+        modifiers: Modifiers,
+        _children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        Self::new(radius, modifiers)
+    }
+}
+
+shape_element!(
+    /// The largest circle that fits the frame, centered
+    Circle,
+    "circle"
+);
+
+impl Circle {
+    pub fn new(modifiers: Modifiers) -> Self {
+        Self(ShapeElement {
+            shape: Shape::Circle,
+            modifiers,
+        })
+    }
+
+    pub fn turubai_new_with_0_args(
+        modifiers: Modifiers,
+        _children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        Self::new(modifiers)
+    }
+}
+
+shape_element!(
+    /// A rectangle whose shorter sides are half circles
+    Capsule,
+    "capsule"
+);
+
+impl Capsule {
+    pub fn new(modifiers: Modifiers) -> Self {
+        Self(ShapeElement {
+            shape: Shape::Capsule,
+            modifiers,
+        })
+    }
+
+    pub fn turubai_new_with_0_args(
+        modifiers: Modifiers,
+        _children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        Self::new(modifiers)
+    }
+}
+
+shape_element!(
+    /// Draws a [`shape::Path`] in its own coordinates, e.g.
+    /// `Path(shape::Path::new().move_to(..)..)`
+    Path,
+    "path"
+);
+
+impl Path {
+    pub fn new(path: shape::Path, modifiers: Modifiers) -> Self {
+        Self(ShapeElement {
+            shape: Shape::Path(path),
+            modifiers,
+        })
+    }
+
+    pub fn turubai_new_with_1_args(
+        path: shape::Path,
+        modifiers: Modifiers,
+        _children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        Self::new(path, modifiers)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ShapeModifiers {
+    pub fill: Option<Fill>,
+    pub stroke: Option<Stroke>,
+}
@@ -3,8 +3,8 @@ use std::sync::Mutex;
 
 use crate::color::Color;
 use crate::elements::{Element, Modifiers};
-use crate::font::Font;
-use crate::font::FontWeight;
+use crate::environment::Environment;
+use crate::font::{Font, TextStyle};
 use crate::shadow::ShadowDescriptor;
 
 pub struct Text {
@@ -58,16 +58,16 @@ impl Element for Text {
         "Text"
     }
 
-    fn shadow_descriptor(&self) -> ShadowDescriptor {
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
         let inner = self.inner.lock().unwrap();
         let mods_inner = inner.modifiers.lock().unwrap();
         let text_mods = &mods_inner.text;
 
         ShadowDescriptor::text(
             inner.contents.clone(),
-            text_mods.font.clone(),
-            text_mods.color.clone(),
-            text_mods.decoration.clone(),
+            env.resolve_font(text_mods.style, text_mods.font.as_ref()),
+            env.themed_color(&text_mods.color),
+            text_mods.decoration.themed(env),
         )
     }
 
@@ -81,6 +81,10 @@ impl Element for Text {
             f(child.as_ref());
         }
     }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
 }
 
 #[derive(Default, PartialEq, Eq, Clone, Copy)]
@@ -93,7 +97,10 @@ pub enum TextAlign {
 
 #[derive(Clone, PartialEq)]
 pub struct TextModifiers {
-    pub font: Font,
+    /// `None` for the theme's body font
+    pub font: Option<Font>,
+    /// Semantic role; anything but [`TextStyle::Custom`] takes precedence over `font`
+    pub style: TextStyle,
     pub color: Color,
     pub decoration: TextDecoration,
 }
@@ -102,19 +109,35 @@ impl Default for TextModifiers {
     fn default() -> Self {
         Self {
             color: Color::Text,
-            font: Font::default(),
+            font: None,
+            style: TextStyle::default(),
             decoration: TextDecoration::default(),
         }
     }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextDecoration {
     pub underline: TextDecorationLine,
     pub strike_through: TextDecorationLine,
 }
 
+impl TextDecoration {
+    fn themed(&self, env: &Environment) -> Self {
+        let line = |line: &TextDecorationLine| TextDecorationLine {
+            style: line.style.clone(),
+            color: env.themed_color(&line.color),
+        };
+        Self {
+            underline: line(&self.underline),
+            strike_through: line(&self.strike_through),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextDecorationLine {
     pub style: TextLineStyle,
     pub color: Color,
@@ -130,6 +153,7 @@ impl Default for TextDecorationLine {
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextLineStyle {
     #[default]
     None,
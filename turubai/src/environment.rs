@@ -0,0 +1,146 @@
+use crate::{
+    color::{Color, ColorScheme, Rgba},
+    font::{Font, TextStyle},
+    stylesheet::Stylesheet,
+    theme::Theme,
+    Unit,
+};
+
+/// Values provided by the running backend that elements can read while
+/// the shadow tree is being built.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Environment {
+    /// Number of device pixels per logical pixel on the display the window
+    /// is shown on. Layout always happens in logical pixels.
+    pub scale_factor: f64,
+    /// Light or dark appearance; decides what system, semantic and dynamic
+    /// colors resolve to.
+    pub color_scheme: ColorScheme,
+    /// Design tokens: palette, typography, spacing and corner radii.
+    pub theme: Theme,
+    /// Rules applied onto element modifiers while the tree is built.
+    pub stylesheet: Stylesheet,
+    /// Multiplier applied to every font size, for users who need larger
+    /// (or smaller) text.
+    pub text_scale: f64,
+    /// Draw [`ShadowTree::debug_overlay`](crate::shadow::ShadowTree::debug_overlay)
+    /// over the window.
+    pub debug_layout: bool,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            scale_factor: 1.0,
+            color_scheme: ColorScheme::default(),
+            theme: Theme::default(),
+            stylesheet: Stylesheet::default(),
+            text_scale: 1.0,
+            debug_layout: false,
+        }
+    }
+}
+
+impl Environment {
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    pub fn with_color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.color_scheme = color_scheme;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn with_stylesheet(mut self, stylesheet: Stylesheet) -> Self {
+        self.stylesheet = stylesheet;
+        self
+    }
+
+    pub fn stylesheet(&self) -> &Stylesheet {
+        &self.stylesheet
+    }
+
+    pub fn with_text_scale(mut self, text_scale: f64) -> Self {
+        self.text_scale = text_scale;
+        self
+    }
+
+    pub fn text_scale(&self) -> f64 {
+        self.text_scale
+    }
+
+    pub fn with_debug_layout(mut self, debug_layout: bool) -> Self {
+        self.debug_layout = debug_layout;
+        self
+    }
+
+    pub fn debug_layout(&self) -> bool {
+        self.debug_layout
+    }
+
+    /// The font text with `style` is set in, with the text scale applied.
+    /// `font` is used for [`TextStyle::Custom`]; without one, text is set
+    /// in the theme's body font.
+    pub fn resolve_font(&self, style: TextStyle, font: Option<&Font>) -> Font {
+        let typography = &self.theme.typography;
+        typography
+            .font(style)
+            .or(font)
+            .unwrap_or(&typography.body)
+            .scaled(self.text_scale)
+    }
+
+    /// Replace a [`Unit::Space`] with the theme's spacing step.
+    pub fn resolve_unit(&self, unit: Unit) -> Unit {
+        self.theme.spacing.resolve(unit)
+    }
+
+    /// Substitute the theme's palette for semantic colors.
+    pub fn themed_color(&self, color: &Color) -> Color {
+        self.theme.palette.apply(color)
+    }
+
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.color_scheme
+    }
+
+    /// Resolve `color` through the theme's palette under the current color scheme.
+    pub fn resolve_color(&self, color: &Color) -> Rgba {
+        self.themed_color(color).resolve(self.color_scheme)
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// The asset variant that best matches the display, e.g. `2` for `@2x` images.
+    pub fn asset_scale(&self) -> u32 {
+        (self.scale_factor.ceil() as u32).clamp(1, 3)
+    }
+
+    /// Convert a length in logical pixels to device pixels.
+    pub fn to_physical(&self, logical: f64) -> f64 {
+        logical * self.scale_factor
+    }
+
+    /// Convert a length in device pixels to logical pixels.
+    pub fn to_logical(&self, physical: f64) -> f64 {
+        physical / self.scale_factor
+    }
+
+    /// Round a logical coordinate to the nearest device pixel boundary.
+    pub fn snap(&self, logical: f32) -> f32 {
+        let scale = self.scale_factor as f32;
+        (logical * scale).round() / scale
+    }
+}
@@ -0,0 +1,214 @@
+use std::path::{Path, PathBuf};
+
+use crate::{color::Color, Unit};
+
+/// A point in a shape's own coordinate space where `(0, 0)` is the top
+/// leading corner and `(1, 1)` the bottom trailing one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnitPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl UnitPoint {
+    pub const TOP_LEADING: Self = Self::new(0.0, 0.0);
+    pub const TOP: Self = Self::new(0.5, 0.0);
+    pub const TOP_TRAILING: Self = Self::new(1.0, 0.0);
+    pub const LEADING: Self = Self::new(0.0, 0.5);
+    pub const CENTER: Self = Self::new(0.5, 0.5);
+    pub const TRAILING: Self = Self::new(1.0, 0.5);
+    pub const BOTTOM_LEADING: Self = Self::new(0.0, 1.0);
+    pub const BOTTOM: Self = Self::new(0.5, 1.0);
+    pub const BOTTOM_TRAILING: Self = Self::new(1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Default for UnitPoint {
+    fn default() -> Self {
+        Self::CENTER
+    }
+}
+
+/// A color at a position along a gradient, `0.0` being the start and `1.0` the end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorStop {
+    pub color: Color,
+    pub offset: f32,
+}
+
+impl ColorStop {
+    pub fn new(color: Color, offset: f32) -> Self {
+        Self { color, offset }
+    }
+
+    /// Spread `colors` evenly from start to end.
+    pub fn evenly(colors: &[Color]) -> Vec<ColorStop> {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| ColorStop::new(*color, i as f32 / last))
+            .collect()
+    }
+}
+
+/// How an image fill covers its area.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageTiling {
+    /// Scale the image to cover the whole area
+    #[default]
+    Stretch,
+    /// Repeat the image at its natural size
+    Tile,
+}
+
+/// What the inside of a background or shape is painted with.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fill {
+    Color(Color),
+    /// A gradient along a line through the center. `angle` is in degrees
+    /// and follows CSS: `0` runs bottom to top, `90` leading to trailing.
+    LinearGradient { angle: f32, stops: Vec<ColorStop> },
+    /// A circular gradient around `center`. A `radius` of [`Unit::Auto`]
+    /// reaches the farthest corner.
+    RadialGradient {
+        center: UnitPoint,
+        radius: Unit,
+        stops: Vec<ColorStop>,
+    },
+    /// A gradient sweeping clockwise around `center`, starting at `angle`
+    /// degrees from the top.
+    ConicGradient {
+        center: UnitPoint,
+        angle: f32,
+        stops: Vec<ColorStop>,
+    },
+    Image { path: PathBuf, tiling: ImageTiling },
+}
+
+impl Fill {
+    pub fn linear_gradient(angle: f32, stops: Vec<ColorStop>) -> Self {
+        Self::LinearGradient { angle, stops }
+    }
+
+    pub fn radial_gradient(center: UnitPoint, radius: Unit, stops: Vec<ColorStop>) -> Self {
+        Self::RadialGradient {
+            center,
+            radius,
+            stops,
+        }
+    }
+
+    pub fn conic_gradient(center: UnitPoint, angle: f32, stops: Vec<ColorStop>) -> Self {
+        Self::ConicGradient {
+            center,
+            angle,
+            stops,
+        }
+    }
+
+    pub fn image(path: impl AsRef<Path>) -> Self {
+        Self::Image {
+            path: path.as_ref().to_path_buf(),
+            tiling: ImageTiling::default(),
+        }
+    }
+
+    pub fn tiled_image(path: impl AsRef<Path>) -> Self {
+        Self::Image {
+            path: path.as_ref().to_path_buf(),
+            tiling: ImageTiling::Tile,
+        }
+    }
+
+    /// The same fill with every color passed through `f`.
+    pub fn map_colors(&self, f: impl Fn(&Color) -> Color) -> Fill {
+        let stops = |stops: &[ColorStop]| {
+            stops
+                .iter()
+                .map(|stop| ColorStop::new(f(&stop.color), stop.offset))
+                .collect()
+        };
+        match self {
+            Fill::Color(color) => Fill::Color(f(color)),
+            Fill::LinearGradient { angle, stops: s } => Fill::LinearGradient {
+                angle: *angle,
+                stops: stops(s),
+            },
+            Fill::RadialGradient {
+                center,
+                radius,
+                stops: s,
+            } => Fill::RadialGradient {
+                center: *center,
+                radius: *radius,
+                stops: stops(s),
+            },
+            Fill::ConicGradient {
+                center,
+                angle,
+                stops: s,
+            } => Fill::ConicGradient {
+                center: *center,
+                angle: *angle,
+                stops: stops(s),
+            },
+            Fill::Image { .. } => self.clone(),
+        }
+    }
+
+    /// The gradient's color stops, if this is a gradient.
+    pub fn stops(&self) -> Option<&[ColorStop]> {
+        match self {
+            Fill::LinearGradient { stops, .. }
+            | Fill::RadialGradient { stops, .. }
+            | Fill::ConicGradient { stops, .. } => Some(stops),
+            Fill::Color(_) | Fill::Image { .. } => None,
+        }
+    }
+}
+
+/// The start and end of a CSS linear gradient at `angle` degrees over a
+/// `width` x `height` area. The line runs through the center and is long
+/// enough for the first and last stops to touch opposite corners.
+pub fn linear_gradient_line(angle: f32, width: f64, height: f64) -> ((f64, f64), (f64, f64)) {
+    let (sin, cos) = (angle as f64).to_radians().sin_cos();
+    let half = (width * sin.abs() + height * cos.abs()) / 2.0;
+    let (cx, cy) = (width / 2.0, height / 2.0);
+    // 0 degrees points up, y points down
+    let (dx, dy) = (sin * half, -cos * half);
+    ((cx - dx, cy - dy), (cx + dx, cy + dy))
+}
+
+/// The radius of a radial gradient around `center` over a `width` x
+/// `height` area. [`Unit::Auto`] reaches the farthest corner and
+/// percentages are of the longer side.
+pub fn radial_gradient_radius(center: UnitPoint, radius: Unit, width: f64, height: f64) -> f64 {
+    match radius {
+        Unit::Auto => {
+            let dx = (center.x as f64).max(1.0 - center.x as f64) * width;
+            let dy = (center.y as f64).max(1.0 - center.y as f64) * height;
+            dx.hypot(dy)
+        }
+        radius => radius.to_pixels(Some(width.max(height))),
+    }
+}
+
+impl Default for Fill {
+    fn default() -> Self {
+        Self::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.0))
+    }
+}
+
+impl From<Color> for Fill {
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
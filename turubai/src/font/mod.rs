@@ -0,0 +1,312 @@
+use std::{fmt, path::Path, sync::Arc};
+
+pub mod registry;
+mod typography;
+
+pub use typography::{TextStyle, Typography};
+
+#[derive(Default, Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontWeight {
+    ExtraBlack  = 950,
+    Black       = 900,
+    ExtraBold   = 800,
+    Bold        = 700,
+    SemiBold    = 600,
+    Medium      = 500,
+
+    #[default]
+    Regular      = 400,
+    SemiLight   = 350,
+    Light       = 300,
+    ExtraLight  = 200,
+    Thin        = 100,
+}
+
+/// A family the platform picks a concrete face for.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GenericFamily {
+    Serif,
+    SansSerif,
+    Monospace,
+    SystemUi,
+}
+
+impl GenericFamily {
+    /// The CSS keyword for this family.
+    pub fn css_name(&self) -> &'static str {
+        match self {
+            GenericFamily::Serif => "serif",
+            GenericFamily::SansSerif => "sans-serif",
+            GenericFamily::Monospace => "monospace",
+            GenericFamily::SystemUi => "system-ui",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "serif" => Some(GenericFamily::Serif),
+            "sans-serif" | "sans" => Some(GenericFamily::SansSerif),
+            "monospace" | "mono" => Some(GenericFamily::Monospace),
+            "system-ui" => Some(GenericFamily::SystemUi),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of a font's family list.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontFamily {
+    Named(String),
+    Generic(GenericFamily),
+}
+
+impl From<&str> for FontFamily {
+    fn from(name: &str) -> Self {
+        match GenericFamily::parse(name) {
+            Some(generic) => FontFamily::Generic(generic),
+            None => FontFamily::Named(name.trim().to_string()),
+        }
+    }
+}
+
+impl From<GenericFamily> for FontFamily {
+    fn from(generic: GenericFamily) -> Self {
+        FontFamily::Generic(generic)
+    }
+}
+
+impl fmt::Display for FontFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontFamily::Named(name) => f.write_str(name),
+            FontFamily::Generic(generic) => f.write_str(generic.css_name()),
+        }
+    }
+}
+
+/// Where a resolved face came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontSource {
+    /// Loaded through [`Font::from_file`] or [`Font::from_bytes`]
+    Registered,
+    /// Installed on the system and picked by the toolkit
+    System,
+    /// The measurer's own metrics, used when no face is available
+    Builtin,
+}
+
+/// The face a backend actually used for a [`Font`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedFont {
+    /// Family name of the face
+    pub family: String,
+    /// Index into [`Font::families`] of the entry that matched, `None` if
+    /// nothing in the list did and the backend fell back on its own
+    pub matched: Option<usize>,
+    pub source: FontSource,
+}
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    /// The data is not a font ttf-parser understands
+    Parse,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(err) => write!(f, "could not read font: {err}"),
+            FontError::Parse => f.write_str("could not parse font data"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FontError::Io(err) => Some(err),
+            FontError::Parse => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FontError {
+    fn from(err: std::io::Error) -> Self {
+        FontError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontInner {
+    families: Vec<FontFamily>,
+    size: u32,
+    weight: FontWeight,
+    italicized: bool,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Font {
+    pub(crate) inner: Arc<FontInner>
+}
+
+impl Font {
+    pub fn new(family: &str, size: u32, weight: FontWeight, italic: bool) -> Self {
+        Self::new_with_fallbacks(&[family], size, weight, italic)
+    }
+
+    /// A font that tries each family in turn, e.g.
+    /// `["Inter", "Helvetica", "sans-serif"]`.
+    pub fn new_with_fallbacks(families: &[&str], size: u32, weight: FontWeight, italic: bool) -> Self {
+        let inner = FontInner {
+            families: families.iter().map(|family| FontFamily::from(*family)).collect(),
+            size,
+            weight,
+            italicized: italic,
+        };
+
+        Self {
+            inner: Arc::new(inner)
+        }
+    }
+
+    /// Register the font file at `path` and return a font using its family.
+    pub fn from_file(path: impl AsRef<Path>, size: u32, weight: FontWeight, italic: bool) -> Result<Self, FontError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let family = registry::register(data, Some(path.to_path_buf()))?;
+        Ok(Self::new(&family, size, weight, italic))
+    }
+
+    /// Register an in-memory font file and return a font using its family.
+    pub fn from_bytes(data: impl Into<Vec<u8>>, size: u32, weight: FontWeight, italic: bool) -> Result<Self, FontError> {
+        let family = registry::register(data.into(), None)?;
+        Ok(Self::new(&family, size, weight, italic))
+    }
+
+    /// Append `family` to the end of the fallback list.
+    pub fn with_fallback(&self, family: impl Into<FontFamily>) -> Self {
+        let mut inner = (*self.inner).clone();
+        inner.families.push(family.into());
+        Self {
+            inner: Arc::new(inner)
+        }
+    }
+
+    /// The same font at `size` points.
+    pub fn with_size(&self, size: u32) -> Self {
+        let mut inner = (*self.inner).clone();
+        inner.size = size;
+        Self {
+            inner: Arc::new(inner)
+        }
+    }
+
+    /// The same font with its size multiplied by `factor`, rounded to whole points.
+    pub fn scaled(&self, factor: f64) -> Self {
+        if factor == 1.0 {
+            return self.clone();
+        }
+        self.with_size((self.inner.size as f64 * factor).round().max(1.0) as u32)
+    }
+
+    /// The first family in the list.
+    pub fn name(&self) -> String {
+        self.inner.families.first().map(|family| family.to_string()).unwrap_or_default()
+    }
+
+    pub fn families(&self) -> &[FontFamily] {
+        &self.inner.families
+    }
+
+    pub fn size(&self) -> f32 {
+        self.inner.size as _
+    }
+
+    pub fn weight(&self) -> FontWeight {
+        self.inner.weight
+    }
+
+    pub fn is_italic(&self) -> bool {
+        self.inner.italicized
+    }
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::new("Arial", 12, FontWeight::Regular, false)
+    }
+}
+
+#[test]
+fn test_font_fallbacks() {
+    let font = Font::new_with_fallbacks(&["Inter", "Helvetica", "sans"], 12, FontWeight::Regular, false);
+    assert_eq!(font.name(), "Inter");
+    assert_eq!(font.families()[2], FontFamily::Generic(GenericFamily::SansSerif));
+
+    let font = font.with_fallback(GenericFamily::Monospace);
+    assert_eq!(font.families().len(), 4);
+    assert_eq!(font.families()[3].to_string(), "monospace");
+}
+
+#[test]
+fn test_font_from_file() {
+    // A font with no glyphs, just a name
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fonts/TurubaiTest.ttf");
+
+    let registered = Font::from_file(FIXTURE, 12, FontWeight::Regular, false).unwrap();
+    assert_eq!(registered.name(), "Turubai Test");
+
+    let font = Font::new_with_fallbacks(&["Inter", "Turubai Test", "sans-serif"], 12, FontWeight::Regular, false);
+    let resolved = crate::pal::headless::resolve_font(&font);
+    assert_eq!(resolved.matched, Some(1));
+    assert_eq!(resolved.source, FontSource::Registered);
+
+    assert!(matches!(Font::from_bytes(vec![0u8; 16], 12, FontWeight::Regular, false), Err(FontError::Parse)));
+}
+
+#[test]
+fn test_text_styles() {
+    use crate::{
+        color::ColorScheme,
+        elements::{Modifiers, Text},
+        pal::headless::{Context, Window},
+        shadow::NodeKind,
+    };
+
+    let modifiers = Modifiers::default();
+    modifiers.lock().unwrap().text.style = TextStyle::Headline;
+
+    let font_size = |window: &Window| match &window.root.kind {
+        NodeKind::Text { font, .. } => font.size(),
+        _ => unreachable!(),
+    };
+    let text = || Box::new(Text::new("Hello", modifiers.clone()));
+    let normal = Context::new(ColorScheme::Light).render(text(), 400.0, 100.0);
+    let large = Context::new(ColorScheme::Light)
+        .with_text_scale(2.0)
+        .render(text(), 400.0, 100.0);
+
+    assert_eq!(font_size(&normal), 13.0);
+    assert_eq!(font_size(&large), 26.0);
+    assert!(
+        large.layout(&large.root).unwrap().size.height
+            > normal.layout(&normal.root).unwrap().size.height
+    );
+
+    // Text without a font is set in the body font, but a font that was
+    // chosen is kept even if it is the default one
+    let modifiers = Modifiers::default();
+    let text = Box::new(Text::new("Hello", modifiers.fork()));
+    let body = Context::new(ColorScheme::Light).render(text, 400.0, 100.0);
+    modifiers.lock().unwrap().text.font = Some(Font::default());
+    let text = Box::new(Text::new("Hello", modifiers));
+    let chosen = Context::new(ColorScheme::Light).render(text, 400.0, 100.0);
+    assert_eq!(font_size(&body), 13.0);
+    assert_eq!(font_size(&chosen), Font::default().size());
+}
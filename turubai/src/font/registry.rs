@@ -0,0 +1,114 @@
+//! Font faces registered by the application through [`Font::from_file`]
+//! and [`Font::from_bytes`].
+//!
+//! [`Font::from_file`]: super::Font::from_file
+//! [`Font::from_bytes`]: super::Font::from_bytes
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use super::{Font, FontError, FontFamily, FontSource, ResolvedFont};
+
+/// One face of a registered font file.
+#[derive(Debug)]
+pub struct RegisteredFace {
+    pub family: String,
+    pub weight: u16,
+    pub italic: bool,
+    /// Index of the face inside a font collection
+    pub index: u32,
+    pub data: Arc<Vec<u8>>,
+    /// The file the face was loaded from, if any
+    pub path: Option<PathBuf>,
+}
+
+static REGISTERED: LazyLock<Mutex<Vec<Arc<RegisteredFace>>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Prefer the typographic family (which groups every weight under one
+/// name) over the legacy family name.
+fn family_name(face: &ttf_parser::Face) -> Option<String> {
+    let find = |id: u16| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == id)
+            .find_map(|name| name.to_string())
+    };
+    find(ttf_parser::name_id::TYPOGRAPHIC_FAMILY).or_else(|| find(ttf_parser::name_id::FAMILY))
+}
+
+/// Register every face in `data` and return the family name of the first one.
+pub(crate) fn register(data: Vec<u8>, path: Option<PathBuf>) -> Result<String, FontError> {
+    let data = Arc::new(data);
+    let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+
+    let mut faces = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let face = ttf_parser::Face::parse(&data, index).map_err(|_| FontError::Parse)?;
+        let family = family_name(&face).ok_or(FontError::Parse)?;
+        faces.push(Arc::new(RegisteredFace {
+            family,
+            weight: face.weight().to_number(),
+            italic: face.is_italic(),
+            index,
+            data: data.clone(),
+            path: path.clone(),
+        }));
+    }
+
+    let family = faces.first().map(|face| face.family.clone()).ok_or(FontError::Parse)?;
+    REGISTERED.lock().unwrap().extend(faces);
+    Ok(family)
+}
+
+/// All registered faces, in registration order.
+pub fn registered_faces() -> Vec<Arc<RegisteredFace>> {
+    REGISTERED.lock().unwrap().clone()
+}
+
+/// The registered face of `family` closest in weight and style to `font`.
+pub(crate) fn find_face(family: &str, font: &Font) -> Option<Arc<RegisteredFace>> {
+    let faces = REGISTERED.lock().unwrap();
+    faces
+        .iter()
+        .filter(|face| face.family.eq_ignore_ascii_case(family))
+        .min_by_key(|face| {
+            let style_penalty = if face.italic == font.is_italic() { 0 } else { 1000 };
+            style_penalty + (face.weight as i32 - font.weight() as i32).abs()
+        })
+        .cloned()
+}
+
+/// Walk the font's family list and pick the first registered face. Generic
+/// families and names nobody registered are skipped; when nothing matches,
+/// the first generic family (or sans-serif) is reported as builtin.
+pub(crate) fn resolve(font: &Font) -> (ResolvedFont, Option<Arc<RegisteredFace>>) {
+    for (position, family) in font.families().iter().enumerate() {
+        if let FontFamily::Named(name) = family {
+            if let Some(face) = find_face(name, font) {
+                let resolved = ResolvedFont {
+                    family: face.family.clone(),
+                    matched: Some(position),
+                    source: FontSource::Registered,
+                };
+                return (resolved, Some(face));
+            }
+        }
+    }
+
+    let generic = font
+        .families()
+        .iter()
+        .position(|family| matches!(family, FontFamily::Generic(_)));
+    let family = generic
+        .map(|position| font.families()[position].to_string())
+        .unwrap_or_else(|| "sans-serif".to_string());
+    let resolved = ResolvedFont {
+        family,
+        matched: generic,
+        source: FontSource::Builtin,
+    };
+    (resolved, None)
+}
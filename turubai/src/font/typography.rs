@@ -0,0 +1,73 @@
+use super::{Font, FontWeight};
+
+/// The role a piece of text plays. Each role resolves to a concrete
+/// [`Font`] through the environment's [`Typography`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextStyle {
+    /// Use `text::font` as given, or the body font if it isn't set
+    #[default]
+    Custom,
+    LargeTitle,
+    Title,
+    Title2,
+    Headline,
+    Subheadline,
+    Body,
+    Callout,
+    Footnote,
+    Caption,
+}
+
+/// The font used for each [`TextStyle`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    pub large_title: Font,
+    pub title: Font,
+    pub title2: Font,
+    pub headline: Font,
+    pub subheadline: Font,
+    pub body: Font,
+    pub callout: Font,
+    pub footnote: Font,
+    pub caption: Font,
+}
+
+impl Typography {
+    /// The default type scale set in `families`.
+    pub fn new(families: &[&str]) -> Self {
+        let font = |size, weight| Font::new_with_fallbacks(families, size, weight, false);
+        Self {
+            large_title: font(26, FontWeight::Regular),
+            title: font(22, FontWeight::Regular),
+            title2: font(17, FontWeight::Regular),
+            headline: font(13, FontWeight::Bold),
+            subheadline: font(11, FontWeight::Regular),
+            body: font(13, FontWeight::Regular),
+            callout: font(12, FontWeight::Regular),
+            footnote: font(10, FontWeight::Regular),
+            caption: font(10, FontWeight::Regular),
+        }
+    }
+
+    /// The font for `style`, or `None` for [`TextStyle::Custom`].
+    pub fn font(&self, style: TextStyle) -> Option<&Font> {
+        match style {
+            TextStyle::Custom => None,
+            TextStyle::LargeTitle => Some(&self.large_title),
+            TextStyle::Title => Some(&self.title),
+            TextStyle::Title2 => Some(&self.title2),
+            TextStyle::Headline => Some(&self.headline),
+            TextStyle::Subheadline => Some(&self.subheadline),
+            TextStyle::Body => Some(&self.body),
+            TextStyle::Callout => Some(&self.callout),
+            TextStyle::Footnote => Some(&self.footnote),
+            TextStyle::Caption => Some(&self.caption),
+        }
+    }
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Self::new(&["system-ui", "sans-serif"])
+    }
+}
@@ -0,0 +1,104 @@
+use super::{Bitmap, ImageError};
+
+/// Decode PNG, JPEG or SVG data, told apart by their contents.
+pub(super) fn decode(data: &[u8], scale_factor: f64) -> Result<Bitmap, ImageError> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        decode_png(data)
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        decode_jpeg(data)
+    } else if looks_like_svg(data) {
+        decode_svg(data, scale_factor)
+    } else {
+        Err(ImageError::Unsupported)
+    }
+}
+
+fn decode_png(data: &[u8]) -> Result<Bitmap, ImageError> {
+    let mut decoder = png::Decoder::new(data);
+    // Palettes, low bit depths and 16 bit samples all become 8 bit samples
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|err| ImageError::Decode(err.to_string()))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|err| ImageError::Decode(err.to_string()))?;
+    buf.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 0xff])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 0xff]).collect(),
+        png::ColorType::Indexed => {
+            return Err(ImageError::Decode("unexpanded palette".to_string()));
+        }
+    };
+    Bitmap::new(info.width, info.height, pixels)
+}
+
+fn decode_jpeg(data: &[u8]) -> Result<Bitmap, ImageError> {
+    use zune_jpeg::zune_core::{colorspace::ColorSpace, options::DecoderOptions};
+
+    let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGBA);
+    let mut decoder = zune_jpeg::JpegDecoder::new_with_options(data, options);
+    let pixels = decoder
+        .decode()
+        .map_err(|err| ImageError::Decode(format!("{err:?}")))?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| ImageError::Decode("missing JPEG header".to_string()))?;
+    Bitmap::new(info.width as u32, info.height as u32, pixels)
+}
+
+fn looks_like_svg(data: &[u8]) -> bool {
+    let head = &data[..data.len().min(1024)];
+    String::from_utf8_lossy(head).contains("<svg")
+}
+
+/// Rasterize at the document's size times `scale_factor`, so the bitmap is
+/// sharp on the display but takes up the document's size in logical pixels.
+#[cfg(feature = "svg")]
+fn decode_svg(data: &[u8], scale_factor: f64) -> Result<Bitmap, ImageError> {
+    use resvg::{tiny_skia, usvg};
+
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default())
+        .map_err(|err| ImageError::Decode(err.to_string()))?;
+    let scale = scale_factor.max(0.1) as f32;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| ImageError::Decode("empty SVG".to_string()))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| ImageError::Decode("empty SVG".to_string()))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    // tiny-skia premultiplies, bitmaps don't
+    let mut pixels = pixmap.take();
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        if alpha != 0 && alpha != 0xff {
+            for channel in &mut pixel[..3] {
+                *channel = ((*channel as u32 * 0xff + alpha / 2) / alpha).min(0xff) as u8;
+            }
+        }
+    }
+    Ok(Bitmap::new(size.width(), size.height(), pixels)?.with_scale(scale as f64))
+}
+
+#[cfg(not(feature = "svg"))]
+fn decode_svg(_data: &[u8], _scale_factor: f64) -> Result<Bitmap, ImageError> {
+    Err(ImageError::Unsupported)
+}
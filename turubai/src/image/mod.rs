@@ -0,0 +1,260 @@
+//! Pictures for the [`Image`](crate::elements::Image) element: where they
+//! come from and their decoded pixels.
+
+mod decode;
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+};
+
+/// Where an image's pixels come from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageSource {
+    /// A PNG, JPEG or (with the `svg` feature) SVG file
+    Path(PathBuf),
+    /// The contents of a PNG, JPEG or SVG file, e.g. from `include_bytes!`
+    Bytes(Arc<[u8]>),
+    /// Already decoded pixels
+    Bitmap(Arc<Bitmap>),
+}
+
+impl ImageSource {
+    pub fn path(path: impl AsRef<Path>) -> Self {
+        Self::Path(path.as_ref().to_path_buf())
+    }
+
+    pub fn bytes(data: impl Into<Arc<[u8]>>) -> Self {
+        Self::Bytes(data.into())
+    }
+
+    /// `pixels` holds `width * height` RGBA pixels, 8 bits per channel,
+    /// row by row from the top.
+    pub fn rgba(width: u32, height: u32, pixels: impl Into<Vec<u8>>) -> Result<Self, ImageError> {
+        Ok(Self::Bitmap(Arc::new(Bitmap::new(width, height, pixels.into())?)))
+    }
+
+    /// Decode the image for a display with `scale_factor` device pixels per
+    /// logical pixel, which only matters for vector images. Files are read
+    /// and decoded once and then shared.
+    pub fn load(&self, scale_factor: f64) -> Result<Arc<Bitmap>, ImageError> {
+        match self {
+            ImageSource::Path(path) => {
+                let key = (path.clone(), scale_factor.to_bits());
+                if let Some(bitmap) = CACHE.lock().unwrap().get(&key) {
+                    return Ok(bitmap.clone());
+                }
+                let data = std::fs::read(path)?;
+                let bitmap = Arc::new(decode::decode(&data, scale_factor)?);
+                CACHE.lock().unwrap().insert(key, bitmap.clone());
+                Ok(bitmap)
+            }
+            ImageSource::Bytes(data) => Ok(Arc::new(decode::decode(data, scale_factor)?)),
+            ImageSource::Bitmap(bitmap) => Ok(bitmap.clone()),
+        }
+    }
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        Self::path(path)
+    }
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+/// Decoded files by path and the bits of the scale factor.
+type Cache = HashMap<(PathBuf, u64), Arc<Bitmap>>;
+
+static CACHE: LazyLock<Mutex<Cache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Forget every decoded file, e.g. after they changed on disk.
+pub fn clear_cache() {
+    CACHE.lock().unwrap().clear();
+}
+
+/// Decoded pixels: RGBA, 8 bits per channel, not premultiplied.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bitmap {
+    width: u32,
+    height: u32,
+    /// Device pixels per logical pixel the bitmap was made for, above 1
+    /// for vector images rendered on high density displays
+    scale: f64,
+    pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, ImageError> {
+        if pixels.len() != width as usize * height as usize * 4 {
+            return Err(ImageError::Size {
+                width,
+                height,
+                len: pixels.len(),
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            scale: 1.0,
+            pixels,
+        })
+    }
+
+    #[cfg(any(feature = "svg", feature = "raster"))]
+    pub(crate) fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// The size the image takes up unless it is resizable, in logical pixels.
+    pub fn logical_size(&self) -> (f64, f64) {
+        (self.width as f64 / self.scale, self.height as f64 / self.scale)
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The pixel at `(x, y)` as `[r, g, b, a]`.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[start..start + 4].try_into().ok()
+    }
+
+    /// Encode as an 8 bit RGBA PNG.
+    pub fn encode_png(&self) -> Result<Vec<u8>, ImageError> {
+        let mut data = vec![];
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(std::io::Error::from)?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(std::io::Error::from)?;
+        writer.finish().map_err(std::io::Error::from)?;
+        Ok(data)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        std::fs::write(path, self.encode_png()?)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bitmap")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("scale", &self.scale)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(std::io::Error),
+    /// The data is not in a format we can decode
+    Unsupported,
+    /// The data looked like a known format but could not be decoded
+    Decode(String),
+    /// A pixel buffer whose length doesn't match its size
+    Size { width: u32, height: u32, len: usize },
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Io(err) => write!(f, "could not read image: {err}"),
+            ImageError::Unsupported => f.write_str("unsupported image format"),
+            ImageError::Decode(err) => write!(f, "could not decode image: {err}"),
+            ImageError::Size { width, height, len } => write!(
+                f,
+                "{len} bytes is not a {width}x{height} RGBA image"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImageError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(err: std::io::Error) -> Self {
+        ImageError::Io(err)
+    }
+}
+
+#[test]
+fn test_decode() {
+    // A 2x1 grayscale PNG with a transparent black and an opaque white pixel
+    let mut data = vec![];
+    let mut encoder = png::Encoder::new(&mut data, 2, 1);
+    encoder.set_color(png::ColorType::GrayscaleAlpha);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(&[0, 0, 0xff, 0xff])
+        .unwrap();
+
+    let bitmap = ImageSource::bytes(data).load(2.0).unwrap();
+    assert_eq!((bitmap.width(), bitmap.height()), (2, 1));
+    // Only vector images depend on the scale factor
+    assert_eq!(bitmap.logical_size(), (2.0, 1.0));
+    assert_eq!(bitmap.pixel(0, 0), Some([0, 0, 0, 0]));
+    assert_eq!(bitmap.pixel(1, 0), Some([0xff, 0xff, 0xff, 0xff]));
+
+    assert!(matches!(
+        ImageSource::bytes(&b"GIF89a"[..]).load(1.0),
+        Err(ImageError::Unsupported)
+    ));
+    assert!(matches!(
+        ImageSource::path("/nonexistent.png").load(1.0),
+        Err(ImageError::Io(_))
+    ));
+
+    // Vector images are rasterized for the display but keep their size
+    let svg = ImageSource::bytes(
+        &br#"<svg xmlns="http://www.w3.org/2000/svg" width="3" height="2"/>"#[..],
+    );
+    #[cfg(feature = "svg")]
+    {
+        let bitmap = svg.load(2.0).unwrap();
+        assert_eq!((bitmap.width(), bitmap.height()), (6, 4));
+        assert_eq!(bitmap.logical_size(), (3.0, 2.0));
+    }
+    #[cfg(not(feature = "svg"))]
+    assert!(matches!(svg.load(2.0), Err(ImageError::Unsupported)));
+}
@@ -0,0 +1,475 @@
+//! A server exposing the live shadow tree of a window over a Unix domain
+//! socket, for the `turubai-inspect` client and other tools.
+//!
+//! Set `TURUBAI_INSPECTOR=1` to have the backend listen on
+//! [`socket_path`] for the application, or to a path to listen there:
+//!
+//! ```text
+//! $ TURUBAI_INSPECTOR=1 cargo run --features gtk,inspector --example hello_world
+//! $ turubai-inspect tree
+//! VStack [0,0 800x600]  .
+//!   Text "Hello" [380,0 40x21]  0
+//! $ turubai-inspect set 0 text::color SystemRed
+//! ```
+//!
+//! Clients send [`Request`]s and receive [`Response`]s as JSON, one per
+//! line. Like in a browser's element inspector, modifiers set through the
+//! inspector last until the markup is built again, e.g. because state
+//! changed.
+
+mod protocol;
+
+pub use protocol::*;
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::{
+        fs::{DirBuilderExt, FileTypeExt, MetadataExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use crate::{
+    elements::Element,
+    markup::{Registry, Value},
+    shadow::{ShadowNode, ShadowTree},
+};
+
+/// Set to `1` to listen on the default socket or to the path of one.
+pub const INSPECTOR_ENV: &str = "TURUBAI_INSPECTOR";
+
+/// Where inspector sockets are put: `$XDG_RUNTIME_DIR`, or without one a
+/// directory in the temporary one that only the current user can enter,
+/// created if needed. Fails if that directory is someone else's or others
+/// can get into it.
+pub fn socket_dir() -> io::Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return Ok(dir.into());
+    }
+    let uid = unsafe { libc::getuid() };
+    let dir = std::env::temp_dir().join(format!("turubai-{uid}"));
+    match fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Err(err) if err.kind() != io::ErrorKind::AlreadyExists => return Err(err),
+        _ => {}
+    }
+    // It may have been there already, made by anyone
+    let metadata = fs::symlink_metadata(&dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} isn't private to the current user", dir.display()),
+        ));
+    }
+    Ok(dir)
+}
+
+/// The socket an application with `app_id` listens on by default.
+pub fn socket_path(app_id: &str) -> io::Result<PathBuf> {
+    Ok(socket_dir()?.join(format!("turubai-{app_id}.sock")))
+}
+
+/// Remove the socket at `path` if it was left behind by a process that
+/// didn't exit cleanly, i.e. nothing accepts connections on it anymore.
+/// Anything else is left for binding to fail on.
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if !metadata.file_type().is_socket() {
+        return Ok(());
+    }
+    match UnixStream::connect(path) {
+        Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path),
+        _ => Ok(()),
+    }
+}
+
+/// A request and where its response goes, serialized because taffy styles
+/// can't be sent between threads.
+type Pending = (Request, Sender<String>);
+
+/// The server. Connections are accepted on background threads, while
+/// requests are answered on the thread that owns the window, whenever it
+/// calls [`Inspector::serve`].
+pub struct Inspector {
+    path: PathBuf,
+    requests: Receiver<Pending>,
+    highlight: Option<NodePath>,
+    registry: Registry,
+}
+
+impl Inspector {
+    /// Listen on the socket at `path`, replacing a stale socket there.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        remove_stale_socket(&path)?;
+        let listener = UnixListener::bind(&path)?;
+
+        let (sender, requests) = mpsc::channel();
+        thread::Builder::new()
+            .name("turubai-inspector".into())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let sender = sender.clone();
+                    let _ = thread::Builder::new()
+                        .name("turubai-inspector-client".into())
+                        .spawn(move || connection(stream, sender));
+                }
+            })?;
+
+        Ok(Self {
+            path,
+            requests,
+            highlight: None,
+            registry: Registry::default(),
+        })
+    }
+
+    /// Listen if [`INSPECTOR_ENV`] asks for it.
+    pub fn from_env(app_id: &str) -> Option<Self> {
+        let path = match std::env::var_os(INSPECTOR_ENV)? {
+            value if value == "0" || value.is_empty() => return None,
+            value if value == "1" => match socket_path(app_id) {
+                Ok(path) => path,
+                Err(err) => {
+                    eprintln!("[turubai] can't listen for the inspector: {err}");
+                    return None;
+                }
+            },
+            value => PathBuf::from(value),
+        };
+        match Self::bind(&path) {
+            Ok(inspector) => {
+                eprintln!("[turubai] inspector listening on {}", path.display());
+                Some(inspector)
+            }
+            Err(err) => {
+                eprintln!("[turubai] can't listen on {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Modifiers set through the inspector are looked up in `registry`.
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Answer the requests that came in since the last call, up to the
+    /// first that sets a modifier. `element` is what the window was built
+    /// from, `root` the node shown in it. Returns whether a modifier was
+    /// set, in which case the shadow tree needs to be built again from
+    /// `element`.
+    pub fn serve(&mut self, element: &dyn Element, tree: &ShadowTree, root: &ShadowNode) -> bool {
+        let mut edited = false;
+        while let Ok((request, reply)) = self.requests.try_recv() {
+            let response = match request {
+                Request::Tree { path } => {
+                    let mut inspected = None;
+                    if let Some(node) = node_at(root, &path) {
+                        with_element_at(element, &path, &mut |element| {
+                            inspected = Some(InspectedNode::new(element, node, tree, path.clone()));
+                        });
+                    }
+                    match inspected {
+                        Some(inspected) => Response::Tree(Box::new(inspected)),
+                        None => no_node(&path),
+                    }
+                }
+                Request::Highlight { path: Some(path) } if node_at(root, &path).is_none() => {
+                    no_node(&path)
+                }
+                Request::Highlight { path } => {
+                    self.highlight = path;
+                    Response::Ok
+                }
+                Request::Set {
+                    path,
+                    modifier,
+                    value,
+                } => match self.set(element, &path, &modifier, &value) {
+                    Ok(()) => {
+                        edited = true;
+                        Response::Ok
+                    }
+                    Err(message) => Response::Error(message),
+                },
+            };
+            let response = serde_json::to_string(&response).expect("responses serialize");
+            let _ = reply.send(response);
+            if edited {
+                // Later requests are answered once the tree is rebuilt
+                break;
+            }
+        }
+        edited
+    }
+
+    fn set(&self, element: &dyn Element, path: &[usize], modifier: &str, value: &str) -> Result<(), String> {
+        let value = Value::parse(value).map_err(|err| err.to_string())?;
+        let mut result = Err(format!("no node at {}", format_path(path)));
+        with_element_at(element, path, &mut |element| {
+            result = match element.modifiers() {
+                Some(modifiers) => {
+                    let name = match modifier.contains("::") {
+                        true => modifier.to_string(),
+                        false => format!("{}::{modifier}", element.name()),
+                    };
+                    self.registry
+                        .set_modifier(&mut modifiers.lock().unwrap(), &name, &value)
+                }
+                None => Err(format!("{} has no modifiers", element.display_name())),
+            };
+        });
+        result
+    }
+
+    /// The node to outline, if any.
+    pub fn highlighted(&self) -> Option<&[usize]> {
+        self.highlight.as_deref()
+    }
+
+    /// Where the outlined node is laid out, as `(x, y, width, height)` in
+    /// window coordinates.
+    pub fn highlight_frame(&self, tree: &ShadowTree, root: &ShadowNode) -> Option<(f32, f32, f32, f32)> {
+        let path = self.highlight.as_ref()?;
+        let mut node = root;
+        let mut layout = tree.get_layout(node.taffy_id)?;
+        let (mut x, mut y) = (layout.location.x, layout.location.y);
+        for index in path {
+            node = node.children.get(*index)?;
+            layout = tree.get_layout(node.taffy_id)?;
+            x += layout.location.x;
+            y += layout.location.y;
+        }
+        Some((x, y, layout.size.width, layout.size.height))
+    }
+}
+
+impl Drop for Inspector {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Read requests from `stream` and write back their responses.
+fn connection(stream: UnixStream, sender: Sender<Pending>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => {
+                let (reply, response) = mpsc::channel();
+                if sender.send((request, reply)).is_err() {
+                    break;
+                }
+                match response.recv() {
+                    Ok(response) => response,
+                    // The inspector is gone along with the window
+                    Err(_) => break,
+                }
+            }
+            Err(err) => {
+                let response = Response::Error(format!("invalid request: {err}"));
+                serde_json::to_string(&response)?
+            }
+        };
+        writer.write_all(response.as_bytes())?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// A connection to an inspector.
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Client {
+    pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        let writer = UnixStream::connect(path)?;
+        Ok(Self {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+        })
+    }
+
+    /// Send `request` and wait for its response.
+    pub fn request(&mut self, request: &Request) -> io::Result<Response> {
+        serde_json::to_writer(&mut self.writer, request)?;
+        self.writer.write_all(b"\n")?;
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+/// `0.1.2`, or `.` for the root.
+pub fn format_path(path: &[usize]) -> String {
+    match path {
+        [] => ".".into(),
+        path => path.iter().map(usize::to_string).collect::<Vec<_>>().join("."),
+    }
+}
+
+/// Read a path written by [`format_path`].
+pub fn parse_path(path: &str) -> Option<NodePath> {
+    match path {
+        "." | "" => Some(vec![]),
+        path => path.split('.').map(|index| index.parse().ok()).collect(),
+    }
+}
+
+fn no_node(path: &[usize]) -> Response {
+    Response::Error(format!("no node at {}", format_path(path)))
+}
+
+fn node_at<'a>(root: &'a ShadowNode, path: &[usize]) -> Option<&'a ShadowNode> {
+    path.iter().try_fold(root, |node, index| node.children.get(*index))
+}
+
+/// Call `f` with the element at `path` below the window's content, which
+/// is the child of `element` if it is a window, like backends unwrap it.
+fn with_element_at(element: &dyn Element, path: &[usize], f: &mut dyn FnMut(&dyn Element)) {
+    if element.name() == "window" {
+        let mut first = true;
+        element.for_each_child(&mut |content| {
+            if std::mem::take(&mut first) {
+                descend(content, path, f);
+            }
+        });
+    } else {
+        descend(element, path, f);
+    }
+}
+
+fn descend(element: &dyn Element, path: &[usize], f: &mut dyn FnMut(&dyn Element)) {
+    let Some((index, rest)) = path.split_first() else {
+        return f(element);
+    };
+    let mut i = 0;
+    element.for_each_child(&mut |child| {
+        if i == *index {
+            descend(child, rest, f);
+        }
+        i += 1;
+    });
+}
+
+#[test]
+fn test_inspector() {
+    use crate::{
+        color::ColorScheme, composition::VStack, elements::{Modifiers, Text}, pal::headless::Context,
+        shadow::NodeKind,
+    };
+
+    let path = std::env::temp_dir().join(format!("turubai-inspector-{}.sock", std::process::id()));
+    let mut inspector = Inspector::bind(&path).unwrap();
+
+    let modifiers = Modifiers::default();
+    let children: Vec<Box<dyn Element>> = vec![
+        Box::new(Text::new("Hello", modifiers.fork())),
+        Box::new(Text::new("World", modifiers.fork())),
+    ];
+    let stack = Box::new(VStack::new(modifiers, children));
+    let mut window = Context::new(ColorScheme::Light).render(stack, 200.0, 100.0);
+
+    let client_path = path.clone();
+    let client = thread::spawn(move || {
+        let mut client = Client::connect(client_path).unwrap();
+        let requests = [
+            Request::Tree { path: vec![] },
+            Request::Set {
+                path: vec![1],
+                modifier: "font".into(),
+                value: r#"Font::new("Inter", 26, Bold, false)"#.into(),
+            },
+            Request::Tree { path: vec![1] },
+            Request::Highlight { path: Some(vec![1]) },
+            Request::Set {
+                path: vec![0],
+                modifier: "text::colour".into(),
+                value: "SystemRed".into(),
+            },
+            Request::Highlight { path: Some(vec![5]) },
+        ];
+        requests
+            .iter()
+            .map(|request| serde_json::to_string(&client.request(request).unwrap()).unwrap())
+            .collect::<Vec<_>>()
+    });
+    while !client.is_finished() {
+        window.inspect(&mut inspector);
+        thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let responses: Vec<Response> = client
+        .join()
+        .unwrap()
+        .iter()
+        .map(|response| serde_json::from_str(response).unwrap())
+        .collect();
+
+    let Response::Tree(tree) = &responses[0] else {
+        panic!("{:?}", responses[0]);
+    };
+    assert_eq!(tree.element, "VStack");
+    assert_eq!(tree.children[1].path, vec![1]);
+    assert_eq!(tree.children[1].element, "Text");
+    assert!(matches!(&tree.children[1].kind, NodeKind::Text { content, .. } if content == "World"));
+    let small = tree.children[1].layout.unwrap().size.height;
+
+    // Setting the font rebuilt the tree with the larger one
+    assert!(matches!(responses[1], Response::Ok));
+    let Response::Tree(text) = &responses[2] else {
+        panic!("{:?}", responses[2]);
+    };
+    assert!(text.layout.unwrap().size.height > small);
+
+    assert!(matches!(responses[3], Response::Ok));
+    assert_eq!(inspector.highlighted(), Some(&[1][..]));
+    let (_, y, _, height) = inspector.highlight_frame(&window.tree, &window.root).unwrap();
+    assert_eq!((y, height), (small, text.layout.unwrap().size.height));
+
+    assert!(matches!(&responses[4], Response::Error(message) if message == "unknown modifier `text::colour`"));
+    assert!(matches!(&responses[5], Response::Error(message) if message == "no node at 5"));
+
+    drop(inspector);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_stale_socket() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("turubai-stale-{}.sock", std::process::id()));
+
+    // Nothing listens on a socket whose listener is gone
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+    let inspector = Inspector::bind(&path).unwrap();
+
+    // A socket still in use is left alone
+    let err = Inspector::bind(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    drop(inspector);
+
+    // So is anything that isn't a socket
+    fs::write(&path, "data").unwrap();
+    assert!(Inspector::bind(&path).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "data");
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use taffy::Style;
+
+use crate::{
+    elements::Element,
+    shadow::{NodeIdentity, NodeKind, SavedLayout, ShadowNode, ShadowTree},
+};
+
+/// The path of a node: the index of each node on the way to it among its
+/// siblings, starting below the window. The window's content is `[]`.
+pub type NodePath = Vec<usize>;
+
+/// What a client asks for, one JSON object per line:
+///
+/// ```text
+/// {"command": "tree", "path": [0]}
+/// {"command": "highlight", "path": [0, 1]}
+/// {"command": "set", "path": [0, 1], "modifier": "text::color", "value": "SystemRed"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    /// The node at `path` and its descendants
+    Tree {
+        #[serde(default)]
+        path: NodePath,
+    },
+    /// Outline the node at `path` in the window, or nothing if `None`
+    Highlight { path: Option<NodePath> },
+    /// Set a modifier of the element at `path` and rebuild the window.
+    /// `modifier` is named like an optional argument in markup, with the
+    /// element's own member left out if it is the one meant, and `value`
+    /// is written like one too.
+    Set {
+        path: NodePath,
+        modifier: String,
+        value: String,
+    },
+}
+
+/// The answer to a [`Request`], one JSON value per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response {
+    Tree(Box<InspectedNode>),
+    Ok,
+    Error(String),
+}
+
+/// A node of the live shadow tree with what it was built from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectedNode {
+    pub path: NodePath,
+    /// The display name of the element the node was built from, e.g.
+    /// `Text` or `Padding`
+    pub element: String,
+    pub identity: NodeIdentity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub kind: NodeKind,
+    pub style: Style,
+    /// `None` if the node hasn't been laid out
+    pub layout: Option<SavedLayout>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<InspectedNode>,
+}
+
+impl InspectedNode {
+    /// Describe `node`, built from `element`, and its descendants.
+    pub fn new(element: &dyn Element, node: &ShadowNode, tree: &ShadowTree, path: NodePath) -> Self {
+        let mut children = vec![];
+        let mut index = 0;
+        element.for_each_child(&mut |child| {
+            if let Some(child_node) = node.children.get(index) {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                children.push(InspectedNode::new(child, child_node, tree, child_path));
+            }
+            index += 1;
+        });
+        Self {
+            path,
+            element: element.display_name().to_string(),
+            identity: node.identity,
+            key: node.key.clone(),
+            kind: node.kind.clone(),
+            style: node.style.clone(),
+            layout: tree.get_layout(node.taffy_id).map(SavedLayout::from),
+            children,
+        }
+    }
+}
@@ -1,11 +1,24 @@
+pub mod animation;
+pub mod canvas;
 pub mod color;
 pub mod composition;
 pub mod elements;
+pub mod environment;
+pub mod fill;
 pub mod font;
+pub mod image;
+#[cfg(all(feature = "inspector", unix))]
+pub mod inspector;
+pub mod markup;
 pub mod pal;
 pub mod postprocessing;
 pub mod runtime;
 pub mod shadow;
+pub mod shape;
+pub mod state;
+pub mod stylesheet;
+pub mod testing;
+pub mod theme;
 
 mod units;
 pub use units::*;
@@ -26,4 +39,14 @@ pub enum Backend {
 pub trait Application: Send + Sync {
     fn id(&self) -> &'static str;
     fn markup(&self) -> Box<dyn Element>;
+
+    /// The theme every window of the application starts out with.
+    fn theme(&self) -> theme::Theme {
+        theme::Theme::default()
+    }
+
+    /// Rules applied to every window of the application.
+    fn stylesheet(&self) -> stylesheet::Stylesheet {
+        stylesheet::Stylesheet::default()
+    }
 }
@@ -0,0 +1,244 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    color::Color,
+    composition::{HorizontalAlignment, Spacer, VStack},
+    elements::{Element, Modifiers, Text},
+    font::{Font, FontWeight, GenericFamily, TextStyle},
+    postprocessing::{BackgroundColor, Padding},
+    runtime::WindowTemplate,
+    state, Unit,
+};
+
+use super::{watch, Markup, MarkupError, Registry};
+
+/// Set to `1` to watch markup files in release builds or `0` to not
+/// watch them in debug builds.
+pub const HOT_RELOAD_ENV: &str = "TURUBAI_HOT_RELOAD";
+
+/// Markup read from a file that is read again whenever it changes, so a
+/// running application picks up edits without being rebuilt:
+///
+/// ```ignore
+/// struct App { screen: LiveMarkup }
+///
+/// impl Application for App {
+///     fn markup(&self) -> Box<dyn Element> {
+///         self.screen.build()
+///     }
+/// }
+///
+/// turubai_main(App { screen: LiveMarkup::new("screens/main.tui") });
+/// ```
+///
+/// In debug builds the file is watched, and saving it rebuilds the shadow
+/// tree and native widgets of the open window in place like a state change
+/// does. Markup that doesn't parse or build is shown as an error in place
+/// of the window until it is fixed, so the file should hold a whole
+/// `WindowTemplate`.
+pub struct LiveMarkup {
+    path: PathBuf,
+    registry: Registry,
+    changed: Arc<AtomicBool>,
+    loaded: Mutex<Option<Loaded>>,
+    /// Stops watching the file once the markup is dropped
+    _watcher: Option<watch::Watcher>,
+}
+
+struct Loaded {
+    source: String,
+    markup: Result<Markup, MarkupError>,
+}
+
+impl LiveMarkup {
+    /// Markup at `path` built with the built-in registry.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self::with_registry(path, Registry::default())
+    }
+
+    pub fn with_registry(path: impl AsRef<Path>, registry: Registry) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let changed = Arc::new(AtomicBool::new(false));
+        let watcher = hot_reload()
+            .then(|| {
+                let changed = changed.clone();
+                watch::watch(path.clone(), move || {
+                    changed.store(true, Ordering::SeqCst);
+                    state::invalidate();
+                })
+                .map_err(|err| eprintln!("[turubai] can't watch {}: {err}", path.display()))
+                .ok()
+            })
+            .flatten();
+        Self {
+            path,
+            registry,
+            changed,
+            loaded: Mutex::new(None),
+            _watcher: watcher,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the file again on the next [`LiveMarkup::build`], as if it
+    /// changed.
+    pub fn reload(&self) {
+        self.changed.store(true, Ordering::SeqCst);
+    }
+
+    /// Build the elements, or a view of what's wrong with the markup.
+    pub fn build(&self) -> Box<dyn Element> {
+        let mut loaded = self.loaded.lock().unwrap();
+        let loaded = self.load(&mut loaded);
+        let built = match &loaded.markup {
+            Ok(markup) => markup.build(&self.registry),
+            Err(err) => return error_view(&self.path, &loaded.source, err),
+        };
+        built.unwrap_or_else(|err| {
+            eprintln!("[turubai] {}: {err}", self.path.display());
+            error_view(&self.path, &loaded.source, &err)
+        })
+    }
+
+    fn load<'a>(&self, loaded: &'a mut Option<Loaded>) -> &'a Loaded {
+        if self.changed.swap(false, Ordering::SeqCst) {
+            *loaded = None;
+        }
+        loaded.get_or_insert_with(|| {
+            let (source, markup) = match std::fs::read_to_string(&self.path) {
+                Ok(source) => {
+                    let markup = Markup::parse(&source);
+                    (source, markup)
+                }
+                Err(err) => (String::new(), Err(err.into())),
+            };
+            if let Err(err) = &markup {
+                eprintln!("[turubai] {}: {err}", self.path.display());
+            }
+            Loaded { source, markup }
+        })
+    }
+}
+
+/// Whether markup files are watched, see [`HOT_RELOAD_ENV`].
+fn hot_reload() -> bool {
+    match std::env::var(HOT_RELOAD_ENV).as_deref() {
+        Ok("1") => true,
+        Ok("0") => false,
+        _ => cfg!(debug_assertions),
+    }
+}
+
+/// A window showing `err` and, if it has one, the line it points at.
+fn error_view(path: &Path, source: &str, err: &MarkupError) -> Box<dyn Element> {
+    let text = |content: &str, style: TextStyle, color: Color| -> Box<dyn Element> {
+        let modifiers = Modifiers::default();
+        {
+            let mut inner = modifiers.lock().unwrap();
+            inner.text.style = style;
+            inner.text.color = color;
+            if style == TextStyle::Custom {
+                inner.text.font = Some(Font::new_with_fallbacks(
+                    &[GenericFamily::Monospace.css_name()],
+                    13,
+                    FontWeight::Regular,
+                    false,
+                ));
+            }
+        }
+        Box::new(Text::new(content, modifiers))
+    };
+    let white = Color::new(255, 255, 255);
+    let muted = Color::new_with_alpha(255, 255, 255, 0.7);
+
+    let mut lines = vec![text("Markup error", TextStyle::Headline, white)];
+    let location = match err.position() {
+        Some(position) => format!("{}:{position}", path.display()),
+        None => path.display().to_string(),
+    };
+    lines.push(text(&location, TextStyle::Footnote, muted));
+    lines.push(text(&err.to_string(), TextStyle::Body, white));
+    if let Some(line) = err
+        .position()
+        .and_then(|position| Some((source.lines().nth(position.line - 1)?, position)))
+    {
+        let (line, position) = line;
+        let caret = format!("{}^", " ".repeat(position.column - 1));
+        lines.push(text(line, TextStyle::Custom, white));
+        lines.push(text(&caret, TextStyle::Custom, Color::SystemRed));
+    }
+    lines.push(Box::new(Spacer::turubai_new_with_0_args(
+        Modifiers::default(),
+        |_| vec![],
+    )));
+
+    let modifiers = Modifiers::default();
+    {
+        let mut inner = modifiers.lock().unwrap();
+        inner.v_stack.alignment = HorizontalAlignment::Leading;
+        inner.v_stack.spacing = Unit::Pixels(6.0);
+    }
+    let column = Box::new(VStack::new(modifiers, lines));
+    let padded = Box::new(Padding::new(
+        Unit::Pixels(16.0),
+        Unit::Pixels(16.0),
+        Unit::Pixels(16.0),
+        Unit::Pixels(16.0),
+        column,
+    ));
+    let background = Box::new(BackgroundColor::new(&Color::new(110, 24, 24), padded));
+
+    let modifiers = Modifiers::default();
+    modifiers.lock().unwrap().window_template.title = "Markup error";
+    Box::new(WindowTemplate::turubai_new_with_0_args(
+        modifiers,
+        move |_| vec![background],
+    ))
+}
+
+#[test]
+fn test_live_markup() {
+    use crate::{color::ColorScheme, pal::headless::Context};
+
+    let path = std::env::temp_dir().join(format!("turubai-live-{}.tui", std::process::id()));
+    std::fs::write(&path, r#"WindowTemplate { Text("Before") }"#).unwrap();
+    let live = Arc::new(LiveMarkup::new(&path));
+
+    let markup = live.clone();
+    let mut window =
+        Context::new(ColorScheme::Light).render_markup(move || markup.build(), 200.0, 100.0);
+    assert!(window.dump_layout().starts_with("Text \"Before\""));
+
+    // Saving the file makes the window rebuild with the new markup
+    std::fs::write(&path, r#"WindowTemplate { Text("After") }"#).unwrap();
+    let start = std::time::Instant::now();
+    while !live.changed.load(Ordering::SeqCst) {
+        assert!(start.elapsed().as_secs() < 5, "the change wasn't noticed");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    window.update();
+    assert!(window.dump_layout().starts_with("Text \"After\""));
+
+    std::fs::write(&path, "WindowTemplate {\n  Text(\"After\"\n}").unwrap();
+    live.reload();
+    state::invalidate();
+    window.update();
+    assert_eq!(window.title.as_deref(), Some("Markup error"));
+    let dump = window.dump_layout();
+    assert!(
+        dump.contains(&format!("Text \"{}:3:1\"", path.display())),
+        "{dump}"
+    );
+    assert!(dump.contains("Text \"^\" "), "{dump}");
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,231 @@
+//! Markup read at runtime instead of compiled with `turubai!`, so screens
+//! can live in files next to the application.
+//!
+//! The grammar is the one `turubai!` takes, with literal values in place of
+//! Rust expressions:
+//!
+//! ```text
+//! WindowTemplate(title: "Turubai Composed.") {
+//!     VStack(text::color: Color::new(255, 255, 255)) {
+//!         Text("Hello, World!")
+//!         Text("From the Turubai Compositor", font: Font::new("Inter", 16, Bold, false))
+//!     }
+//!     .padding(all: Em(1.0))
+//!     .background_color("#000000bf")
+//!     .corner_radius(12.0)
+//! }
+//! ```
+//!
+//! Values are strings, numbers, `true`/`false`, enum variants with or
+//! without their type (`Center`, `HorizontalAlignment::Center`), the
+//! constructors of a few value types (`Em(1.0)`, `Color::new(..)`), tuples
+//! and lists. Anything else is handed over by the application with
+//! [`Registry::bind`] and referred to by name. Each tag, postprocessing
+//! call and optional argument is looked up in a [`Registry`], which knows
+//! the ones of this crate and can be taught new ones.
+//!
+//! ```ignore
+//! fn markup(&self) -> Box<dyn Element> {
+//!     markup::load("screens/main.tui").unwrap_or_else(|err| panic!("{err}"))
+//! }
+//! ```
+//!
+//! During development, [`LiveMarkup`] reads the file again whenever it is
+//! saved and the open window is rebuilt in place.
+
+mod live;
+mod parse;
+mod registry;
+mod value;
+mod watch;
+
+pub use live::{LiveMarkup, HOT_RELOAD_ENV};
+pub use registry::{Arguments, Registry};
+pub use value::{FromValue, Value};
+
+use std::{fmt, path::Path};
+
+use crate::elements::Element;
+
+/// Where something is in the markup source, counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum MarkupError {
+    Io(std::io::Error),
+    /// The source isn't markup
+    Syntax {
+        position: Position,
+        message: String,
+    },
+    /// The markup names something the registry doesn't know, or passes
+    /// something it doesn't take
+    Build {
+        position: Position,
+        message: String,
+    },
+}
+
+impl MarkupError {
+    /// Where in the source the error is, unless the file couldn't be read.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            MarkupError::Io(_) => None,
+            MarkupError::Syntax { position, .. } | MarkupError::Build { position, .. } => {
+                Some(*position)
+            }
+        }
+    }
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkupError::Io(err) => write!(f, "could not read markup: {err}"),
+            MarkupError::Syntax { position, message } => {
+                write!(f, "invalid markup at {position}: {message}")
+            }
+            MarkupError::Build { position, message } => {
+                write!(f, "could not build markup at {position}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MarkupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MarkupError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for MarkupError {
+    fn from(err: std::io::Error) -> Self {
+        MarkupError::Io(err)
+    }
+}
+
+/// Parsed markup, which can be built into elements any number of times.
+#[derive(Debug, Clone)]
+pub struct Markup {
+    root: parse::Node,
+}
+
+impl Markup {
+    /// Parse markup with a single root element.
+    pub fn parse(source: &str) -> Result<Self, MarkupError> {
+        Ok(Self {
+            root: parse::parse(source)?,
+        })
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, MarkupError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Build the elements, with tags, calls and modifiers looked up in
+    /// `registry`.
+    pub fn build(&self, registry: &Registry) -> Result<Box<dyn Element>, MarkupError> {
+        registry.build(&self.root)
+    }
+}
+
+/// Read, parse and build the markup in the file at `path` with the built-in
+/// registry.
+pub fn load(path: impl AsRef<Path>) -> Result<Box<dyn Element>, MarkupError> {
+    Markup::read(path)?.build(&Registry::default())
+}
+
+#[test]
+fn test_markup() {
+    use crate::{
+        color::{Color, ColorScheme},
+        composition::{HStack, HorizontalAlignment, Spacer, VStack},
+        elements::{Modifiers, Text},
+        fill::{ColorStop, Fill},
+        pal::headless::Context,
+        postprocessing::{background, padding},
+        runtime::WindowTemplate,
+        Unit::{Em, Pixels},
+    };
+
+    let gradient = Fill::linear_gradient(
+        180.0,
+        ColorStop::evenly(&[Color::SystemBlue, Color::SystemPurple]),
+    );
+    let source = r#"
+        // The same screen as below, read at runtime
+        WindowTemplate("main", title: "Markup") {
+            VStack(spacing: Pixels(4), alignment: HorizontalAlignment::Leading, text::color: SystemRed) {
+                Text("Hello"),
+                HStack {
+                    Spacer()
+                    Text("World", font: Font::new("Inter", 20, Bold, false))
+                }
+                .padding(all: Em(1.0), left: Some(Pixels(-2)))
+            }
+            .background(brand)
+        }
+    "#;
+    let registry = Registry::new().bind("brand", gradient.clone());
+    let loaded = Markup::parse(source).unwrap().build(&registry).unwrap();
+
+    let compiled = turubai_macros::turubai!(
+        WindowTemplate("main", title: "Markup") {
+            VStack(spacing: Pixels(4.0), alignment: HorizontalAlignment::Leading, text::color: Color::SystemRed) {
+                Text("Hello")
+                HStack {
+                    Spacer()
+                    Text("World", font: Some(crate::font::Font::new("Inter", 20, crate::font::FontWeight::Bold, false)))
+                }
+                .padding(all: Em(1.0), left: Some(Pixels(-2.0)))
+            }
+            .background(gradient)
+        }
+    );
+
+    let context = Context::new(ColorScheme::Light);
+    assert_eq!(
+        context.render(loaded, 400.0, 300.0).dump_layout(),
+        context.render(compiled, 400.0, 300.0).dump_layout(),
+    );
+
+    let error =
+        |source: &str| match Markup::parse(source).and_then(|markup| markup.build(&registry)) {
+            Ok(_) => panic!("{source} built"),
+            Err(err) => err.to_string(),
+        };
+    assert_eq!(
+        error("VStack {\n  Text(\"a\", colour: SystemRed)\n}"),
+        "could not build markup at 2:13: unknown modifier `text::colour`"
+    );
+    assert_eq!(
+        error("Text(12)"),
+        "could not build markup at 1:1: Text: argument 1: expected String, found `12`"
+    );
+    assert_eq!(
+        error("Text(\"a\").blur()"),
+        "could not build markup at 1:11: blur: missing argument 1"
+    );
+    assert_eq!(
+        error("Text(\"a\" 1)"),
+        "invalid markup at 1:10: expected `)`, found `1`"
+    );
+    assert_eq!(
+        error("Text(color: Accent, \"a\")"),
+        "invalid markup at 1:21: required arguments cannot come after optional arguments"
+    );
+    assert!(matches!(load("missing.tui"), Err(MarkupError::Io(_))));
+}
@@ -0,0 +1,433 @@
+use super::{MarkupError, Position, Value};
+
+/// `Tag(args) { children }.call(args)...`
+#[derive(Debug, Clone)]
+pub(super) struct Node {
+    pub tag: String,
+    pub arguments: Arguments,
+    pub children: Vec<Node>,
+    pub calls: Vec<Call>,
+    pub position: Position,
+}
+
+/// A postprocessing call such as `.padding(all: Em(1.0))`.
+#[derive(Debug, Clone)]
+pub(super) struct Call {
+    pub name: String,
+    pub arguments: Arguments,
+    pub position: Position,
+}
+
+/// The required arguments of a tag or call, then the optional ones.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Arguments {
+    pub required: Vec<Value>,
+    pub optional: Vec<Optional>,
+}
+
+/// `name: value` or `namespace::name: value`.
+#[derive(Debug, Clone)]
+pub(super) struct Optional {
+    pub namespace: Option<String>,
+    pub name: String,
+    pub value: Value,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    String(String),
+    /// `::`
+    PathSeparator,
+    Punct(char),
+    End,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => format!("`{ident}`"),
+            Token::Number(number) => format!("`{number}`"),
+            Token::String(string) => format!("{string:?}"),
+            Token::PathSeparator => "`::`".to_string(),
+            Token::Punct(c) => format!("`{c}`"),
+            Token::End => "the end".to_string(),
+        }
+    }
+}
+
+fn syntax(position: Position, message: impl Into<String>) -> MarkupError {
+    MarkupError::Syntax {
+        position,
+        message: message.into(),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Position)>, MarkupError> {
+    let mut tokens = vec![];
+    let mut chars = source.chars().peekable();
+    let mut position = Position { line: 1, column: 1 };
+    let advance = |position: &mut Position, c: char| {
+        if c == '\n' {
+            position.line += 1;
+            position.column = 1;
+        } else {
+            position.column += 1;
+        }
+    };
+
+    while let Some(&c) = chars.peek() {
+        let start = position;
+        if c.is_whitespace() {
+            chars.next();
+            advance(&mut position, c);
+        } else if c == '/' {
+            chars.next();
+            advance(&mut position, c);
+            if chars.peek() != Some(&'/') {
+                return Err(syntax(start, "unexpected `/`"));
+            }
+            // A comment runs to the end of the line
+            while let Some(&c) = chars.peek() {
+                if c == '\n' {
+                    break;
+                }
+                chars.next();
+                advance(&mut position, c);
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                ident.push(c);
+                chars.next();
+                advance(&mut position, c);
+            }
+            tokens.push((Token::Ident(ident), start));
+        } else if c.is_ascii_digit() {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                // A `.` only belongs to the number if a digit follows,
+                // so `1.padding()` isn't read as `1.` and `padding()`
+                let fraction = c == '.' && {
+                    let mut ahead = chars.clone();
+                    ahead.next();
+                    ahead.peek().is_some_and(|c| c.is_ascii_digit())
+                };
+                if !(c.is_ascii_digit() || c == '_' || fraction) {
+                    break;
+                }
+                if c != '_' {
+                    number.push(c);
+                }
+                chars.next();
+                advance(&mut position, c);
+            }
+            let number = number
+                .parse()
+                .map_err(|_| syntax(start, format!("invalid number `{number}`")))?;
+            tokens.push((Token::Number(number), start));
+        } else if c == '"' {
+            chars.next();
+            advance(&mut position, c);
+            let mut string = String::new();
+            loop {
+                let Some(c) = chars.next() else {
+                    return Err(syntax(start, "unterminated string"));
+                };
+                advance(&mut position, c);
+                match c {
+                    '"' => break,
+                    '\\' => {
+                        let escaped = chars.next();
+                        if let Some(escaped) = escaped {
+                            advance(&mut position, escaped);
+                        }
+                        string.push(match escaped {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some('"') => '"',
+                            Some('\\') => '\\',
+                            _ => return Err(syntax(start, "invalid escape in string")),
+                        });
+                    }
+                    c => string.push(c),
+                }
+            }
+            tokens.push((Token::String(string), start));
+        } else if c == ':' {
+            chars.next();
+            advance(&mut position, c);
+            if chars.peek() == Some(&':') {
+                chars.next();
+                advance(&mut position, ':');
+                tokens.push((Token::PathSeparator, start));
+            } else {
+                tokens.push((Token::Punct(':'), start));
+            }
+        } else if "(){}[],.-".contains(c) {
+            chars.next();
+            advance(&mut position, c);
+            tokens.push((Token::Punct(c), start));
+        } else {
+            return Err(syntax(start, format!("unexpected `{c}`")));
+        }
+    }
+    tokens.push((Token::End, position));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let index = (self.index + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.index].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.index].0.clone();
+        if token != Token::End {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if *self.peek() == Token::Punct(c) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), MarkupError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{c}`")))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> MarkupError {
+        syntax(
+            self.position(),
+            format!("expected {expected}, found {}", self.peek().describe()),
+        )
+    }
+
+    fn ident(&mut self) -> Result<String, MarkupError> {
+        match self.peek() {
+            Token::Ident(_) => match self.next() {
+                Token::Ident(ident) => Ok(ident),
+                _ => unreachable!(),
+            },
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    /// `a::b::c`
+    fn path(&mut self) -> Result<String, MarkupError> {
+        let mut path = self.ident()?;
+        while *self.peek() == Token::PathSeparator {
+            self.next();
+            path += "::";
+            path += &self.ident()?;
+        }
+        Ok(path)
+    }
+
+    /// Nodes separated by optional commas, up to `end`.
+    fn nodes(&mut self, end: Token) -> Result<Vec<Node>, MarkupError> {
+        let mut nodes = vec![];
+        loop {
+            while self.eat(',') {}
+            if *self.peek() == end {
+                return Ok(nodes);
+            }
+            nodes.push(self.node()?);
+        }
+    }
+
+    fn node(&mut self) -> Result<Node, MarkupError> {
+        let position = self.position();
+        let tag = self.path()?;
+        let tag = tag.rsplit("::").next().unwrap().to_string();
+        let arguments = if self.eat('(') {
+            self.arguments()?
+        } else {
+            Arguments::default()
+        };
+        let children = if self.eat('{') {
+            let children = self.nodes(Token::Punct('}'))?;
+            self.expect('}')?;
+            children
+        } else {
+            vec![]
+        };
+
+        let mut calls = vec![];
+        while self.eat('.') {
+            let position = self.position();
+            let name = self.ident()?;
+            self.expect('(')?;
+            calls.push(Call {
+                name,
+                arguments: self.arguments()?,
+                position,
+            });
+        }
+
+        Ok(Node {
+            tag,
+            arguments,
+            children,
+            calls,
+            position,
+        })
+    }
+
+    /// Everything after `(` up to and including `)`.
+    fn arguments(&mut self) -> Result<Arguments, MarkupError> {
+        let mut arguments = Arguments::default();
+        while !self.eat(')') {
+            let position = self.position();
+            let optional = matches!(self.peek(), Token::Ident(_))
+                && match self.peek_at(1) {
+                    Token::Punct(':') => true,
+                    Token::PathSeparator => {
+                        matches!(self.peek_at(2), Token::Ident(_))
+                            && *self.peek_at(3) == Token::Punct(':')
+                    }
+                    _ => false,
+                };
+
+            if optional {
+                let mut name = self.ident()?;
+                let mut namespace = None;
+                if *self.peek() == Token::PathSeparator {
+                    self.next();
+                    namespace = Some(name);
+                    name = self.ident()?;
+                }
+                self.expect(':')?;
+                arguments.optional.push(Optional {
+                    namespace,
+                    name,
+                    value: self.value()?,
+                    position,
+                });
+            } else if arguments.optional.is_empty() {
+                arguments.required.push(self.value()?);
+            } else {
+                return Err(syntax(
+                    position,
+                    "required arguments cannot come after optional arguments",
+                ));
+            }
+
+            if !self.eat(',') {
+                self.expect(')')?;
+                break;
+            }
+        }
+        Ok(arguments)
+    }
+
+    /// Values separated by commas, up to and including `end`.
+    fn values(&mut self, end: char) -> Result<Vec<Value>, MarkupError> {
+        let mut values = vec![];
+        while !self.eat(end) {
+            values.push(self.value()?);
+            if !self.eat(',') {
+                self.expect(end)?;
+                break;
+            }
+        }
+        Ok(values)
+    }
+
+    fn value(&mut self) -> Result<Value, MarkupError> {
+        match self.peek().clone() {
+            Token::String(string) => {
+                self.next();
+                Ok(Value::String(string))
+            }
+            Token::Number(number) => {
+                self.next();
+                Ok(Value::Number(number))
+            }
+            Token::Punct('-') => {
+                self.next();
+                match self.next() {
+                    Token::Number(number) => Ok(Value::Number(-number)),
+                    _ => Err(syntax(self.position(), "expected a number after `-`")),
+                }
+            }
+            Token::Punct('(') => {
+                self.next();
+                let mut values = self.values(')')?;
+                Ok(match values.len() {
+                    1 => values.pop().unwrap(),
+                    _ => Value::Tuple(values),
+                })
+            }
+            Token::Punct('[') => {
+                self.next();
+                Ok(Value::List(self.values(']')?))
+            }
+            Token::Ident(_) => {
+                let path = self.path()?;
+                if self.eat('(') {
+                    return Ok(Value::Call(path, self.values(')')?));
+                }
+                Ok(match path.as_str() {
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    _ => Value::Path(path),
+                })
+            }
+            _ => Err(self.unexpected("a value")),
+        }
+    }
+}
+
+/// Parse markup holding a single root element.
+pub(super) fn parse(source: &str) -> Result<Node, MarkupError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        index: 0,
+    };
+    let mut nodes = parser.nodes(Token::End)?;
+    match nodes.len() {
+        1 => Ok(nodes.pop().unwrap()),
+        0 => Err(syntax(parser.position(), "expected an element")),
+        _ => Err(syntax(nodes[1].position, "expected a single root element")),
+    }
+}
+
+/// Parse a single value, such as the argument of a modifier.
+pub(super) fn value(source: &str) -> Result<Value, MarkupError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        index: 0,
+    };
+    let value = parser.value()?;
+    match parser.peek() {
+        Token::End => Ok(value),
+        _ => Err(parser.unexpected("the end")),
+    }
+}
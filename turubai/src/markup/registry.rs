@@ -0,0 +1,607 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use crate::{
+    animation::Transition,
+    color::Color,
+    composition::{HStack, HorizontalAlignment, Spacer, VStack, VerticalAlignment},
+    elements::{
+        Capsule, Circle, ContentMode, Element, Image, Interpolation, Modifiers, ModifiersInner,
+        Path, Rectangle, RoundedRectangle, Text, TextDecoration,
+    },
+    fill::{Fill, UnitPoint},
+    font::{Font, TextStyle},
+    image::ImageSource,
+    postprocessing::{self, Background, BackgroundColor, Border, Clip, CornerRadius},
+    runtime::WindowTemplate,
+    shape::{self, Stroke},
+    stylesheet::Stylesheet,
+    theme::Theme,
+    Unit,
+};
+
+use super::{
+    parse::{self, Node},
+    FromValue, MarkupError, Position, Value,
+};
+
+type Children<'a> = &'a mut dyn FnMut(Modifiers) -> Vec<Box<dyn Element>>;
+type ElementFn = Arc<
+    dyn Fn(&mut Arguments, Modifiers, Children) -> Result<Box<dyn Element>, String> + Send + Sync,
+>;
+type PostprocessorFn = Arc<
+    dyn Fn(&mut Arguments, Box<dyn Element>, Modifiers) -> Result<Box<dyn Element>, String>
+        + Send
+        + Sync,
+>;
+type ModifierFn = Arc<dyn Fn(&mut ModifiersInner, &Value) -> Result<(), String> + Send + Sync>;
+
+/// The required arguments of a tag or postprocessing call, taken in order.
+pub struct Arguments {
+    values: VecDeque<Value>,
+    taken: usize,
+}
+
+impl Arguments {
+    pub fn new(values: Vec<Value>) -> Self {
+        Self {
+            values: values.into(),
+            taken: 0,
+        }
+    }
+
+    /// Convert the next argument.
+    pub fn take<T: FromValue>(&mut self) -> Result<T, String> {
+        self.taken += 1;
+        let value = self
+            .values
+            .pop_front()
+            .ok_or_else(|| format!("missing argument {}", self.taken))?;
+        value
+            .get()
+            .map_err(|err| format!("argument {}: {err}", self.taken))
+    }
+
+    /// How many arguments are left.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.values.front() {
+            Some(value) => Err(format!("unexpected argument {}: `{value}`", self.taken + 1)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What markup tags, postprocessing calls and optional arguments mean, so
+/// markup can be built into elements at runtime.
+///
+/// ```ignore
+/// let registry = Registry::new()
+///     .element("Badge", |arguments, modifiers, _children| {
+///         Ok(Box::new(Badge::new(arguments.take()?, modifiers)))
+///     })
+///     .modifier("badge::tint", |inner, tint: Color| inner.shape.fill = Some(tint.into()))
+///     .bind("brand", Fill::linear_gradient(..));
+/// ```
+#[derive(Clone)]
+pub struct Registry {
+    elements: HashMap<String, ElementFn>,
+    postprocessors: HashMap<String, PostprocessorFn>,
+    modifiers: HashMap<String, ModifierFn>,
+    values: HashMap<String, Value>,
+}
+
+impl Default for Registry {
+    /// The elements, postprocessing functions and modifiers of this crate.
+    /// Canvases need a closure and can't be written in markup.
+    fn default() -> Self {
+        builtins(Self::empty())
+    }
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry that knows nothing.
+    pub fn empty() -> Self {
+        Self {
+            elements: HashMap::new(),
+            postprocessors: HashMap::new(),
+            modifiers: HashMap::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// Make `Tag(...) { ... }` build an element. `children` builds the
+    /// tag's children with the modifiers they inherit.
+    pub fn element(
+        mut self,
+        tag: &str,
+        constructor: impl Fn(&mut Arguments, Modifiers, Children) -> Result<Box<dyn Element>, String>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.elements.insert(tag.to_string(), Arc::new(constructor));
+        self
+    }
+
+    /// Make `.name(...)` wrap or change the element before it.
+    pub fn postprocessor(
+        mut self,
+        name: &str,
+        postprocessor: impl Fn(&mut Arguments, Box<dyn Element>, Modifiers) -> Result<Box<dyn Element>, String>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.postprocessors
+            .insert(name.to_string(), Arc::new(postprocessor));
+        self
+    }
+
+    /// Make the optional argument `member::field: value` set a modifier.
+    /// Without a namespace, an argument goes to the member named after the
+    /// tag or call in snake case, as in compiled markup.
+    pub fn modifier<T: FromValue>(
+        mut self,
+        name: &str,
+        set: impl Fn(&mut ModifiersInner, T) + Send + Sync + 'static,
+    ) -> Self {
+        let set: ModifierFn = Arc::new(move |inner, value| {
+            set(inner, value.get()?);
+            Ok(())
+        });
+        self.modifiers.insert(name.to_string(), set);
+        self
+    }
+
+    /// Let markup refer to `value` by `name`, for values it can't spell
+    /// such as gradients, strokes, themes or image bytes.
+    pub fn bind<T: Send + Sync + 'static>(mut self, name: &str, value: T) -> Self {
+        self.values.insert(name.to_string(), Value::host(value));
+        self
+    }
+
+    /// Set the modifier `member::field` to `value`, as an optional
+    /// argument would.
+    pub fn set_modifier(
+        &self,
+        inner: &mut ModifiersInner,
+        name: &str,
+        value: &Value,
+    ) -> Result<(), String> {
+        let set = self
+            .modifiers
+            .get(name)
+            .ok_or_else(|| format!("unknown modifier `{name}`"))?;
+        set(inner, &self.resolve(value)).map_err(|message| format!("{name}: {message}"))
+    }
+
+    pub(super) fn build(&self, node: &Node) -> Result<Box<dyn Element>, MarkupError> {
+        self.build_node(node, &Modifiers::default())
+    }
+
+    fn build_node(
+        &self,
+        node: &Node,
+        modifiers: &Modifiers,
+    ) -> Result<Box<dyn Element>, MarkupError> {
+        let constructor = self
+            .elements
+            .get(&node.tag)
+            .ok_or_else(|| build_error(node.position, format!("unknown element `{}`", node.tag)))?;
+        let fork = self.fork(modifiers, &node.arguments, &to_snake_case(&node.tag))?;
+        let mut arguments = self.arguments(&node.arguments);
+
+        let mut child_error = None;
+        let element = constructor(&mut arguments, fork, &mut |modifiers: Modifiers| {
+            let mut children = vec![];
+            for child in &node.children {
+                match self.build_node(child, &modifiers) {
+                    Ok(child) => children.push(child),
+                    Err(err) => {
+                        child_error.get_or_insert(err);
+                    }
+                }
+            }
+            children
+        });
+        if let Some(err) = child_error {
+            return Err(err);
+        }
+        let mut element = element
+            .and_then(|element| arguments.finish().map(|()| element))
+            .map_err(|message| build_error(node.position, format!("{}: {message}", node.tag)))?;
+
+        for call in &node.calls {
+            let postprocessor = self.postprocessors.get(&call.name).ok_or_else(|| {
+                build_error(call.position, format!("unknown function `{}`", call.name))
+            })?;
+            let fork = self.fork(modifiers, &call.arguments, &call.name)?;
+            let mut arguments = self.arguments(&call.arguments);
+            element = postprocessor(&mut arguments, element, fork)
+                .and_then(|element| arguments.finish().map(|()| element))
+                .map_err(|message| {
+                    build_error(call.position, format!("{}: {message}", call.name))
+                })?;
+        }
+        Ok(element)
+    }
+
+    /// A fork of `modifiers` with the optional arguments applied.
+    fn fork(
+        &self,
+        modifiers: &Modifiers,
+        arguments: &parse::Arguments,
+        default_member: &str,
+    ) -> Result<Modifiers, MarkupError> {
+        let fork = modifiers.fork();
+        let mut inner = fork.lock().unwrap();
+        for optional in &arguments.optional {
+            let member = optional.namespace.as_deref().unwrap_or(default_member);
+            let name = format!("{member}::{}", optional.name);
+            self.set_modifier(&mut inner, &name, &optional.value)
+                .map_err(|message| build_error(optional.position, message))?;
+        }
+        std::mem::drop(inner);
+        Ok(fork)
+    }
+
+    fn arguments(&self, arguments: &parse::Arguments) -> Arguments {
+        Arguments::new(
+            arguments
+                .required
+                .iter()
+                .map(|value| self.resolve(value))
+                .collect(),
+        )
+    }
+
+    /// Replace names of bound values with the values.
+    fn resolve(&self, value: &Value) -> Value {
+        let resolve_all =
+            |values: &[Value]| values.iter().map(|value| self.resolve(value)).collect();
+        match value {
+            Value::Path(name) => self
+                .values
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| value.clone()),
+            Value::Call(path, arguments) => Value::Call(path.clone(), resolve_all(arguments)),
+            Value::Tuple(values) => Value::Tuple(resolve_all(values)),
+            Value::List(values) => Value::List(resolve_all(values)),
+            _ => value.clone(),
+        }
+    }
+}
+
+fn build_error(position: Position, message: String) -> MarkupError {
+    MarkupError::Build { position, message }
+}
+
+/// `WindowTemplate` as `window_template`, like compiled markup.
+fn to_snake_case(original: &str) -> String {
+    let mut output = String::new();
+    for (i, c) in original.chars().enumerate() {
+        if c.is_ascii_uppercase() && i != 0 {
+            output.push('_');
+        }
+        output.push(c.to_ascii_lowercase());
+    }
+    output
+}
+
+/// Build a wrapping element with the single child it needs.
+fn with_child(
+    tag: &str,
+    modifiers: &Modifiers,
+    children: Children,
+) -> Result<Vec<Box<dyn Element>>, String> {
+    let children = children(modifiers.clone());
+    match children.len() {
+        1 => Ok(children),
+        _ => Err(format!("{tag} needs exactly one child")),
+    }
+}
+
+/// Register optional arguments setting `member.field`.
+macro_rules! modifiers {
+    ($registry:ident, $member:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        $(let $registry = $registry.modifier(
+            concat!(stringify!($member), "::", stringify!($field)),
+            |inner, value: $ty| inner.$member.$field = value,
+        );)*
+    };
+}
+
+fn builtins(registry: Registry) -> Registry {
+    let registry = registry
+        .element("Text", |arguments, modifiers, _| {
+            Ok(Box::new(Text::new(&arguments.take::<String>()?, modifiers)))
+        })
+        .element("Image", |arguments, modifiers, _| {
+            Ok(Box::new(Image::new(
+                arguments.take::<ImageSource>()?,
+                modifiers,
+            )))
+        })
+        .element("Rectangle", |_, modifiers, _| {
+            Ok(Box::new(Rectangle::new(modifiers)))
+        })
+        .element("RoundedRectangle", |arguments, modifiers, _| {
+            Ok(Box::new(RoundedRectangle::new(
+                arguments.take()?,
+                modifiers,
+            )))
+        })
+        .element("Circle", |_, modifiers, _| {
+            Ok(Box::new(Circle::new(modifiers)))
+        })
+        .element("Capsule", |_, modifiers, _| {
+            Ok(Box::new(Capsule::new(modifiers)))
+        })
+        .element("Path", |arguments, modifiers, _| {
+            Ok(Box::new(Path::new(
+                arguments.take::<shape::Path>()?,
+                modifiers,
+            )))
+        })
+        .element("VStack", |_, modifiers, children| {
+            Ok(Box::new(VStack::turubai_new_with_0_args(
+                modifiers, children,
+            )))
+        })
+        .element("HStack", |_, modifiers, children| {
+            Ok(Box::new(HStack::turubai_new_with_0_args(
+                modifiers, children,
+            )))
+        })
+        .element("Spacer", |_, modifiers, children| {
+            Ok(Box::new(Spacer::turubai_new_with_0_args(
+                modifiers, children,
+            )))
+        })
+        .element("WindowTemplate", |arguments, modifiers, children| {
+            Ok(Box::new(match arguments.len() {
+                0 => WindowTemplate::turubai_new_with_0_args(modifiers, children),
+                _ => WindowTemplate::turubai_new_with_1_args(
+                    &arguments.take::<String>()?,
+                    modifiers,
+                    children,
+                ),
+            }))
+        })
+        .element("Background", |arguments, modifiers, children| {
+            let fill: Fill = arguments.take()?;
+            let children = with_child("Background", &modifiers, children)?;
+            Ok(Box::new(Background::turubai_new_with_1_args(
+                fill,
+                modifiers,
+                move |_| children,
+            )))
+        })
+        .element("BackgroundColor", |arguments, modifiers, children| {
+            let color: Color = arguments.take()?;
+            let children = with_child("BackgroundColor", &modifiers, children)?;
+            Ok(Box::new(BackgroundColor::turubai_new_with_1_args(
+                color,
+                modifiers,
+                move |_| children,
+            )))
+        })
+        .element("Border", |arguments, modifiers, children| {
+            let (width, color, style) = (arguments.take()?, arguments.take()?, arguments.take()?);
+            let children = with_child("Border", &modifiers, children)?;
+            Ok(Box::new(Border::turubai_new_with_3_args(
+                width,
+                color,
+                style,
+                modifiers,
+                move |_| children,
+            )))
+        })
+        .element("CornerRadius", |arguments, modifiers, children| {
+            let radius: f64 = arguments.take()?;
+            let children = with_child("CornerRadius", &modifiers, children)?;
+            Ok(Box::new(CornerRadius::turubai_new_with_1_args(
+                radius,
+                modifiers,
+                move |_| children,
+            )))
+        })
+        .element("Clip", |_, modifiers, children| {
+            let children = with_child("Clip", &modifiers, children)?;
+            Ok(Box::new(Clip::turubai_new_with_0_args(
+                modifiers,
+                move |_| children,
+            )))
+        });
+
+    let registry = registry
+        .postprocessor("background", |arguments, child, modifiers| {
+            Ok(Box::new(postprocessing::background(
+                arguments.take::<Fill>()?,
+                child,
+                modifiers,
+            )))
+        })
+        .postprocessor("background_color", |arguments, child, modifiers| {
+            Ok(Box::new(postprocessing::background_color(
+                arguments.take()?,
+                child,
+                modifiers,
+            )))
+        })
+        .postprocessor("border", |arguments, child, modifiers| {
+            let (width, color, style) = (arguments.take()?, arguments.take()?, arguments.take()?);
+            Ok(Box::new(postprocessing::border(
+                width, color, style, child, modifiers,
+            )))
+        })
+        .postprocessor("clip", |_, child, modifiers| {
+            Ok(Box::new(postprocessing::clip(child, modifiers)))
+        })
+        .postprocessor("corner_radius", |arguments, child, modifiers| {
+            Ok(Box::new(postprocessing::corner_radius(
+                arguments.take()?,
+                child,
+                modifiers,
+            )))
+        })
+        .postprocessor("corner_radii", |_, child, modifiers| {
+            Ok(Box::new(postprocessing::corner_radii(child, modifiers)))
+        })
+        .postprocessor("padding", |_, child, modifiers| {
+            Ok(Box::new(postprocessing::padding(child, modifiers)))
+        })
+        .postprocessor("fill", |arguments, child, modifiers| {
+            Ok(postprocessing::fill(
+                arguments.take::<Fill>()?,
+                child,
+                modifiers,
+            ))
+        })
+        .postprocessor("stroke", |arguments, child, modifiers| {
+            let (paint, width) = (arguments.take::<Fill>()?, arguments.take()?);
+            let dash: Vec<f64> = match arguments.is_empty() {
+                true => vec![],
+                false => arguments.take()?,
+            };
+            Ok(postprocessing::stroke(paint, width, dash, child, modifiers))
+        })
+        .postprocessor("opacity", |arguments, child, modifiers| {
+            Ok(postprocessing::opacity(arguments.take()?, child, modifiers))
+        })
+        .postprocessor("shadow", |arguments, child, modifiers| {
+            let (color, radius, offset) = (arguments.take()?, arguments.take()?, arguments.take()?);
+            Ok(postprocessing::shadow(
+                color, radius, offset, child, modifiers,
+            ))
+        })
+        .postprocessor("blur", |arguments, child, modifiers| {
+            Ok(postprocessing::blur(arguments.take()?, child, modifiers))
+        })
+        .postprocessor("offset", |arguments, child, modifiers| {
+            let (x, y) = (arguments.take()?, arguments.take()?);
+            Ok(postprocessing::offset(x, y, child, modifiers))
+        })
+        .postprocessor("scale", |arguments, child, modifiers| {
+            let (x, y, anchor) = (arguments.take()?, arguments.take()?, arguments.take()?);
+            Ok(postprocessing::scale(x, y, anchor, child, modifiers))
+        })
+        .postprocessor("rotate", |arguments, child, modifiers| {
+            let (angle, anchor): (f64, UnitPoint) = (arguments.take()?, arguments.take()?);
+            Ok(postprocessing::rotate(angle, anchor, child, modifiers))
+        })
+        .postprocessor("transition", |arguments, child, modifiers| {
+            Ok(postprocessing::transition(
+                arguments.take::<Transition>()?,
+                child,
+                modifiers,
+            ))
+        })
+        .postprocessor("id", |arguments, child, modifiers| {
+            Ok(postprocessing::id(
+                &arguments.take::<String>()?,
+                child,
+                modifiers,
+            ))
+        })
+        .postprocessor("key", |arguments, child, modifiers| {
+            Ok(postprocessing::key(
+                arguments.take::<String>()?,
+                child,
+                modifiers,
+            ))
+        })
+        .postprocessor("class", |arguments, child, modifiers| {
+            Ok(postprocessing::class(
+                &arguments.take::<String>()?,
+                child,
+                modifiers,
+            ))
+        });
+
+    modifiers!(
+        registry,
+        text {
+            font: Option<Font>,
+            style: TextStyle,
+            color: Color,
+            decoration: TextDecoration,
+        }
+    );
+    modifiers!(
+        registry,
+        image {
+            resizable: bool,
+            aspect_ratio: ContentMode,
+            interpolation: Interpolation,
+            alt: String,
+        }
+    );
+    modifiers!(registry, shape {
+        fill: Option<Fill>,
+        stroke: Option<Stroke>,
+    });
+    modifiers!(
+        registry,
+        v_stack {
+            spacing: Unit,
+            alignment: HorizontalAlignment,
+        }
+    );
+    modifiers!(
+        registry,
+        h_stack {
+            spacing: Unit,
+            alignment: VerticalAlignment,
+        }
+    );
+    modifiers!(registry, window_template {
+        title: &'static str,
+        theme: Option<Theme>,
+        stylesheet: Option<Stylesheet>,
+    });
+    modifiers!(
+        registry,
+        frame {
+            max_width: Unit,
+            max_height: Unit,
+            min_width: Unit,
+            min_height: Unit,
+            width: Unit,
+            height: Unit,
+        }
+    );
+    modifiers!(registry, padding {
+        all: Unit,
+        top_bottom: Option<Unit>,
+        left_right: Option<Unit>,
+        top: Option<Unit>,
+        bottom: Option<Unit>,
+        left: Option<Unit>,
+        right: Option<Unit>,
+    });
+    modifiers!(registry, border { radius: Option<f64> });
+    modifiers!(registry, corner_radii {
+        all: Option<f64>,
+        top_leading: Option<f64>,
+        top_trailing: Option<f64>,
+        bottom_trailing: Option<f64>,
+        bottom_leading: Option<f64>,
+    });
+    registry
+}
@@ -0,0 +1,477 @@
+use std::{any::Any, fmt, sync::Arc};
+
+use crate::{
+    animation::{Edge, Transition},
+    color::{Color, ColorScheme},
+    composition::{HorizontalAlignment, VerticalAlignment},
+    elements::{ContentMode, Interpolation, TextDecoration, TextLineStyle},
+    fill::{Fill, ImageTiling, UnitPoint},
+    font::{Font, FontWeight, TextStyle},
+    image::ImageSource,
+    postprocessing::BorderStyle,
+    shape::{Path, Stroke},
+    stylesheet::Stylesheet,
+    theme::{Space, Theme},
+    Unit,
+};
+
+use super::MarkupError;
+
+/// An argument as written in markup, converted to what an element expects
+/// with [`Value::get`].
+#[derive(Clone)]
+pub enum Value {
+    Bool(bool),
+    Number(f64),
+    String(String),
+    /// `Center`, `Color::SystemRed` or the name of a bound value
+    Path(String),
+    /// `Em(1.5)` or `Color::new(255, 67, 86)`
+    Call(String, Vec<Value>),
+    /// `(4.0, 2.0)`
+    Tuple(Vec<Value>),
+    /// `[4.0, 2.0]`
+    List(Vec<Value>),
+    /// A value of any type handed over by the application, see
+    /// [`Registry::bind`](super::Registry::bind)
+    Host(Arc<dyn Any + Send + Sync>),
+}
+
+impl Value {
+    /// Parse a value written like an argument in markup, e.g. `Em(1.0)`.
+    pub fn parse(source: &str) -> Result<Self, MarkupError> {
+        super::parse::value(source)
+    }
+
+    pub fn host<T: Send + Sync + 'static>(value: T) -> Self {
+        Value::Host(Arc::new(value))
+    }
+
+    /// Convert the value, or describe why it can't be.
+    pub fn get<T: FromValue>(&self) -> Result<T, String> {
+        if let Value::Host(host) = self {
+            if let Some(value) = host.downcast_ref::<T>() {
+                return Ok(value.clone());
+            }
+        }
+        T::from_value(self).ok_or_else(|| {
+            let type_name = std::any::type_name::<T>();
+            format!("expected {}, found `{self}`", short_type_name(type_name))
+        })
+    }
+
+    /// The variant named by a path such as `Center` or
+    /// `HorizontalAlignment::Center`, if the type matches `ty`.
+    pub fn variant(&self, ty: &str) -> Option<&str> {
+        let Value::Path(path) = self else {
+            return None;
+        };
+        last_segment(path, ty)
+    }
+
+    /// The function and arguments of a call such as `Em(1.0)` or
+    /// `Color::new(1, 2, 3)`, if the type matches `ty`.
+    pub fn call(&self, ty: &str) -> Option<(&str, &[Value])> {
+        let Value::Call(path, arguments) = self else {
+            return None;
+        };
+        Some((last_segment(path, ty)?, arguments))
+    }
+}
+
+/// The last segment of `path`, if the one before it (when there is one)
+/// is `ty`.
+fn last_segment<'a>(path: &'a str, ty: &str) -> Option<&'a str> {
+    let mut segments = path.rsplit("::");
+    let last = segments.next()?;
+    match segments.next() {
+        Some(segment) if segment != ty => None,
+        _ => Some(last),
+    }
+}
+
+/// `core::option::Option<turubai::Unit>` as `Option<Unit>`.
+fn short_type_name(type_name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in type_name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short += segment.rsplit("::").next().unwrap_or("");
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short += segment.rsplit("::").next().unwrap_or("");
+    short
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, values: &[Value]| {
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{value}")?;
+            }
+            Ok(())
+        };
+        match self {
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Number(value) => write!(f, "{value}"),
+            Value::String(value) => write!(f, "{value:?}"),
+            Value::Path(path) => f.write_str(path),
+            Value::Call(path, arguments) => {
+                write!(f, "{path}(")?;
+                list(f, arguments)?;
+                f.write_str(")")
+            }
+            Value::Tuple(values) => {
+                f.write_str("(")?;
+                list(f, values)?;
+                f.write_str(")")
+            }
+            Value::List(values) => {
+                f.write_str("[")?;
+                list(f, values)?;
+                f.write_str("]")
+            }
+            Value::Host(_) => f.write_str("<bound value>"),
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Value({self})")
+    }
+}
+
+/// Types markup values convert to. Values bound by the application are
+/// taken as they are if they have the right type; `from_value` handles
+/// what can be written in markup.
+pub trait FromValue: Clone + Send + Sync + 'static {
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+/// Enums written as `Variant` or `Type::Variant`.
+macro_rules! variants {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl FromValue for $ty {
+            fn from_value(value: &Value) -> Option<Self> {
+                match value.variant(stringify!($ty))? {
+                    $(stringify!($variant) => Some($ty::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/// Types that can only be bound by the application.
+macro_rules! bound_only {
+    ($($ty:ty),* $(,)?) => {
+        $(impl FromValue for $ty {
+            fn from_value(_value: &Value) -> Option<Self> {
+                None
+            }
+        })*
+    };
+}
+
+variants!(HorizontalAlignment {
+    Leading,
+    Center,
+    Trailing
+});
+variants!(VerticalAlignment {
+    Top,
+    Center,
+    Bottom
+});
+variants!(FontWeight {
+    ExtraBlack,
+    Black,
+    ExtraBold,
+    Bold,
+    SemiBold,
+    Medium,
+    Regular,
+    SemiLight,
+    Light,
+    ExtraLight,
+    Thin,
+});
+variants!(TextStyle {
+    Custom,
+    LargeTitle,
+    Title,
+    Title2,
+    Headline,
+    Subheadline,
+    Body,
+    Callout,
+    Footnote,
+    Caption,
+});
+variants!(TextLineStyle {
+    None,
+    Single,
+    Thick,
+    Double,
+    Dotted,
+    Dashed
+});
+variants!(ContentMode { Stretch, Fit, Fill });
+variants!(Interpolation { Nearest, Linear });
+variants!(BorderStyle {
+    Solid,
+    Dashed,
+    Dotted,
+    Double
+});
+variants!(ImageTiling { Stretch, Tile });
+variants!(Edge {
+    Top,
+    Leading,
+    Bottom,
+    Trailing
+});
+variants!(ColorScheme { Light, Dark });
+variants!(Space {
+    Xxs,
+    Xs,
+    Sm,
+    Md,
+    Lg,
+    Xl
+});
+
+bound_only!(TextDecoration, Stroke, Path, Theme, Stylesheet);
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: &Value) -> Option<Self> {
+        f64::from_value(value).map(|value| value as f32)
+    }
+}
+
+impl FromValue for u32 {
+    fn from_value(value: &Value) -> Option<Self> {
+        let value = f64::from_value(value)?;
+        (value.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&value)).then_some(value as u32)
+    }
+}
+
+impl FromValue for u8 {
+    fn from_value(value: &Value) -> Option<Self> {
+        u32::from_value(value)?.try_into().ok()
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Leaked, for window titles. A user interface has only a handful.
+impl FromValue for &'static str {
+    fn from_value(value: &Value) -> Option<Self> {
+        String::from_value(value).map(|value| &*Box::leak(value.into_boxed_str()))
+    }
+}
+
+/// `None`, `Some(value)` or just the value.
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Option<Self> {
+        if value.variant("Option") == Some("None") {
+            return Some(None);
+        }
+        match value.call("Option") {
+            Some(("Some", [value])) => value.get().ok().map(Some),
+            _ => value.get().ok().map(Some),
+        }
+    }
+}
+
+impl<A: FromValue, B: FromValue> FromValue for (A, B) {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Tuple(values) => match values.as_slice() {
+                [a, b] => Some((a.get().ok()?, b.get().ok()?)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::List(values) => values.iter().map(|value| value.get().ok()).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// `Auto`, `Pixels(4.0)`, `Percent(0.5)`, `Em(1.0)`, `Space(Md)` or a
+/// number of pixels.
+impl FromValue for Unit {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::Number(pixels) = value {
+            return Some(Unit::Pixels(*pixels));
+        }
+        if value.variant("Unit") == Some("Auto") {
+            return Some(Unit::Auto);
+        }
+        let (name, [argument]) = value.call("Unit")? else {
+            return None;
+        };
+        if name == "Space" {
+            return Some(Unit::Space(argument.get().ok()?));
+        }
+        let argument = argument.get().ok()?;
+        match name {
+            "Pixels" => Some(Unit::Pixels(argument)),
+            "Percent" => Some(Unit::Percent(argument)),
+            "Em" => Some(Unit::Em(argument)),
+            _ => None,
+        }
+    }
+}
+
+/// A named color such as `SystemRed` or `Color::Accent`, a CSS color
+/// string, `Color::new(r, g, b)`, `Color::new_with_alpha(r, g, b, a)` or
+/// `Color::parse("...")`.
+impl FromValue for Color {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::String(css) = value {
+            return Color::parse(css).ok();
+        }
+        if let Some(variant) = value.variant("Color") {
+            return Some(match variant {
+                "Text" => Color::Text,
+                "SystemRed" => Color::SystemRed,
+                "SystemOrange" => Color::SystemOrange,
+                "SystemYellow" => Color::SystemYellow,
+                "SystemGreen" => Color::SystemGreen,
+                "SystemBlue" => Color::SystemBlue,
+                "SystemIndigo" => Color::SystemIndigo,
+                "SystemPurple" => Color::SystemPurple,
+                "SystemPink" => Color::SystemPink,
+                "WindowBackground" => Color::WindowBackground,
+                "SecondaryText" => Color::SecondaryText,
+                "Separator" => Color::Separator,
+                "Accent" => Color::Accent,
+                "ControlFill" => Color::ControlFill,
+                _ => return None,
+            });
+        }
+        match value.call("Color")? {
+            ("new", [r, g, b]) => Some(Color::new(r.get().ok()?, g.get().ok()?, b.get().ok()?)),
+            ("new_with_alpha", [r, g, b, a]) => Some(Color::new_with_alpha(
+                r.get().ok()?,
+                g.get().ok()?,
+                b.get().ok()?,
+                a.get().ok()?,
+            )),
+            ("parse" | "from_hex", [css]) => Color::parse(&css.get::<String>().ok()?).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A color, or a bound fill such as a gradient.
+impl FromValue for Fill {
+    fn from_value(value: &Value) -> Option<Self> {
+        value.get::<Color>().ok().map(Fill::Color)
+    }
+}
+
+/// `Font::new("Inter", 16, FontWeight::Bold, false)`.
+impl FromValue for Font {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value.call("Font")? {
+            ("new", [family, size, weight, italic]) => Some(Font::new(
+                &family.get::<String>().ok()?,
+                size.get().ok()?,
+                weight.get().ok()?,
+                italic.get().ok()?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// A constant such as `UnitPoint::CENTER` or `UnitPoint::new(0.5, 0.0)`.
+impl FromValue for UnitPoint {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Some(variant) = value.variant("UnitPoint") {
+            return Some(match variant {
+                "TOP_LEADING" => UnitPoint::TOP_LEADING,
+                "TOP" => UnitPoint::TOP,
+                "TOP_TRAILING" => UnitPoint::TOP_TRAILING,
+                "LEADING" => UnitPoint::LEADING,
+                "CENTER" => UnitPoint::CENTER,
+                "TRAILING" => UnitPoint::TRAILING,
+                "BOTTOM_LEADING" => UnitPoint::BOTTOM_LEADING,
+                "BOTTOM" => UnitPoint::BOTTOM,
+                "BOTTOM_TRAILING" => UnitPoint::BOTTOM_TRAILING,
+                _ => return None,
+            });
+        }
+        match value.call("UnitPoint")? {
+            ("new", [x, y]) => Some(UnitPoint::new(x.get().ok()?, y.get().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+/// `Identity`, `Fade`, `Scale` or `Slide(Edge::Leading)`.
+impl FromValue for Transition {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Some(variant) = value.variant("Transition") {
+            return match variant {
+                "Identity" => Some(Transition::Identity),
+                "Fade" => Some(Transition::Fade),
+                "Scale" => Some(Transition::Scale),
+                _ => None,
+            };
+        }
+        match value.call("Transition")? {
+            ("Slide", [edge]) => Some(Transition::Slide(edge.get().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+/// A file path, or bound bytes or pixels.
+impl FromValue for ImageSource {
+    fn from_value(value: &Value) -> Option<Self> {
+        String::from_value(value).map(ImageSource::path)
+    }
+}
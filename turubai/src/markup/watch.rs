@@ -0,0 +1,226 @@
+use std::{io, path::PathBuf};
+
+pub(super) use platform::Watcher;
+
+/// Call `on_change` on a background thread whenever `path` is written,
+/// replaced or created, until the returned [`Watcher`] is dropped. Editors
+/// often save by writing a new file and renaming it over the old one, so
+/// the directory is watched rather than the file.
+pub(super) fn watch(path: PathBuf, on_change: impl Fn() + Send + 'static) -> io::Result<Watcher> {
+    platform::watch(path, on_change)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::{
+        ffi::{CString, OsStr},
+        io,
+        mem::size_of,
+        os::{raw::c_int, unix::ffi::OsStrExt},
+        path::{Path, PathBuf},
+        ptr,
+        thread::{self, JoinHandle},
+    };
+
+    /// Watching a file. Dropping it stops the thread and closes the
+    /// inotify instance.
+    pub(in crate::markup) struct Watcher {
+        inotify: c_int,
+        /// Written to when dropped, to wake the thread up
+        stop: c_int,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            let one = 1u64;
+            unsafe { libc::write(self.stop, (&one as *const u64).cast(), size_of::<u64>()) };
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            unsafe {
+                libc::close(self.inotify);
+                libc::close(self.stop);
+            }
+        }
+    }
+
+    pub(super) fn watch(
+        path: PathBuf,
+        on_change: impl Fn() + Send + 'static,
+    ) -> io::Result<Watcher> {
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?
+            .to_owned();
+        let directory = CString::new(directory.as_os_str().as_bytes())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        let inotify = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if inotify < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let stop = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC) };
+        if stop < 0 {
+            let err = io::Error::last_os_error();
+            unsafe { libc::close(inotify) };
+            return Err(err);
+        }
+        // Closes both if anything below fails
+        let mut watcher = Watcher {
+            inotify,
+            stop,
+            thread: None,
+        };
+        let mask = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_CREATE;
+        if unsafe { libc::inotify_add_watch(inotify, directory.as_ptr(), mask) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let thread = thread::Builder::new()
+            .name("turubai-watch".into())
+            .spawn(move || {
+                let mut fds = [inotify, stop].map(|fd| libc::pollfd {
+                    fd,
+                    events: libc::POLLIN,
+                    revents: 0,
+                });
+                let mut buffer = [0u8; 4096];
+                loop {
+                    if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) } < 0 {
+                        if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                            continue;
+                        }
+                        eprintln!(
+                            "[turubai] stopped watching {name:?}: {}",
+                            io::Error::last_os_error()
+                        );
+                        break;
+                    }
+                    if fds[1].revents != 0 {
+                        break;
+                    }
+                    if fds[0].revents & libc::POLLIN == 0 {
+                        continue;
+                    }
+
+                    let read =
+                        unsafe { libc::read(inotify, buffer.as_mut_ptr().cast(), buffer.len()) };
+                    if read < 0 {
+                        if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                            continue;
+                        }
+                        eprintln!(
+                            "[turubai] stopped watching {name:?}: {}",
+                            io::Error::last_os_error()
+                        );
+                        break;
+                    }
+
+                    // Events are a header followed by a NUL padded name
+                    let mut changed = false;
+                    let mut offset = 0;
+                    while offset + size_of::<libc::inotify_event>() <= read as usize {
+                        let event: libc::inotify_event =
+                            unsafe { ptr::read_unaligned(buffer[offset..].as_ptr().cast()) };
+                        let start = offset + size_of::<libc::inotify_event>();
+                        offset = start + event.len as usize;
+                        let event_name = buffer[start..offset.min(read as usize)]
+                            .split(|byte| *byte == 0)
+                            .next()
+                            .unwrap_or_default();
+                        changed |= OsStr::from_bytes(event_name) == name.as_os_str();
+                    }
+                    if changed {
+                        on_change();
+                    }
+                }
+            })?;
+        watcher.thread = Some(thread);
+        Ok(watcher)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use std::{
+        io,
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+    };
+
+    /// How often the file's modification time is checked.
+    const INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Watching a file. Dropping it stops the thread.
+    pub(in crate::markup) struct Watcher {
+        stop: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::SeqCst);
+            if let Some(thread) = self.thread.take() {
+                thread.thread().unpark();
+                let _ = thread.join();
+            }
+        }
+    }
+
+    pub(super) fn watch(
+        path: PathBuf,
+        on_change: impl Fn() + Send + 'static,
+    ) -> io::Result<Watcher> {
+        let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let mut last = modified(&path);
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let thread = thread::Builder::new()
+            .name("turubai-watch".into())
+            .spawn(move || loop {
+                thread::park_timeout(INTERVAL);
+                if stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                let current = modified(&path);
+                if current != last {
+                    last = current;
+                    on_change();
+                }
+            })?;
+        Ok(Watcher {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+#[test]
+fn test_watch() {
+    use std::{sync::mpsc, time::Duration};
+
+    let path = std::env::temp_dir().join(format!("turubai-watch-{}.tui", std::process::id()));
+    let (sender, changes) = mpsc::channel();
+    let watcher = watch(path.clone(), move || {
+        let _ = sender.send(());
+    })
+    .unwrap();
+    std::fs::write(&path, "Text(\"One\")").unwrap();
+    changes.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    // Dropping the watcher ends the thread, taking `on_change` with it
+    drop(watcher);
+    while changes.try_recv().is_ok() {}
+    assert_eq!(changes.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -1,4 +1,6 @@
-use cacao::color::Color;
+use cacao::color::{Color, Theme};
+
+use crate::color::Rgba;
 
 #[derive(Debug, Clone)]
 pub struct NativeColor {
@@ -37,17 +39,40 @@ impl NativeColor {
                 color: Color::SystemPink,
             },
 
+            crate::color::Color::WindowBackground => Self {
+                color: Color::MacOSWindowBackgroundColor,
+            },
+            crate::color::Color::SecondaryText => Self {
+                color: Color::LabelSecondary,
+            },
+            crate::color::Color::Separator => Self {
+                color: Color::Separator,
+            },
+            crate::color::Color::Accent => Self {
+                color: Color::SystemBlue,
+            },
+            crate::color::Color::ControlFill => Self {
+                color: Color::SystemFill,
+            },
+
+            crate::color::Color::Dynamic { light, dark } => Self {
+                color: Color::dynamic(move |style| match style.theme {
+                    Theme::Dark => Self::rgba(&dark),
+                    _ => Self::rgba(&light),
+                }),
+            },
+
             crate::color::Color::Custom { r, g, b, a } => Self {
-                color: Color::rgba(
-                    (r * 255.0) as u8,
-                    (g * 255.0) as u8,
-                    (b * 255.0) as u8,
-                    (a * 255.0) as u8,
-                ),
+                color: Self::rgba(&Rgba::new(r, g, b, a)),
             },
         }
     }
 
+    fn rgba(rgba: &Rgba) -> Color {
+        let [r, g, b, a] = rgba.to_u8();
+        Color::rgba(r, g, b, a)
+    }
+
     pub fn os_color(&self) -> Color {
         self.color.clone()
     }
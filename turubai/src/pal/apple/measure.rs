@@ -38,13 +38,29 @@ pub fn request_dimensions(
 
         NodeKind::Spacer => (Unit::Percent(1.0), Unit::Percent(1.0)),
 
-        NodeKind::Window { title: _ } | NodeKind::BackgroundColor { .. } => request_dimensions(
+        NodeKind::Window { title: _ }
+        | NodeKind::BackgroundColor { .. }
+        | NodeKind::Background { .. }
+        | NodeKind::CornerRadius { .. }
+        | NodeKind::Clip => request_dimensions(
             node.children.get(0).unwrap(),
             context.clone(),
             available_width,
             available_height,
         ),
 
+        NodeKind::Border { width, .. } => {
+            let (w, h) = request_dimensions(
+                node.children.get(0).unwrap(),
+                context.clone(),
+                available_width,
+                available_height,
+            );
+            let w = w.to_pixels(Some(available_width));
+            let h = h.to_pixels(Some(available_height));
+            (Unit::Pixels(w + 2.0 * width), Unit::Pixels(h + 2.0 * width))
+        }
+
         NodeKind::Padding {
             top,
             left,
@@ -106,7 +122,19 @@ pub fn request_minimum_dimensions(
         // Spacers have 0 minimum size
         NodeKind::Spacer => (0.0, 0.0),
 
-        NodeKind::Window { title: _ } | NodeKind::BackgroundColor { .. } => {
+        // Resizable images shrink like spacers, fixed ones keep their size
+        NodeKind::Image {
+            bitmap, resizable, ..
+        } => match (bitmap, resizable) {
+            (Some(bitmap), false) => bitmap.logical_size(),
+            _ => (0.0, 0.0),
+        },
+
+        NodeKind::Window { title: _ }
+        | NodeKind::BackgroundColor { .. }
+        | NodeKind::Background { .. }
+        | NodeKind::CornerRadius { .. }
+        | NodeKind::Clip => {
             request_minimum_dimensions(
                 node.children.get(0).unwrap(),
                 context.clone(),
@@ -114,6 +142,15 @@ pub fn request_minimum_dimensions(
             )
         }
 
+        NodeKind::Border { width, .. } => {
+            let (w, h) = request_minimum_dimensions(
+                node.children.get(0).unwrap(),
+                context.clone(),
+                available_width - 2.0 * width,
+            );
+            (w + 2.0 * width, h + 2.0 * width)
+        }
+
         NodeKind::Padding {
             top,
             left,
@@ -163,6 +200,11 @@ pub fn update_node_sizes(
             (true, true)
         }
 
+        // Fixed images are sized by their style already, resizable ones
+        // grow like spacers
+        NodeKind::Image { resizable, .. } => (*resizable, *resizable),
+        NodeKind::Shape { .. } | NodeKind::Canvas { .. } => (true, true),
+
         NodeKind::VStack { .. } => {
             let mut needs_full_height = false;
             let mut needs_full_width = false;
@@ -247,7 +289,11 @@ pub fn update_node_sizes(
             available_height,
         ),
 
-        NodeKind::BackgroundColor { .. } => {
+        NodeKind::BackgroundColor { .. }
+        | NodeKind::Background { .. }
+        | NodeKind::Border { .. }
+        | NodeKind::CornerRadius { .. }
+        | NodeKind::Clip => {
             // Background color wrapper: propagate child's size requirements
             let child = node
                 .children
@@ -8,7 +8,8 @@ use cacao::appkit::{window, App, AppDelegate};
 use cacao::core_foundation::bundle::{CFBundleGetIdentifier, CFBundleGetMainBundle};
 use cacao::core_graphics::display::{CGPoint, CGRect, CGSize};
 use cacao::layout::{Layout, LayoutConstraint};
-use cacao::objc::{msg_send, sel, sel_impl};
+use cacao::foundation::id;
+use cacao::objc::{class, msg_send, sel, sel_impl};
 use cacao::text::Label;
 use cacao::view::View;
 
@@ -30,6 +31,8 @@ use crate::pal::apple::measure::{
 use crate::pal::apple::stack::{render_h_stack, render_spacer, render_v_stack};
 use crate::pal::apple::text::render_text;
 use crate::pal::{apple, DynContext};
+use crate::environment::Environment;
+use crate::fill::Fill;
 use crate::shadow::{NodeKind, ShadowNode, ShadowTree};
 use crate::{Application, Backend, Unit};
 
@@ -91,6 +94,9 @@ impl Context {
             NodeKind::HStack { .. } => render_h_stack(node, tree, context.clone()),
             NodeKind::VStack { .. } => render_v_stack(node, tree, context.clone()),
             NodeKind::Spacer { .. } => render_spacer(),
+            // Images and shapes aren't drawn natively yet; keep their space
+            // so the rest of the layout matches the other backends
+            NodeKind::Image { .. } | NodeKind::Shape { .. } | NodeKind::Canvas { .. } => render_spacer(),
 
             NodeKind::BackgroundColor { color } => {
                 let child_node = node
@@ -111,6 +117,57 @@ impl Context {
                 }
             }
 
+            NodeKind::Background { fill } => {
+                let child_node = node
+                    .children
+                    .first()
+                    .expect("Background must have a child");
+                let child_view = Context::render_node(child_node, tree, context.clone());
+
+                let view = View::new();
+                // Gradients and images are not drawn natively yet; approximate
+                // them with their first color so the region stays visible.
+                let color = match fill {
+                    Fill::Color(color) => Some(*color),
+                    _ => fill.stops().and_then(|stops| stops.first()).map(|stop| stop.color),
+                };
+                if let Some(color) = color {
+                    let native_color = context.get_native_color(&color);
+                    view.set_background_color(native_color.os_color());
+                }
+                view.set_translates_autoresizing_mask_into_constraints(true);
+                view.add_subview(child_view.view());
+
+                NativeView::Container {
+                    view,
+                    _children: vec![child_view],
+                }
+            }
+
+            NodeKind::Border { .. } | NodeKind::CornerRadius { .. } | NodeKind::Clip => {
+                let child_node = node.children.first().expect("Border must have a child");
+                let child_view = Context::render_node(child_node, tree, context.clone());
+
+                let view = View::new();
+                // Borders are not stroked natively yet; the child keeps the
+                // inset the border takes up in layout.
+                if let NodeKind::CornerRadius { radii } = &node.kind {
+                    let radius = radii
+                        .top_leading
+                        .max(radii.top_trailing)
+                        .max(radii.bottom_trailing)
+                        .max(radii.bottom_leading);
+                    view.layer.set_corner_radius(radius);
+                }
+                view.set_translates_autoresizing_mask_into_constraints(true);
+                view.add_subview(child_view.view());
+
+                NativeView::Container {
+                    view,
+                    _children: vec![child_view],
+                }
+            }
+
             NodeKind::Padding { .. } => {
                 let child_node = node.children.first().expect("Padding must have a child");
                 let child_view = Context::render_node(child_node, tree, context.clone());
@@ -130,6 +187,16 @@ impl Context {
             }
         };
         view.view().set_frame(frame);
+        // Shadows, blur and transforms need a layer-backed view and aren't
+        // applied yet
+        if node.effects.opacity < 1.0 {
+            let alpha = node.effects.opacity as f64;
+            view.view()
+                .objc
+                .with_mut(|handle| unsafe {
+                    let _: () = msg_send![handle, setAlphaValue: alpha];
+                });
+        }
         view
     }
 
@@ -159,6 +226,18 @@ impl Context {
         window
     }
 
+    /// Device pixels per point of the main screen
+    fn backing_scale_factor(&self) -> f64 {
+        unsafe {
+            let screen: id = msg_send![class!(NSScreen), mainScreen];
+            if screen.is_null() {
+                1.0
+            } else {
+                msg_send![screen, backingScaleFactor]
+            }
+        }
+    }
+
     fn get_native_color(&self, color: &Color) -> Arc<NativeColor> {
         let mut colors = self.inner.native_colors.lock().unwrap();
         if let Some(color_weak) = colors.get(color) {
@@ -193,7 +272,11 @@ impl AppDelegate for Context {
     fn will_finish_launching(&self) {
         let window_element = self.inner.user_app.markup();
 
-        let mut shadow_tree = ShadowTree::new();
+        let environment = Environment::default()
+            .with_scale_factor(self.backing_scale_factor())
+            .with_theme(self.inner.user_app.theme())
+            .with_stylesheet(self.inner.user_app.stylesheet());
+        let mut shadow_tree = ShadowTree::with_environment(environment);
 
         let mut window_node = shadow_tree.create_node_from_element(window_element.as_ref());
 
@@ -1,10 +1,24 @@
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
+
 use gtk4::{
     gdk::Display,
-    prelude::{StyleContextExt, WidgetExt},
+    prelude::{IsA, StyleContextExt, WidgetExt},
     CssProvider,
 };
+use pango::prelude::{FontExt, FontFamilyExt, FontMapExt};
 
-use crate::{color::Color, elements::TextLineStyle};
+use crate::{
+    canvas::DrawCommand,
+    color::{Color, ColorScheme},
+    elements::{ContentMode, Interpolation, TextLineStyle},
+    fill::{linear_gradient_line, radial_gradient_radius, ColorStop, Fill, ImageTiling},
+    image::Bitmap,
+    shape::{Path, PathCommand, Shape, Stroke},
+    postprocessing::{AffineTransform, BorderStyle, CornerRadii, EffectModifiers},
+    font::{registry, Font, FontFamily, FontSource, FontWeight, ResolvedFont},
+    shadow::ShadowNode,
+    Unit,
+};
 
 pub fn conv_underline_style(style: &crate::elements::TextLineStyle) -> &'static str {
     match style {
@@ -28,41 +42,182 @@ pub fn conv_strike_through_style(style: &crate::elements::TextLineStyle) -> &'st
     }
 }
 
-pub fn conv_color_to_css(color: &Color) -> String {
-    match color {
-        Color::Custom { r, g, b, a } => {
-            format! {"rgba({},{},{},{})", r * 255.0, g * 255.0, b * 255.0, a}
+/// GTK size requests are whole logical pixels, while snapped layouts may sit
+/// on fractional logical pixels at scale factors above 1. Round up so that
+/// content is never clipped.
+pub fn conv_size_request(layout: &taffy::Layout) -> (i32, i32) {
+    (
+        layout.size.width.ceil() as i32,
+        layout.size.height.ceil() as i32,
+    )
+}
+
+/// Resolve `color` under `scheme` into a CSS `rgba()` value. System and
+/// semantic colors are resolved here rather than mapped to CSS names so that
+/// they follow the light/dark appearance.
+pub fn conv_color_to_css(color: &Color, scheme: ColorScheme) -> String {
+    let rgba = color.resolve(scheme);
+    format! {"rgba({},{},{},{})", rgba.r * 255.0, rgba.g * 255.0, rgba.b * 255.0, rgba.a}
+}
+
+/// The font's family list as a CSS `font-family` value. Named families are
+/// quoted, generic ones are left as keywords.
+pub fn conv_font_family_to_css(font: &Font) -> String {
+    font.families()
+        .iter()
+        .map(|family| match family {
+            FontFamily::Named(name) => format!("\"{}\"", name.replace('"', "\\\"")),
+            FontFamily::Generic(generic) => generic.css_name().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+thread_local! {
+    /// The families of each display's font map, listed once instead of for
+    /// every label. [`conv_register_fonts`] forgets the list of a font map
+    /// it adds to.
+    static INSTALLED_FAMILIES: RefCell<HashMap<pango::FontMap, Rc<[String]>>> =
+        RefCell::default();
+}
+
+fn installed_families(font_map: &pango::FontMap) -> Rc<[String]> {
+    INSTALLED_FAMILIES.with(|cache| {
+        cache
+            .borrow_mut()
+            .entry(font_map.clone())
+            .or_insert_with(|| {
+                font_map
+                    .list_families()
+                    .iter()
+                    .map(|family| family.name().to_string())
+                    .collect()
+            })
+            .clone()
+    })
+}
+
+/// Ask Pango which face `widget` would use for `font`.
+pub fn conv_resolve_font(widget: &impl IsA<gtk4::Widget>, font: &Font) -> ResolvedFont {
+    let pango_context = widget.pango_context();
+    let installed = pango_context
+        .font_map()
+        .map(|font_map| installed_families(&font_map))
+        .unwrap_or_else(|| Rc::from([]));
+
+    let mut description = pango::FontDescription::new();
+    description.set_family(
+        &font
+            .families()
+            .iter()
+            .map(|family| family.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    let family = pango_context
+        .load_font(&description)
+        .and_then(|loaded| loaded.describe().family().map(|family| family.to_string()))
+        .unwrap_or_default();
+
+    // Generic families always resolve to something through fontconfig.
+    let matched = font
+        .families()
+        .iter()
+        .position(|candidate| match candidate {
+            FontFamily::Named(name) => installed
+                .iter()
+                .any(|installed| installed.eq_ignore_ascii_case(name)),
+            FontFamily::Generic(_) => true,
+        });
+    let source = match matched.map(|position| &font.families()[position]) {
+        Some(FontFamily::Named(name))
+            if registry::registered_faces()
+                .iter()
+                .any(|face| face.family.eq_ignore_ascii_case(name)) =>
+        {
+            FontSource::Registered
         }
-        Color::SystemRed => "red".to_string(),
-        Color::SystemGreen => "green".to_string(),
-        Color::SystemBlue => "blue".to_string(),
-        Color::SystemYellow => "yellow".to_string(),
-        Color::SystemOrange => "orange".to_string(),
-        Color::SystemPurple => "purple".to_string(),
-        Color::SystemPink => "pink".to_string(),
-        Color::SystemIndigo => "indigo".to_string(),
-        Color::Text => "inherit".to_string(),
+        _ => FontSource::System,
+    };
+
+    ResolvedFont {
+        family,
+        matched,
+        source,
     }
 }
 
+/// Font files written out for Pango, removed when dropped.
+#[derive(Default)]
+pub struct TemporaryFonts(Vec<PathBuf>);
+
+impl Drop for TemporaryFonts {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Make every face registered through `Font::from_file`/`from_bytes` known
+/// to the widget's font map. Faces loaded from memory are written out first
+/// since Pango only adds fonts from files; the files are kept until the
+/// returned [`TemporaryFonts`] is dropped.
+pub fn conv_register_fonts(widget: &impl IsA<gtk4::Widget>) -> TemporaryFonts {
+    let mut temporary = TemporaryFonts::default();
+    let Some(font_map) = widget.pango_context().font_map() else {
+        return temporary;
+    };
+
+    let mut added = std::collections::HashSet::new();
+    for (i, face) in registry::registered_faces().iter().enumerate() {
+        let path = match &face.path {
+            Some(path) => path.clone(),
+            None => {
+                let dir = std::env::temp_dir().join("turubai-fonts");
+                let path = dir.join(format!("{}-{}.ttf", std::process::id(), i));
+                if let Err(err) = std::fs::create_dir_all(&dir)
+                    .and_then(|_| std::fs::write(&path, face.data.as_slice()))
+                {
+                    eprintln!("[turubai] could not write font '{}': {}", face.family, err);
+                    continue;
+                }
+                temporary.0.push(path.clone());
+                path
+            }
+        };
+
+        // Faces of one collection share a file.
+        if !added.insert(path.clone()) {
+            continue;
+        }
+        if let Err(err) = font_map.add_font_file(&path) {
+            eprintln!("[turubai] could not add font '{}': {}", path.display(), err);
+        }
+    }
+    INSTALLED_FAMILIES.with(|cache| cache.borrow_mut().remove(&font_map));
+    temporary
+}
+
 pub fn conv_create_text_class(
     font: &crate::font::Font,
     color: &Color,
     decoration: &crate::elements::TextDecoration,
+    scheme: ColorScheme,
 ) -> String {
     let mut properties = String::new();
-    let family = format! {"font-family: \"{}\";", font.name()};
+    let family = format! {"font-family: {};", conv_font_family_to_css(font)};
     let size = format! {"font-size: {}pt;", font.size()};
     let weight = format! {"font-weight: {};", font.weight() as u32};
     let style = format! {"font-style: {};", if font.is_italic() { "italic" } else { "normal" }};
-    let color = format!("color: {};", conv_color_to_css(color));
+    let color = format!("color: {};", conv_color_to_css(color, scheme));
 
     let mut decorations = String::new();
     let underline = conv_underline_style(&decoration.underline.style);
-    let underline_color = conv_color_to_css(&decoration.underline.color);
+    let underline_color = conv_color_to_css(&decoration.underline.color, scheme);
 
     let strikethrough = conv_strike_through_style(&decoration.strike_through.style);
-    let strikethrough_color = conv_color_to_css(&decoration.strike_through.color);
+    let strikethrough_color = conv_color_to_css(&decoration.strike_through.color, scheme);
 
     let underline = format!("{}", underline);
     decorations.push_str(&underline);
@@ -177,30 +332,11 @@ pub fn conv_create_text_class(
 //     (attrs, font_desc)
 // }
 
-pub fn conv_create_background_color_class(color: &Color) -> String {
-    let value = match color {
-        Color::Custom { r, g, b, a } => {
-            format!(
-                "rgba({}, {}, {}, {})",
-                r * 255.0,
-                g * 255.0,
-                b * 255.0,
-                a * 255.0
-            )
-        }
-        Color::SystemRed => "red".to_string(),
-        Color::SystemGreen => "green".to_string(),
-        Color::SystemBlue => "blue".to_string(),
-        Color::SystemYellow => "yellow".to_string(),
-        Color::SystemOrange => "orange".to_string(),
-        Color::SystemPurple => "purple".to_string(),
-        Color::SystemPink => "pink".to_string(),
-        Color::SystemIndigo => "indigo".to_string(),
-        Color::Text => "black".to_string(),
-    };
-
+/// Register a CSS class with the given declarations on the default display
+/// and return its (random) name.
+fn conv_register_class(properties: &str) -> String {
     let class = randomizer::Randomizer::ALPHABETICAL(8).string().unwrap();
-    let css = format!(".{} {{ background-color: {} }}", class, value);
+    let css = format!(".{} {{ {} }}", class, properties);
 
     let provider = CssProvider::new();
     provider.load_from_data(css.as_str());
@@ -212,3 +348,457 @@ pub fn conv_create_background_color_class(color: &Color) -> String {
     );
     class
 }
+
+pub fn conv_create_background_color_class(color: &Color, scheme: ColorScheme) -> String {
+    let value = conv_color_to_css(color, scheme);
+    conv_register_class(&format!("background-color: {}", value))
+}
+
+fn conv_stops_to_css(stops: &[ColorStop], scheme: ColorScheme) -> String {
+    stops
+        .iter()
+        .map(|stop| {
+            format!(
+                "{} {}%",
+                conv_color_to_css(&stop.color, scheme),
+                stop.offset * 100.0
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// CSS declarations that paint `fill` as a widget background.
+pub fn conv_fill_to_css(fill: &Fill, scheme: ColorScheme) -> String {
+    match fill {
+        Fill::Color(color) => format!("background-color: {};", conv_color_to_css(color, scheme)),
+        Fill::LinearGradient { angle, stops } => format!(
+            "background-image: linear-gradient({}deg, {});",
+            angle,
+            conv_stops_to_css(stops, scheme)
+        ),
+        Fill::RadialGradient {
+            center,
+            radius,
+            stops,
+        } => {
+            let shape = match radius {
+                Unit::Auto => "circle farthest-corner".to_string(),
+                Unit::Percent(v) => format!("ellipse {}% {}%", v * 100.0, v * 100.0),
+                _ => format!("circle {}px", radius.to_pixels(None)),
+            };
+            format!(
+                "background-image: radial-gradient({} at {}% {}%, {});",
+                shape,
+                center.x * 100.0,
+                center.y * 100.0,
+                conv_stops_to_css(stops, scheme)
+            )
+        }
+        Fill::ConicGradient {
+            center,
+            angle,
+            stops,
+        } => format!(
+            "background-image: conic-gradient(from {}deg at {}% {}%, {});",
+            angle,
+            center.x * 100.0,
+            center.y * 100.0,
+            conv_stops_to_css(stops, scheme)
+        ),
+        Fill::Image { path, tiling } => {
+            let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            let sizing = match tiling {
+                ImageTiling::Stretch => "background-size: 100% 100%; background-repeat: no-repeat;",
+                ImageTiling::Tile => "background-repeat: repeat;",
+            };
+            format!(
+                "background-image: url(\"file://{}\"); {}",
+                path.display(),
+                sizing
+            )
+        }
+    }
+}
+
+pub fn conv_create_background_class(fill: &Fill, scheme: ColorScheme) -> String {
+    conv_register_class(&conv_fill_to_css(fill, scheme))
+}
+
+pub fn conv_border_style(style: BorderStyle) -> &'static str {
+    match style {
+        BorderStyle::Solid => "solid",
+        BorderStyle::Dashed => "dashed",
+        BorderStyle::Dotted => "dotted",
+        BorderStyle::Double => "double",
+    }
+}
+
+/// `border-radius` with the corners in CSS order (top-left, top-right,
+/// bottom-right, bottom-left). Leading is left, as layout is left to right.
+pub fn conv_radii_to_css(radii: &CornerRadii) -> String {
+    format!(
+        "border-radius: {}px {}px {}px {}px;",
+        radii.top_leading, radii.top_trailing, radii.bottom_trailing, radii.bottom_leading
+    )
+}
+
+pub fn conv_create_border_class(
+    width: f64,
+    color: &Color,
+    style: BorderStyle,
+    radii: &CornerRadii,
+    scheme: ColorScheme,
+) -> String {
+    conv_register_class(&format!(
+        "border: {}px {} {}; {}",
+        width,
+        conv_border_style(style),
+        conv_color_to_css(color, scheme),
+        conv_radii_to_css(radii)
+    ))
+}
+
+pub fn conv_create_corner_radius_class(radii: &CornerRadii) -> String {
+    conv_register_class(&conv_radii_to_css(radii))
+}
+
+/// A `filter` class for a node's effects, `None` when it only
+/// changes opacity, which is set on the widget directly.
+pub fn conv_create_effects_class(effects: &EffectModifiers, scheme: ColorScheme) -> Option<String> {
+    // Shadows are cast by what is painted rather than the widget's box,
+    // after the blur, like the headless renderer does. Filters apply in
+    // order, so the first shadow goes last to end up at the bottom.
+    let mut filters = Vec::new();
+    if effects.blur > 0.0 {
+        filters.push(format!("blur({}px)", effects.blur));
+    }
+    filters.extend(effects.shadows.iter().rev().map(|shadow| {
+        format!(
+            "drop-shadow({}px {}px {}px {})",
+            shadow.offset.0,
+            shadow.offset.1,
+            shadow.radius,
+            conv_color_to_css(&shadow.color, scheme)
+        )
+    }));
+
+    (!filters.is_empty()).then(|| conv_register_class(&format!("filter: {};", filters.join(" "))))
+}
+
+/// Where a child of a `Fixed` goes: its layout position inside the
+/// parent's `border`, with its own transform applied first. GTK picks
+/// through child transforms, so hit testing follows along.
+pub fn conv_child_transform(node: &ShadowNode, layout: &taffy::Layout, border: &taffy::Rect<f32>) -> gtk4::gsk::Transform {
+    let transform = gtk4::gsk::Transform::new().translate(&gtk4::graphene::Point::new(
+        layout.location.x - border.left,
+        layout.location.y - border.top,
+    ));
+    if node.transform.is_identity() {
+        return transform;
+    }
+    let matrix = conv_affine_to_matrix(&node.transform.resolve(layout.size.width, layout.size.height));
+    transform.matrix(&matrix)
+}
+
+pub fn conv_affine_to_matrix(transform: &AffineTransform) -> gtk4::graphene::Matrix {
+    gtk4::graphene::Matrix::from_2d(
+        transform.a as f64,
+        transform.b as f64,
+        transform.c as f64,
+        transform.d as f64,
+        transform.e as f64,
+        transform.f as f64,
+    )
+}
+
+pub fn conv_bitmap_to_texture(bitmap: &Bitmap) -> gtk4::gdk::MemoryTexture {
+    let bytes = gtk4::glib::Bytes::from(bitmap.pixels());
+    gtk4::gdk::MemoryTexture::new(
+        bitmap.width() as i32,
+        bitmap.height() as i32,
+        gtk4::gdk::MemoryFormat::R8g8b8a8,
+        &bytes,
+        bitmap.width() as usize * 4,
+    )
+}
+
+pub fn conv_content_fit(mode: ContentMode) -> gtk4::ContentFit {
+    match mode {
+        ContentMode::Stretch => gtk4::ContentFit::Fill,
+        ContentMode::Fit => gtk4::ContentFit::Contain,
+        ContentMode::Fill => gtk4::ContentFit::Cover,
+    }
+}
+
+/// Trace `path` on `cr` as a new path.
+pub fn conv_cairo_path(cr: &gtk4::cairo::Context, path: &Path) {
+    cr.new_path();
+    let mut current = (0.0, 0.0);
+    for command in path.commands() {
+        match *command {
+            PathCommand::MoveTo { x, y } => {
+                cr.move_to(x, y);
+                current = (x, y);
+            }
+            PathCommand::LineTo { x, y } => {
+                cr.line_to(x, y);
+                current = (x, y);
+            }
+            // Cairo has no quadratic curves, raise them to cubics
+            PathCommand::QuadTo { x1, y1, x, y } => {
+                let (x0, y0) = current;
+                cr.curve_to(
+                    x0 + 2.0 / 3.0 * (x1 - x0),
+                    y0 + 2.0 / 3.0 * (y1 - y0),
+                    x + 2.0 / 3.0 * (x1 - x),
+                    y + 2.0 / 3.0 * (y1 - y),
+                    x,
+                    y,
+                );
+                current = (x, y);
+            }
+            PathCommand::CubicTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                cr.curve_to(x1, y1, x2, y2, x, y);
+                current = (x, y);
+            }
+            PathCommand::Close => {
+                cr.close_path();
+                if let Ok(point) = cr.current_point() {
+                    current = point;
+                }
+            }
+        }
+    }
+}
+
+/// Make `fill` the source of `cr` for an area of `width` x `height`.
+/// Conic gradients and images are approximated by their first color.
+pub fn conv_cairo_source(
+    cr: &gtk4::cairo::Context,
+    fill: &Fill,
+    width: f64,
+    height: f64,
+    scheme: ColorScheme,
+) {
+    let add_stops = |gradient: &gtk4::cairo::Gradient, stops: &[ColorStop]| {
+        for stop in stops {
+            let rgba = stop.color.resolve(scheme);
+            gradient.add_color_stop_rgba(
+                stop.offset as f64,
+                rgba.r as f64,
+                rgba.g as f64,
+                rgba.b as f64,
+                rgba.a as f64,
+            );
+        }
+    };
+    let result = match fill {
+        Fill::LinearGradient { angle, stops } => {
+            let ((x0, y0), (x1, y1)) = linear_gradient_line(*angle, width, height);
+            let gradient = gtk4::cairo::LinearGradient::new(x0, y0, x1, y1);
+            add_stops(&gradient, stops);
+            cr.set_source(&gradient)
+        }
+        Fill::RadialGradient {
+            center,
+            radius,
+            stops,
+        } => {
+            let (cx, cy) = (center.x as f64 * width, center.y as f64 * height);
+            let radius = radial_gradient_radius(*center, *radius, width, height);
+            let gradient = gtk4::cairo::RadialGradient::new(cx, cy, 0.0, cx, cy, radius);
+            add_stops(&gradient, stops);
+            cr.set_source(&gradient)
+        }
+        fill => {
+            let color = match fill {
+                Fill::Color(color) => Some(*color),
+                _ => fill.stops().and_then(|stops| stops.first()).map(|stop| stop.color),
+            };
+            let rgba = color.map(|color| color.resolve(scheme)).unwrap_or_default();
+            cr.set_source_rgba(rgba.r as f64, rgba.g as f64, rgba.b as f64, rgba.a as f64);
+            Ok(())
+        }
+    };
+    if let Err(err) = result {
+        eprintln!("[turubai] can't paint {fill:?}: {err}");
+    }
+}
+
+/// Paint a shape filling `width` x `height`.
+pub fn conv_draw_shape(
+    cr: &gtk4::cairo::Context,
+    shape: &Shape,
+    fill: Option<&Fill>,
+    stroke: Option<&Stroke>,
+    width: f64,
+    height: f64,
+    scheme: ColorScheme,
+) {
+    conv_cairo_path(cr, &shape.path(width, height));
+    if let Some(fill) = fill {
+        conv_cairo_source(cr, fill, width, height, scheme);
+        let _ = cr.fill_preserve();
+    }
+    if let Some(stroke) = stroke.filter(|stroke| stroke.width > 0.0) {
+        conv_cairo_source(cr, &stroke.paint, width, height, scheme);
+        cr.set_line_width(stroke.width);
+        cr.set_dash(&stroke.dash, 0.0);
+        let _ = cr.stroke_preserve();
+    }
+    cr.new_path();
+}
+
+/// Replay what a canvas recorded. Text is set with cairo's simple text
+/// API in the face Pango picks for the font, on a single line.
+pub fn conv_draw_commands(
+    widget: &impl IsA<gtk4::Widget>,
+    cr: &gtk4::cairo::Context,
+    commands: &[DrawCommand],
+    scheme: ColorScheme,
+) {
+    // Fills and strokes are painted in the coordinates of their path's
+    // bounds, so gradients span the path like they span a shape
+    let paint = |path: &Path, f: &dyn Fn(f64, f64)| {
+        let Some((x, y, width, height)) = path.bounds() else {
+            return;
+        };
+        let _ = cr.save();
+        cr.translate(x, y);
+        conv_cairo_path(
+            cr,
+            &path.transformed(&AffineTransform::translation(-x as f32, -y as f32)),
+        );
+        f(width, height);
+        cr.new_path();
+        let _ = cr.restore();
+    };
+
+    for command in commands {
+        match command {
+            DrawCommand::Save => {
+                let _ = cr.save();
+            }
+            DrawCommand::Restore => {
+                let _ = cr.restore();
+            }
+            DrawCommand::Transform(transform) => {
+                cr.transform(gtk4::cairo::Matrix::new(
+                    transform.a as f64,
+                    transform.b as f64,
+                    transform.c as f64,
+                    transform.d as f64,
+                    transform.e as f64,
+                    transform.f as f64,
+                ));
+            }
+            DrawCommand::Clip(path) => {
+                conv_cairo_path(cr, path);
+                cr.clip();
+            }
+            DrawCommand::Fill { path, paint: fill } => paint(path, &|width, height| {
+                conv_cairo_source(cr, fill, width, height, scheme);
+                let _ = cr.fill_preserve();
+            }),
+            DrawCommand::Stroke { path, stroke } => {
+                if stroke.width > 0.0 {
+                    paint(path, &|width, height| {
+                        conv_cairo_source(cr, &stroke.paint, width, height, scheme);
+                        cr.set_line_width(stroke.width);
+                        cr.set_dash(&stroke.dash, 0.0);
+                        let _ = cr.stroke_preserve();
+                    })
+                }
+            }
+            DrawCommand::Text {
+                content,
+                font,
+                color,
+                origin: (x, y),
+            } => {
+                let family = conv_resolve_font(widget, font).family;
+                let slant = if font.is_italic() {
+                    gtk4::cairo::FontSlant::Italic
+                } else {
+                    gtk4::cairo::FontSlant::Normal
+                };
+                let weight = if font.weight() >= FontWeight::SemiBold {
+                    gtk4::cairo::FontWeight::Bold
+                } else {
+                    gtk4::cairo::FontWeight::Normal
+                };
+                cr.select_font_face(&family, slant, weight);
+                // Font sizes are in points, like in CSS
+                cr.set_font_size(font.size() as f64 * 96.0 / 72.0);
+                let ascent = cr.font_extents().map(|extents| extents.ascent()).unwrap_or_default();
+                let rgba = color.resolve(scheme);
+                cr.set_source_rgba(rgba.r as f64, rgba.g as f64, rgba.b as f64, rgba.a as f64);
+                cr.move_to(*x, y + ascent);
+                let _ = cr.show_text(content);
+                cr.new_path();
+            }
+            DrawCommand::Image {
+                bitmap,
+                rect: (x, y, width, height),
+                interpolation,
+            } => {
+                if bitmap.width() == 0 || bitmap.height() == 0 {
+                    continue;
+                }
+                let Some(surface) = conv_bitmap_to_surface(bitmap) else {
+                    continue;
+                };
+                let _ = cr.save();
+                cr.rectangle(*x, *y, *width, *height);
+                cr.clip();
+                cr.translate(*x, *y);
+                cr.scale(
+                    width / bitmap.width() as f64,
+                    height / bitmap.height() as f64,
+                );
+                if cr.set_source_surface(&surface, 0.0, 0.0).is_ok() {
+                    cr.source().set_filter(match interpolation {
+                        Interpolation::Nearest => gtk4::cairo::Filter::Nearest,
+                        Interpolation::Linear => gtk4::cairo::Filter::Good,
+                    });
+                    let _ = cr.paint();
+                }
+                let _ = cr.restore();
+            }
+        }
+    }
+}
+
+/// Cairo wants premultiplied, native-endian ARGB.
+fn conv_bitmap_to_surface(bitmap: &Bitmap) -> Option<gtk4::cairo::ImageSurface> {
+    let (width, height) = (bitmap.width() as i32, bitmap.height() as i32);
+    let mut surface =
+        gtk4::cairo::ImageSurface::create(gtk4::cairo::Format::ARgb32, width, height).ok()?;
+    let stride = surface.stride() as usize;
+    {
+        let mut data = surface.data().ok()?;
+        for (row, pixels) in bitmap.pixels().chunks_exact(width as usize * 4).enumerate() {
+            for (column, pixel) in pixels.chunks_exact(4).enumerate() {
+                let alpha = pixel[3] as u32;
+                let premultiply = |channel: u8| ((channel as u32 * alpha + 127) / 255) as u8;
+                let argb = u32::from_be_bytes([
+                    pixel[3],
+                    premultiply(pixel[0]),
+                    premultiply(pixel[1]),
+                    premultiply(pixel[2]),
+                ]);
+                let offset = row * stride + column * 4;
+                data[offset..offset + 4].copy_from_slice(&argb.to_ne_bytes());
+            }
+        }
+    }
+    Some(surface)
+}
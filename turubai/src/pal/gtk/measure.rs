@@ -20,7 +20,8 @@ pub fn request_dimensions(
             decoration,
         } => {
             let label = gtk4::Label::new(Some(content.as_str()));
-            let class = conv::conv_create_text_class(&font, &color, &decoration);
+            let class =
+                conv::conv_create_text_class(&font, &color, &decoration, context.color_scheme);
             label.set_css_classes(&[&class]);
 
             let (_, natural_width, _, _) = label.measure(gtk4::Orientation::Horizontal, -1);
@@ -1,5 +1,6 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::time::Duration;
 
 use gtk4::{
     gio::prelude::{ApplicationExt, ApplicationExtManual},
@@ -8,8 +9,12 @@ use gtk4::{
 };
 
 use crate::{
+    animation::Animator,
+    color::ColorScheme,
+    environment::Environment,
     pal::DynContext,
-    shadow::{NodeKind, ShadowNode, ShadowTree},
+    shadow::{debug_layout_from_env, NodeKind, ShadowNode, ShadowTree},
+    state,
     Application, Backend,
 };
 
@@ -38,6 +43,10 @@ enum NativeWidget {
     Text { label: gtk4::Label },
     /// A spacer (flexible empty space)
     Spacer { widget: gtk4::Box },
+    /// An image
+    Picture { picture: gtk4::Picture },
+    /// A shape or canvas drawn with cairo
+    Drawing { area: gtk4::DrawingArea },
 }
 
 impl NativeWidget {
@@ -47,16 +56,22 @@ impl NativeWidget {
             NativeWidget::Container { container, .. } => container.clone().into(),
             NativeWidget::Text { label } => label.clone().into(),
             NativeWidget::Spacer { widget } => widget.clone().into(),
+            NativeWidget::Picture { picture } => picture.clone().into(),
+            NativeWidget::Drawing { area } => area.clone().into(),
         }
     }
 
     /// Recursively update positions and sizes from the shadow tree layout.
     /// This is called after layout recomputation (e.g. on resize).
     fn update_frames(&self, node: &ShadowNode, tree: &ShadowTree) {
+        // Opacity animates, so it is kept up to date here rather than only
+        // set when the widget is created
+        self.widget().set_opacity(node.effects.opacity as f64);
         match self {
             NativeWidget::Text { label } => {
                 if let Some(layout) = tree.get_layout(node.taffy_id) {
-                    label.set_size_request(layout.size.width as i32, layout.size.height as i32);
+                    let (width, height) = conv::conv_size_request(layout);
+                    label.set_size_request(width, height);
                 }
             }
             NativeWidget::Container {
@@ -66,18 +81,23 @@ impl NativeWidget {
                 // Set the size request for the container itself so that
                 // its background color (if any) is visible and it can
                 // properly contain its children.
+                // A CSS border sits outside the Fixed's content box, so
+                // both the size request and the children's positions are
+                // relative to the inside of the border.
+                let mut border = taffy::Rect::zero();
                 if let Some(layout) = tree.get_layout(node.taffy_id) {
-                    container.set_size_request(layout.size.width as i32, layout.size.height as i32);
+                    border = layout.border;
+                    let (width, height) = conv::conv_size_request(layout);
+                    container.set_size_request(
+                        width - (border.left + border.right).ceil() as i32,
+                        height - (border.top + border.bottom).ceil() as i32,
+                    );
                 }
 
                 for (child_widget, child_node) in children.iter().zip(node.children.iter()) {
                     // Reposition child within the Fixed container
                     if let Some(child_layout) = tree.get_layout(child_node.taffy_id) {
-                        let transform =
-                            gtk4::gsk::Transform::new().translate(&gtk4::graphene::Point::new(
-                                child_layout.location.x,
-                                child_layout.location.y,
-                            ));
+                        let transform = conv::conv_child_transform(child_node, child_layout, &border);
                         container.set_child_transform(&child_widget.widget(), Some(&transform));
                     }
 
@@ -87,16 +107,230 @@ impl NativeWidget {
             }
             NativeWidget::Spacer { widget } => {
                 if let Some(layout) = tree.get_layout(node.taffy_id) {
-                    widget.set_size_request(layout.size.width as i32, layout.size.height as i32);
+                    let (width, height) = conv::conv_size_request(layout);
+                    widget.set_size_request(width, height);
+                }
+            }
+            NativeWidget::Picture { picture } => {
+                if let Some(layout) = tree.get_layout(node.taffy_id) {
+                    let (width, height) = conv::conv_size_request(layout);
+                    picture.set_size_request(width, height);
+                }
+            }
+            NativeWidget::Drawing { area } => {
+                if let Some(layout) = tree.get_layout(node.taffy_id) {
+                    let (width, height) = conv::conv_size_request(layout);
+                    area.set_size_request(width, height);
                 }
+                // Drawn from the node, so animated colors and canvases
+                // reading changed state show up too
+                set_draw_func(area, node, tree.environment());
             }
         }
     }
+
+    /// The widget at `path`, a list of child indices starting here.
+    fn find(&self, path: &[usize]) -> Option<&NativeWidget> {
+        match (path.split_first(), self) {
+            (None, _) => Some(self),
+            (Some((index, rest)), NativeWidget::Container { children, .. }) => {
+                children.get(*index)?.find(rest)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Draw the shape or canvas of `node` into `area` from now on.
+fn set_draw_func(area: &gtk4::DrawingArea, node: &ShadowNode, environment: &Environment) {
+    let scheme = environment.color_scheme();
+    match node.kind.clone() {
+        NodeKind::Shape {
+            shape,
+            fill,
+            stroke,
+        } => area.set_draw_func(move |_, cr, width, height| {
+            conv::conv_draw_shape(
+                cr,
+                &shape,
+                fill.as_ref(),
+                stroke.as_ref(),
+                width as f64,
+                height as f64,
+                scheme,
+            );
+        }),
+        NodeKind::Canvas { draw } => {
+            let environment = environment.clone();
+            area.set_draw_func(move |area, cr, width, height| {
+                let commands = draw.record(width as f64, height as f64, &environment);
+                conv::conv_draw_commands(area, cr, &commands, scheme);
+            });
+        }
+        _ => {}
+    }
+}
+
+/// An area over the window drawing the layout overlay of the shadow tree,
+/// labelling the node under the pointer over `target`.
+fn debug_layout_area(
+    target: &gtk4::Overlay,
+    shadow_tree: &Rc<RefCell<ShadowTree>>,
+    root_node: &Rc<RefCell<ShadowNode>>,
+) -> gtk4::DrawingArea {
+    let area = gtk4::DrawingArea::new();
+    area.set_hexpand(true);
+    area.set_vexpand(true);
+    area.set_can_target(false);
+
+    let pointer = Rc::new(Cell::new(None));
+    let motion = gtk4::EventControllerMotion::new();
+    {
+        let pointer = pointer.clone();
+        let area = area.clone();
+        motion.connect_motion(move |_, x, y| {
+            pointer.set(Some((x as f32, y as f32)));
+            area.queue_draw();
+        });
+    }
+    {
+        let pointer = pointer.clone();
+        let area = area.clone();
+        motion.connect_leave(move |_| {
+            pointer.set(None);
+            area.queue_draw();
+        });
+    }
+    target.add_controller(motion);
+
+    let shadow_tree = shadow_tree.clone();
+    let root_node = root_node.clone();
+    area.set_draw_func(move |area, cr, _, _| {
+        let shadow_tree = shadow_tree.borrow();
+        let commands = shadow_tree.debug_overlay(&root_node.borrow(), pointer.get());
+        conv::conv_draw_commands(area, cr, &commands, shadow_tree.environment().color_scheme());
+    });
+    area
+}
+
+/// An area over the window outlining the node highlighted in the
+/// inspector.
+#[cfg(all(feature = "inspector", unix))]
+fn highlight_area(
+    inspector: &Rc<RefCell<Option<crate::inspector::Inspector>>>,
+    shadow_tree: &Rc<RefCell<ShadowTree>>,
+    root_node: &Rc<RefCell<ShadowNode>>,
+) -> gtk4::DrawingArea {
+    let area = gtk4::DrawingArea::new();
+    area.set_hexpand(true);
+    area.set_vexpand(true);
+    area.set_can_target(false);
+
+    let inspector = inspector.clone();
+    let shadow_tree = shadow_tree.clone();
+    let root_node = root_node.clone();
+    area.set_draw_func(move |_, cr, _, _| {
+        let inspector = inspector.borrow();
+        let Some((x, y, width, height)) = inspector
+            .as_ref()
+            .and_then(|inspector| inspector.highlight_frame(&shadow_tree.borrow(), &root_node.borrow()))
+        else {
+            return;
+        };
+        cr.rectangle(x as f64, y as f64, width as f64, height as f64);
+        cr.set_source_rgba(0.26, 0.52, 0.96, 0.25);
+        let _ = cr.fill_preserve();
+        cr.set_source_rgba(0.26, 0.52, 0.96, 0.9);
+        cr.set_line_width(1.0);
+        let _ = cr.stroke();
+    });
+    area
+}
+
+/// The widget of a node an animated change removed, shown in its former
+/// parent until its removal transition is over.
+struct Ghost {
+    removal: u64,
+    widget: NativeWidget,
+    parent: Fixed,
+}
+
+impl Ghost {
+    fn render(context: &Context, root_widget: &NativeWidget, animator: &Animator) -> Vec<Ghost> {
+        animator
+            .removals()
+            .iter()
+            .filter_map(|removal| match root_widget.find(&removal.parent) {
+                Some(NativeWidget::Container { container, .. }) => {
+                    let widget = context.render_node(&removal.node);
+                    container.put(&widget.widget(), 0.0, 0.0);
+                    Some(Ghost {
+                        removal: removal.id,
+                        widget,
+                        parent: container.clone(),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Present the ghost's removal, or take the widget away once it is
+    /// done. Returns whether the ghost is still around.
+    fn update(&self, animator: &Animator) -> bool {
+        let Some(removal) = animator.removals().iter().find(|r| r.id == self.removal) else {
+            self.parent.remove(&self.widget.widget());
+            return false;
+        };
+        self.widget.update_frames(&removal.node, &removal.tree);
+        if let Some(layout) = removal.tree.get_layout(removal.node.taffy_id) {
+            let transform = conv::conv_child_transform(&removal.node, layout, &taffy::Rect::zero());
+            self.parent
+                .set_child_transform(&self.widget.widget(), Some(&transform));
+        }
+        true
+    }
 }
 
-pub struct Context {}
+pub struct Context {
+    /// Device pixels per logical pixel of the window being laid out
+    scale_factor: f64,
+    /// Appearance requested by the desktop through `gtk4::Settings`
+    color_scheme: ColorScheme,
+}
 
 impl Context {
+    fn new(scale_factor: f64) -> Self {
+        Self {
+            scale_factor,
+            color_scheme: Self::system_color_scheme(),
+        }
+    }
+
+    /// Follow the desktop's prefer-dark setting.
+    fn system_color_scheme() -> ColorScheme {
+        let prefers_dark = gtk4::Settings::default()
+            .map(|settings| settings.is_gtk_application_prefer_dark_theme())
+            .unwrap_or(false);
+        if prefers_dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
+
+    /// Hand the window's scale factor and color scheme to the shadow tree so
+    /// computed layouts snap to the device pixel grid and colors resolve
+    /// for the current appearance.
+    fn sync_environment(&self, tree: &mut ShadowTree) {
+        let environment = tree
+            .environment()
+            .clone()
+            .with_scale_factor(self.scale_factor)
+            .with_color_scheme(self.color_scheme);
+        tree.set_environment(environment);
+    }
+
     /// Updates the layout of a node and its children in the shadow tree
     /// returns (full_width, full_height) if the node is a spacer and the parent should be 100% of the available space
     pub fn update_layout(
@@ -121,6 +355,10 @@ impl Context {
                 // Spacers are handled by Taffy (flex: 1)
                 (true, true)
             }
+            // Fixed images are sized by their style already, resizable
+            // ones grow like spacers
+            NodeKind::Image { resizable, .. } => (*resizable, *resizable),
+            NodeKind::Shape { .. } | NodeKind::Canvas { .. } => (true, true),
 
             NodeKind::HStack { .. } => {
                 let mut needs_full_width = false;
@@ -172,7 +410,10 @@ impl Context {
                 (needs_full_width, needs_full_height)
             }
 
-            NodeKind::BackgroundColor { .. } => {
+            NodeKind::BackgroundColor { .. }
+            | NodeKind::Background { .. }
+            | NodeKind::CornerRadius { .. }
+            | NodeKind::Clip => {
                 let child = node
                     .children
                     .get(0)
@@ -198,6 +439,34 @@ impl Context {
                 (needs_full_width, needs_full_height)
             }
 
+            NodeKind::Border { width, .. } => {
+                let child = node.children.first().expect("Border must have a child");
+
+                let (needs_full_width, needs_full_height) = self.update_layout(
+                    child,
+                    tree,
+                    available_width - 2.0 * width,
+                    available_height - 2.0 * width,
+                );
+
+                let width_dim = if needs_full_width {
+                    taffy::Dimension::percent(1.0)
+                } else {
+                    taffy::Dimension::auto()
+                };
+                let height_dim = if needs_full_height {
+                    taffy::Dimension::percent(1.0)
+                } else {
+                    taffy::Dimension::auto()
+                };
+
+                if needs_full_width || needs_full_height {
+                    tree.set_size(node.taffy_id, width_dim, height_dim);
+                }
+
+                (needs_full_width, needs_full_height)
+            }
+
             NodeKind::Padding {
                 top,
                 left,
@@ -251,6 +520,20 @@ impl Context {
     /// Create GTK widgets from the shadow tree WITHOUT applying positions.
     /// Returns a NativeWidget tree that can be positioned separately via update_frames().
     fn render_node(&self, node: &ShadowNode) -> NativeWidget {
+        let native = self.render_kind(node);
+        // For tests and tools driving the window from outside
+        native.widget().set_widget_name(&node.identity.to_string());
+        if !node.effects.is_empty() {
+            let widget = native.widget();
+            widget.set_opacity(node.effects.opacity as f64);
+            if let Some(class) = conv::conv_create_effects_class(&node.effects, self.color_scheme) {
+                widget.style_context().add_class(&class);
+            }
+        }
+        native
+    }
+
+    fn render_kind(&self, node: &ShadowNode) -> NativeWidget {
         match &node.kind {
             NodeKind::Text {
                 content,
@@ -259,8 +542,18 @@ impl Context {
                 decoration,
             } => {
                 let label = gtk4::Label::new(Some(content.as_str()));
-                let class = conv::conv_create_text_class(font, color, decoration);
+                let class =
+                    conv::conv_create_text_class(font, color, decoration, self.color_scheme);
                 label.set_css_classes(&[class.as_str()]);
+
+                let resolved = conv::conv_resolve_font(&label, font);
+                if resolved.matched.is_none() {
+                    eprintln!(
+                        "[turubai] none of {} is available, using '{}'",
+                        conv::conv_font_family_to_css(font),
+                        resolved.family
+                    );
+                }
                 NativeWidget::Text { label }
             }
             NodeKind::VStack { .. } | NodeKind::HStack { .. } => {
@@ -282,6 +575,31 @@ impl Context {
                 widget: gtk4::Box::new(gtk4::Orientation::Horizontal, 0),
             },
 
+            NodeKind::Image {
+                bitmap,
+                alt,
+                aspect_ratio,
+                ..
+            } => {
+                let picture = gtk4::Picture::new();
+                if let Some(bitmap) = bitmap {
+                    picture.set_paintable(Some(&conv::conv_bitmap_to_texture(bitmap)));
+                }
+                // GtkPicture always samples linearly, so `Interpolation` is
+                // only honored by the other renderers
+                picture.set_content_fit(conv::conv_content_fit(*aspect_ratio));
+                picture.set_can_shrink(true);
+                if !alt.is_empty() {
+                    picture.set_alternative_text(Some(alt.as_str()));
+                }
+                NativeWidget::Picture { picture }
+            }
+
+            // Drawn once update_frames hands the draw func the environment
+            NodeKind::Shape { .. } | NodeKind::Canvas { .. } => NativeWidget::Drawing {
+                area: gtk4::DrawingArea::new(),
+            },
+
             NodeKind::BackgroundColor { color } => {
                 let widget = gtk4::Fixed::new();
 
@@ -292,7 +610,7 @@ impl Context {
                 let child_native = self.render_node(&child);
                 widget.put(&child_native.widget(), 0.0, 0.0);
 
-                let style = conv::conv_create_background_color_class(color);
+                let style = conv::conv_create_background_color_class(color, self.color_scheme);
                 widget.style_context().add_class(&style);
 
                 NativeWidget::Container {
@@ -301,6 +619,85 @@ impl Context {
                 }
             }
 
+            NodeKind::Background { fill } => {
+                let widget = gtk4::Fixed::new();
+
+                let child = node
+                    .children
+                    .first()
+                    .expect("Background requires at least one element!");
+                let child_native = self.render_node(child);
+                widget.put(&child_native.widget(), 0.0, 0.0);
+
+                let style = conv::conv_create_background_class(fill, self.color_scheme);
+                widget.style_context().add_class(&style);
+
+                NativeWidget::Container {
+                    container: widget,
+                    children: vec![child_native],
+                }
+            }
+
+            NodeKind::Border {
+                width,
+                color,
+                style,
+                radii,
+            } => {
+                let widget = gtk4::Fixed::new();
+                let child = node
+                    .children
+                    .first()
+                    .expect("Border requires at least one element!");
+                let child_native = self.render_node(child);
+                widget.put(&child_native.widget(), 0.0, 0.0);
+
+                let class =
+                    conv::conv_create_border_class(*width, color, *style, radii, self.color_scheme);
+                widget.style_context().add_class(&class);
+
+                NativeWidget::Container {
+                    container: widget,
+                    children: vec![child_native],
+                }
+            }
+
+            NodeKind::CornerRadius { radii } => {
+                let widget = gtk4::Fixed::new();
+                let child = node
+                    .children
+                    .first()
+                    .expect("CornerRadius requires at least one element!");
+                let child_native = self.render_node(child);
+                widget.put(&child_native.widget(), 0.0, 0.0);
+
+                // Hidden overflow clips to the rounded border box
+                let class = conv::conv_create_corner_radius_class(radii);
+                widget.style_context().add_class(&class);
+                widget.set_overflow(gtk4::Overflow::Hidden);
+
+                NativeWidget::Container {
+                    container: widget,
+                    children: vec![child_native],
+                }
+            }
+
+            NodeKind::Clip => {
+                let widget = gtk4::Fixed::new();
+                let child = node
+                    .children
+                    .first()
+                    .expect("Clip requires at least one element!");
+                let child_native = self.render_node(child);
+                widget.put(&child_native.widget(), 0.0, 0.0);
+                widget.set_overflow(gtk4::Overflow::Hidden);
+
+                NativeWidget::Container {
+                    container: widget,
+                    children: vec![child_native],
+                }
+            }
+
             NodeKind::Padding { .. } => {
                 let widget = gtk4::Fixed::new();
                 let child = node
@@ -330,15 +727,28 @@ impl DynContext for Context {
         let gtk_app = gtk4::Application::builder()
             .application_id(app.id())
             .build();
+        let application: Rc<dyn Application> = Rc::from(app);
 
-        let window_element = app.markup();
+        // Kept to rebuild the shadow tree from when the inspector edits it
+        let window_element = Rc::new(RefCell::new(application.markup()));
 
-        let shadow_tree = Rc::new(RefCell::new(ShadowTree::new()));
+        let environment = Environment::default()
+            .with_theme(application.theme())
+            .with_stylesheet(application.stylesheet())
+            .with_debug_layout(debug_layout_from_env());
+        let shadow_tree = Rc::new(RefCell::new(ShadowTree::with_environment(environment)));
 
         let mut window_node = shadow_tree
             .borrow()
-            .create_node_from_element(window_element.as_ref());
-        let root_node = Rc::new(window_node.children.pop().unwrap());
+            .create_node_from_element(window_element.borrow().as_ref());
+        let root_node = Rc::new(RefCell::new(window_node.children.pop().unwrap()));
+
+        // Font files written for Pango are removed once the application quits
+        let temporary_fonts = Rc::new(RefCell::new(Vec::new()));
+        gtk_app.connect_shutdown({
+            let temporary_fonts = temporary_fonts.clone();
+            move |_| temporary_fonts.borrow_mut().clear()
+        });
 
         gtk_app.connect_activate(move |app| {
             if let NodeKind::Window { title } = &window_node.kind {
@@ -349,11 +759,16 @@ impl DynContext for Context {
                     .default_height(DEFAULT_WINDOW_HEIGHT)
                     .build();
 
-                let context = Context {};
+                temporary_fonts
+                    .borrow_mut()
+                    .push(conv::conv_register_fonts(&window));
+
+                let context = Context::new(window.scale_factor() as f64);
+                context.sync_environment(&mut shadow_tree.borrow_mut());
 
                 // 1. Measure content
                 context.update_layout(
-                    &root_node,
+                    &root_node.borrow(),
                     &shadow_tree.borrow(),
                     DEFAULT_WINDOW_WIDTH as f64,
                     DEFAULT_WINDOW_HEIGHT as f64,
@@ -361,16 +776,16 @@ impl DynContext for Context {
 
                 // 2. Compute layout
                 shadow_tree.borrow_mut().compute_layout(
-                    &root_node,
+                    &root_node.borrow(),
                     DEFAULT_WINDOW_WIDTH as f32,
                     DEFAULT_WINDOW_HEIGHT as f32,
                 );
 
                 // 3. Create widgets (without positions)
-                let root_widget = context.render_node(&root_node);
+                let root_widget = context.render_node(&root_node.borrow());
 
                 // 4. Apply positions from computed layout
-                root_widget.update_frames(&root_node, &shadow_tree.borrow());
+                root_widget.update_frames(&root_node.borrow(), &shadow_tree.borrow());
 
                 // Use an Overlay with a DrawingArea as the base to get
                 // reliable resize events without feedback loops.
@@ -381,30 +796,199 @@ impl DynContext for Context {
                 overlay.set_child(Some(&resize_sensor));
                 overlay.add_overlay(&root_widget.widget());
 
-                window.set_child(Some(&overlay));
+                // Debugging aids go above the root widget, which is swapped
+                // on rebuilds
+                let layers = gtk4::Overlay::new();
+                layers.set_child(Some(&overlay));
+                window.set_child(Some(&layers));
+
+                let debug_layout = shadow_tree
+                    .borrow()
+                    .environment()
+                    .debug_layout()
+                    .then(|| debug_layout_area(&overlay, &shadow_tree, &root_node));
+                if let Some(area) = &debug_layout {
+                    layers.add_overlay(area);
+                }
+
+                #[cfg(all(feature = "inspector", unix))]
+                let (inspector, highlight) = {
+                    let inspector = Rc::new(RefCell::new(crate::inspector::Inspector::from_env(
+                        application.id(),
+                    )));
+                    let highlight = highlight_area(&inspector, &shadow_tree, &root_node);
+                    layers.add_overlay(&highlight);
+                    (inspector, highlight)
+                };
+
+                // 5. Handle resize and scale changes via the DrawingArea
+                let root_widget = Rc::new(RefCell::new(root_widget));
+                let relayout = {
+                    let root_widget = root_widget.clone();
+                    let shadow_tree = shadow_tree.clone();
+                    let root_node = root_node.clone();
+                    Rc::new(move |width: i32, height: i32, scale_factor: i32| {
+                        if width <= 0 || height <= 0 {
+                            return;
+                        }
+
+                        let root_node = root_node.borrow();
+                        let context = Context::new(scale_factor as f64);
+                        context.sync_environment(&mut shadow_tree.borrow_mut());
+                        context.update_layout(
+                            &root_node,
+                            &shadow_tree.borrow(),
+                            width as f64,
+                            height as f64,
+                        );
+                        shadow_tree.borrow_mut().compute_layout(
+                            &root_node,
+                            width as f32,
+                            height as f32,
+                        );
+                        root_widget
+                            .borrow()
+                            .update_frames(&root_node, &shadow_tree.borrow());
+                    })
+                };
 
-                // 5. Handle resize via DrawingArea::connect_resize
-                let root_widget = Rc::new(root_widget);
+                let on_resize = relayout.clone();
+                resize_sensor.connect_resize(move |drawing_area, width, height| {
+                    on_resize(width, height, drawing_area.scale_factor());
+                });
+                resize_sensor.connect_scale_factor_notify(move |drawing_area| {
+                    relayout(
+                        drawing_area.width(),
+                        drawing_area.height(),
+                        drawing_area.scale_factor(),
+                    );
+                });
+
+                // Swap the whole widget tree for one rendered from the
+                // current shadow tree.
+                let rerender = {
+                    let shadow_tree = shadow_tree.clone();
+                    let root_node = root_node.clone();
+                    let root_widget = root_widget.clone();
+                    let overlay = overlay.clone();
+                    Rc::new(move |context: &Context| {
+                        let root_node = root_node.borrow();
+                        let new_widget = context.render_node(&root_node);
+                        new_widget.update_frames(&root_node, &shadow_tree.borrow());
+
+                        let mut root_widget = root_widget.borrow_mut();
+                        overlay.remove_overlay(&root_widget.widget());
+                        overlay.add_overlay(&new_widget.widget());
+                        *root_widget = new_widget;
+                    })
+                };
+
+                // 6. Colors are baked into CSS classes, so re-create the
+                //    widgets when the desktop switches between light and dark.
+                if let Some(settings) = gtk4::Settings::default() {
+                    let shadow_tree = shadow_tree.clone();
+                    let resize_sensor = resize_sensor.clone();
+                    let rerender = rerender.clone();
+                    settings.connect_gtk_application_prefer_dark_theme_notify(move |_| {
+                        let context = Context::new(resize_sensor.scale_factor() as f64);
+                        context.sync_environment(&mut shadow_tree.borrow_mut());
+                        rerender(&context);
+                    });
+                }
+
+                // 7. Once per frame, rebuild from the markup when state
+                //    changed or the inspector edited it and present running
+                //    animations. The frame clock is the animation clock.
+                let application = application.clone();
+                let window_element = window_element.clone();
                 let shadow_tree = shadow_tree.clone();
                 let root_node = root_node.clone();
-                resize_sensor.connect_resize(move |_drawing_area, width, height| {
-                    if width <= 0 || height <= 0 {
-                        return;
+                let animator = RefCell::new(Animator::new());
+                let ghosts = RefCell::new(Vec::<Ghost>::new());
+                let generation = Cell::new(state::generation());
+                window.add_tick_callback(move |_, frame_clock| {
+                    let now = Duration::from_micros(frame_clock.frame_time().max(0) as u64);
+                    let context = Context::new(resize_sensor.scale_factor() as f64);
+                    let mut animator = animator.borrow_mut();
+
+                    #[cfg(all(feature = "inspector", unix))]
+                    let edited = match inspector.borrow_mut().as_mut() {
+                        Some(inspector) => {
+                            let highlighted = inspector.highlighted().map(<[usize]>::to_vec);
+                            let edited = inspector.serve(
+                                window_element.borrow().as_ref(),
+                                &shadow_tree.borrow(),
+                                &root_node.borrow(),
+                            );
+                            if inspector.highlighted() != highlighted.as_deref() {
+                                highlight.queue_draw();
+                            }
+                            edited
+                        }
+                        None => false,
+                    };
+                    #[cfg(not(all(feature = "inspector", unix)))]
+                    let edited = false;
+
+                    let changed = state::generation() != generation.get();
+                    if changed || edited {
+                        // Edits are shown right away
+                        let mut animation = None;
+                        if changed {
+                            animation = state::animation_since(generation.get());
+                            generation.set(state::generation());
+                            *window_element.borrow_mut() = application.markup();
+                        }
+
+                        let (width, height) = match (resize_sensor.width(), resize_sensor.height()) {
+                            (width, height) if width > 0 && height > 0 => (width, height),
+                            _ => (DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT),
+                        };
+                        let mut tree = ShadowTree::with_environment(shadow_tree.borrow().environment().clone());
+                        let mut window_node = tree.create_node_from_element(window_element.borrow().as_ref());
+                        let new_root = window_node.children.pop().expect("Window must have a child");
+                        context.update_layout(&new_root, &tree, width as f64, height as f64);
+                        tree.compute_layout(&new_root, width as f32, height as f32);
+
+                        let old_tree = std::mem::replace(&mut *shadow_tree.borrow_mut(), tree);
+                        let old_root = std::mem::replace(&mut *root_node.borrow_mut(), new_root);
+                        animator.begin(
+                            &old_root,
+                            old_tree,
+                            &root_node.borrow(),
+                            &shadow_tree.borrow(),
+                            animation.as_ref(),
+                            now,
+                        );
+                        animator.tick(&mut root_node.borrow_mut(), &mut shadow_tree.borrow_mut(), now);
+
+                        for ghost in ghosts.borrow_mut().drain(..) {
+                            ghost.parent.remove(&ghost.widget.widget());
+                        }
+                        rerender(&context);
+                        *ghosts.borrow_mut() = Ghost::render(&context, &root_widget.borrow(), &animator);
+                    } else if animator.is_animating() {
+                        let animating =
+                            animator.tick(&mut root_node.borrow_mut(), &mut shadow_tree.borrow_mut(), now);
+                        // Colors don't blend here, as they are baked into CSS
+                        // classes; they switch once the animation is over
+                        if !animating {
+                            rerender(&context);
+                        }
+                    } else {
+                        return gtk4::glib::ControlFlow::Continue;
                     }
 
-                    let context = Context {};
-                    context.update_layout(
-                        &root_node,
-                        &shadow_tree.borrow(),
-                        width as f64,
-                        height as f64,
-                    );
-                    shadow_tree.borrow_mut().compute_layout(
-                        &root_node,
-                        width as f32,
-                        height as f32,
-                    );
-                    root_widget.update_frames(&root_node, &shadow_tree.borrow());
+                    root_widget
+                        .borrow()
+                        .update_frames(&root_node.borrow(), &shadow_tree.borrow());
+                    ghosts.borrow_mut().retain(|ghost| ghost.update(&animator));
+                    if let Some(area) = &debug_layout {
+                        area.queue_draw();
+                    }
+                    #[cfg(all(feature = "inspector", unix))]
+                    highlight.queue_draw();
+                    gtk4::glib::ControlFlow::Continue
                 });
 
                 window.show();
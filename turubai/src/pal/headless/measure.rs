@@ -0,0 +1,122 @@
+use crate::{
+    font::{
+        registry::{self, RegisteredFace},
+        Font, FontFamily, FontWeight, GenericFamily, ResolvedFont,
+    },
+    shadow::{NodeKind, ShadowNode},
+    Unit,
+};
+
+/// Font sizes are in points, layout is in logical pixels (96 per inch).
+pub(crate) const POINTS_TO_PIXELS: f64 = 96.0 / 72.0;
+pub(crate) const LINE_HEIGHT: f64 = 1.2;
+
+/// Approximate advance of `c` as a fraction of the font size. These follow
+/// the proportions of common sans-serif faces closely enough to produce
+/// stable, plausible layouts without any font files.
+fn advance(c: char, monospace: bool) -> f64 {
+    if monospace {
+        return 0.6;
+    }
+    match c {
+        ' ' | 'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 0.28,
+        'f' | 't' | 'r' | '(' | ')' | '[' | ']' | '-' => 0.36,
+        'm' | 'w' | 'M' | 'W' | '@' => 0.85,
+        c if c.is_ascii_uppercase() || c.is_ascii_digit() => 0.64,
+        c if c.is_ascii() => 0.54,
+        // CJK and other wide scripts
+        _ => 1.0,
+    }
+}
+
+fn is_monospace(family: &FontFamily) -> bool {
+    match family {
+        FontFamily::Generic(generic) => *generic == GenericFamily::Monospace,
+        FontFamily::Named(name) => {
+            let name = name.to_ascii_lowercase();
+            name.contains("mono") || name.contains("courier") || name.contains("consolas")
+        }
+    }
+}
+
+/// The face used for `font`. Only registered faces are considered, never
+/// the fonts installed on the machine, so layouts stay the same everywhere.
+pub fn resolve_font(font: &Font) -> ResolvedFont {
+    registry::resolve(font).0
+}
+
+/// Measure with the real advances and line metrics of a registered face.
+fn measure_with_face(content: &str, face: &RegisteredFace, size: f64) -> Option<(f64, f64)> {
+    let face = ttf_parser::Face::parse(&face.data, face.index).ok()?;
+    let scale = size / face.units_per_em() as f64;
+    let line_height = (face.ascender() - face.descender() + face.line_gap()) as f64 * scale;
+
+    let lines: Vec<&str> = content.split('\n').collect();
+    let width = lines
+        .iter()
+        .map(|line| {
+            line.chars()
+                .filter_map(|c| face.glyph_index(c))
+                .filter_map(|glyph| face.glyph_hor_advance(glyph))
+                .map(|advance| advance as f64 * scale)
+                .sum::<f64>()
+        })
+        .fold(0.0_f64, f64::max);
+
+    Some((width.ceil(), (lines.len() as f64 * line_height).ceil()))
+}
+
+/// Size of `content` set in `font`, in logical pixels.
+pub fn measure_text(content: &str, font: &Font) -> (f64, f64) {
+    let size = font.size() as f64 * POINTS_TO_PIXELS;
+    let (resolved, face) = registry::resolve(font);
+    if let Some(size) = face.and_then(|face| measure_with_face(content, &face, size)) {
+        return size;
+    }
+
+    let (monospace, weight) = estimate(font, &resolved);
+
+    let lines: Vec<&str> = content.split('\n').collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().map(|c| advance(c, monospace)).sum::<f64>())
+        .fold(0.0_f64, f64::max);
+    let height = lines.len() as f64 * size * LINE_HEIGHT;
+
+    ((width * size * weight).ceil(), height.ceil())
+}
+
+/// Whether `font` is estimated as monospace and how much wider its weight
+/// makes it.
+fn estimate(font: &Font, resolved: &ResolvedFont) -> (bool, f64) {
+    let monospace = resolved
+        .matched
+        .map(|position| is_monospace(&font.families()[position]))
+        .unwrap_or_else(|| font.families().first().is_some_and(is_monospace));
+    let weight = if font.weight() >= FontWeight::SemiBold {
+        1.05
+    } else {
+        1.0
+    };
+    (monospace, weight)
+}
+
+/// The advance of `c` in logical pixels that [`measure_text`] estimates
+/// for fonts without a registered face.
+#[cfg(feature = "raster")]
+pub(crate) fn estimated_advance(c: char, font: &Font) -> f64 {
+    let size = font.size() as f64 * POINTS_TO_PIXELS;
+    let (monospace, weight) = estimate(font, &resolve_font(font));
+    advance(c, monospace) * size * weight
+}
+
+pub fn request_dimensions(node: &ShadowNode) -> (Unit, Unit) {
+    match &node.kind {
+        NodeKind::Text { content, font, .. } => {
+            let (width, height) = measure_text(content, font);
+            (Unit::Pixels(width), Unit::Pixels(height))
+        }
+        NodeKind::Spacer => (Unit::Percent(1.0), Unit::Percent(1.0)),
+        _ => (Unit::Pixels(0.0), Unit::Pixels(0.0)),
+    }
+}
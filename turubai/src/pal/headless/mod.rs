@@ -0,0 +1,467 @@
+//! A backend without a display. It builds and lays out the shadow tree using
+//! built-in text metrics, so interfaces can be laid out, inspected and tested
+//! on machines without a windowing system.
+
+use std::time::Duration;
+
+use taffy::Layout;
+
+use crate::{
+    animation::{Animator, ManualClock},
+    canvas::DrawCommand,
+    color::{Color, ColorScheme, Rgba},
+    elements::Element,
+    environment::Environment,
+    shadow::{NodeHandle, NodeKind, Query, ShadowNode, ShadowTree},
+    shape::Path,
+    state,
+    stylesheet::Stylesheet,
+    theme::Theme,
+    Application,
+};
+
+mod measure;
+#[cfg(feature = "raster")]
+pub mod raster;
+
+pub use measure::{measure_text, resolve_font};
+
+pub struct Context {
+    environment: Environment,
+}
+
+impl Context {
+    /// Colors resolve under `color_scheme`, since there is no desktop to ask.
+    pub fn new(color_scheme: ColorScheme) -> Self {
+        Self {
+            environment: Environment::default().with_color_scheme(color_scheme),
+        }
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> Self {
+        self.environment = self.environment.with_scale_factor(scale_factor);
+        self
+    }
+
+    /// Multiply every font size by `text_scale`, as a user's accessibility
+    /// setting would.
+    pub fn with_text_scale(mut self, text_scale: f64) -> Self {
+        self.environment = self.environment.with_text_scale(text_scale);
+        self
+    }
+
+    pub fn with_stylesheet(mut self, stylesheet: Stylesheet) -> Self {
+        self.environment = self.environment.with_stylesheet(stylesheet);
+        self
+    }
+
+    /// Paint the layout overlay into snapshots, see
+    /// [`ShadowTree::debug_overlay`].
+    pub fn with_debug_layout(mut self, debug_layout: bool) -> Self {
+        self.environment = self.environment.with_debug_layout(debug_layout);
+        self
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Build the application's markup with its theme and stylesheet and lay
+    /// it out in a window of `width` x `height` logical pixels.
+    pub fn render_application(&self, app: &dyn Application, width: f64, height: f64) -> Window {
+        let environment = self
+            .environment
+            .clone()
+            .with_theme(app.theme())
+            .with_stylesheet(app.stylesheet());
+        Window::new(app.markup(), environment, width, height)
+    }
+
+    /// Build what `markup` returns and lay it out like [`Context::render`].
+    /// `markup` is called again whenever [`State`](crate::state::State)
+    /// changes, on the next [`Window::update`] or [`Window::advance`].
+    pub fn render_markup(
+        &self,
+        markup: impl Fn() -> Box<dyn Element> + 'static,
+        width: f64,
+        height: f64,
+    ) -> Window {
+        let mut window = Window::new(markup(), self.environment.clone(), width, height);
+        window.markup = Some(Box::new(markup));
+        window
+    }
+
+    /// Build `element` and lay it out in `width` x `height` logical pixels.
+    /// A `WindowTemplate` contributes its title and is otherwise unwrapped,
+    /// just like the native backends do.
+    pub fn render(&self, element: Box<dyn Element>, width: f64, height: f64) -> Window {
+        Window::new(element, self.environment.clone(), width, height)
+    }
+}
+
+/// A laid out tree produced by the headless backend.
+pub struct Window {
+    pub title: Option<String>,
+    pub tree: ShadowTree,
+    pub root: ShadowNode,
+    element: Box<dyn Element>,
+    /// Called again to rebuild `element` when state changes
+    markup: Option<Box<dyn Fn() -> Box<dyn Element>>>,
+    width: f64,
+    height: f64,
+    clock: ManualClock,
+    animator: Animator,
+    /// The state generation the tree was built at
+    generation: u64,
+}
+
+/// Build the shadow tree for `element`, unwrapping a `WindowTemplate`.
+fn build(element: &dyn Element, environment: Environment) -> (Option<String>, ShadowTree, ShadowNode) {
+    let tree = ShadowTree::with_environment(environment);
+    let mut node = tree.create_node_from_element(element);
+
+    match &node.kind {
+        NodeKind::Window { title } => {
+            let title = Some(title.clone());
+            let root = node.children.pop().expect("Window must have a child");
+            (title, tree, root)
+        }
+        _ => (None, tree, node),
+    }
+}
+
+impl Window {
+    fn new(element: Box<dyn Element>, environment: Environment, width: f64, height: f64) -> Self {
+        let (title, tree, root) = build(element.as_ref(), environment);
+        let mut window = Window {
+            title,
+            tree,
+            root,
+            element,
+            markup: None,
+            width,
+            height,
+            clock: ManualClock::new(),
+            animator: Animator::new(),
+            generation: state::generation(),
+        };
+        window.relayout(width, height);
+        window
+    }
+
+    /// The clock animations run on. It only moves with [`Window::advance`].
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    pub fn animator(&self) -> &Animator {
+        &self.animator
+    }
+
+    /// Rebuild the tree if state changed since it was built, animating the
+    /// differences if the change asked for it.
+    pub fn update(&mut self) {
+        let generation = state::generation();
+        if generation == self.generation {
+            return;
+        }
+        let animation = state::animation_since(self.generation);
+        self.generation = generation;
+
+        if let Some(markup) = &self.markup {
+            self.element = markup();
+        }
+        let environment = self.tree.environment().clone();
+        let (title, tree, root) = build(self.element.as_ref(), environment);
+        self.title = title;
+        let old_tree = std::mem::replace(&mut self.tree, tree);
+        let old_root = std::mem::replace(&mut self.root, root);
+        self.relayout(self.width, self.height);
+
+        let now = self.clock.now();
+        self.animator
+            .begin(&old_root, old_tree, &self.root, &self.tree, animation.as_ref(), now);
+        self.animator.tick(&mut self.root, &mut self.tree, now);
+    }
+
+    /// Move the clock forward by one frame of `by` and present what is on
+    /// screen at that time, picking up state changes first.
+    pub fn advance(&mut self, by: Duration) {
+        self.clock.advance(by);
+        self.update();
+        self.animator
+            .tick(&mut self.root, &mut self.tree, self.clock.now());
+    }
+
+    /// Rebuild the shadow tree in `environment` and lay it out again.
+    pub fn set_environment(&mut self, environment: Environment) {
+        let (title, tree, root) = build(self.element.as_ref(), environment);
+        self.title = title;
+        self.tree = tree;
+        self.root = root;
+        // Everything is rebuilt, there is nothing to animate from
+        self.animator = Animator::new();
+        self.relayout(self.width, self.height);
+    }
+
+    pub fn theme(&self) -> &Theme {
+        self.tree.environment().theme()
+    }
+
+    /// Swap the theme. Fonts, colors and spacing are baked into the shadow
+    /// tree, so the whole tree is rebuilt and laid out again.
+    pub fn set_theme(&mut self, theme: Theme) {
+        let environment = self.tree.environment().clone().with_theme(theme);
+        self.set_environment(environment);
+    }
+
+    /// Measure and lay out the tree for a window of `width` x `height`.
+    pub fn relayout(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+        update_layout(&self.root, &self.tree, width, height);
+        self.tree
+            .compute_layout(&self.root, width as f32, height as f32);
+    }
+
+    pub fn size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    /// The computed layout of `node`, relative to its parent.
+    pub fn layout(&self, node: &ShadowNode) -> Option<&Layout> {
+        self.tree.get_layout(node.taffy_id)
+    }
+
+    /// The laid out tree as text, see [`ShadowTree::dump`].
+    pub fn dump_layout(&self) -> String {
+        self.tree.dump(&self.root)
+    }
+
+    /// The area `node` paints, relative to its parent: its layout box
+    /// grown by whatever its shadows and blur reach beyond it.
+    pub fn paint_bounds(&self, node: &ShadowNode) -> Option<taffy::Rect<f32>> {
+        let layout = self.layout(node)?;
+        let (left, top, right, bottom) = node.effects.overflow();
+        Some(taffy::Rect {
+            left: layout.location.x - left as f32,
+            top: layout.location.y - top as f32,
+            right: layout.location.x + layout.size.width + right as f32,
+            bottom: layout.location.y + layout.size.height + bottom as f32,
+        })
+    }
+
+    /// Where the picture of an image node is drawn, relative to its parent.
+    /// `None` for other nodes and images that failed to load.
+    pub fn image_bounds(&self, node: &ShadowNode) -> Option<taffy::Rect<f32>> {
+        let NodeKind::Image {
+            bitmap: Some(bitmap),
+            aspect_ratio,
+            ..
+        } = &node.kind
+        else {
+            return None;
+        };
+        let layout = self.layout(node)?;
+        let bounds = (layout.size.width as f64, layout.size.height as f64);
+        let (x, y, width, height) = aspect_ratio.place(bitmap.logical_size(), bounds);
+        let (x, y) = (layout.location.x + x as f32, layout.location.y + y as f32);
+        Some(taffy::Rect {
+            left: x,
+            top: y,
+            right: x + width as f32,
+            bottom: y + height as f32,
+        })
+    }
+
+    /// The outline of a shape node as laid out, in its own coordinates.
+    pub fn shape_path(&self, node: &ShadowNode) -> Option<Path> {
+        let NodeKind::Shape { shape, .. } = &node.kind else {
+            return None;
+        };
+        let layout = self.layout(node)?;
+        Some(shape.path(layout.size.width as f64, layout.size.height as f64))
+    }
+
+    /// What a canvas node draws at its laid out size. Recorded anew on
+    /// every call, so it reflects the current state.
+    pub fn draw_canvas(&self, node: &ShadowNode) -> Option<Vec<DrawCommand>> {
+        let NodeKind::Canvas { draw } = &node.kind else {
+            return None;
+        };
+        let layout = self.layout(node)?;
+        Some(draw.record(
+            layout.size.width as f64,
+            layout.size.height as f64,
+            self.tree.environment(),
+        ))
+    }
+
+    /// Paint the window as it is on screen, at the environment's scale
+    /// factor.
+    #[cfg(feature = "raster")]
+    pub fn snapshot(&self) -> crate::image::Bitmap {
+        raster::render(&self.tree, &self.root, self.width, self.height)
+    }
+
+    /// Answer the requests `inspector` received, rebuilding the tree if
+    /// they set modifiers.
+    #[cfg(all(feature = "inspector", unix))]
+    pub fn inspect(&mut self, inspector: &mut crate::inspector::Inspector) {
+        if inspector.serve(self.element.as_ref(), &self.tree, &self.root) {
+            self.set_environment(self.tree.environment().clone());
+        }
+    }
+
+    /// The topmost node at `(x, y)` in window coordinates. Transforms are
+    /// honored, and children are hit even where they stick out of their
+    /// parent, like in the GTK backend.
+    pub fn hit_test(&self, x: f32, y: f32) -> Option<&ShadowNode> {
+        self.tree.hit_test(&self.root, x, y)
+    }
+
+    /// The first node matching `query`, see [`ShadowTree::find`]. Frames
+    /// are in window coordinates.
+    pub fn find(&self, query: Query) -> Option<NodeHandle<'_>> {
+        self.tree.find(&self.root, query)
+    }
+
+    /// Every node matching `query`, see [`ShadowTree::find_all`].
+    pub fn find_all(&self, query: Query) -> Vec<NodeHandle<'_>> {
+        self.tree.find_all(&self.root, query)
+    }
+
+    /// The first text node showing exactly `text`.
+    pub fn find_by_text(&self, text: &str) -> Option<NodeHandle<'_>> {
+        self.tree.find_by_text(&self.root, text)
+    }
+
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.tree.environment().color_scheme()
+    }
+
+    /// Switch appearance. Layout does not depend on it, so this only
+    /// changes what colors resolve to.
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        let environment = self.tree.environment().clone().with_color_scheme(color_scheme);
+        self.tree.set_environment(environment);
+    }
+
+    /// Resolve `color` the way it would be painted in this window.
+    pub fn resolve_color(&self, color: &Color) -> Rgba {
+        self.tree.environment().resolve_color(color)
+    }
+}
+
+/// Size text nodes and stretch stacks that contain spacers, mirroring what
+/// the GTK backend does before handing the tree to taffy.
+/// Returns (full_width, full_height) when the parent should take all of the available space.
+fn update_layout(
+    node: &ShadowNode,
+    tree: &ShadowTree,
+    available_width: f64,
+    available_height: f64,
+) -> (bool, bool) {
+    match &node.kind {
+        NodeKind::Text { .. } => {
+            let (w, h) = measure::request_dimensions(node);
+            tree.set_size(
+                node.taffy_id,
+                taffy::Dimension::length(w.to_pixels(Some(available_width)) as f32),
+                taffy::Dimension::length(h.to_pixels(Some(available_height)) as f32),
+            );
+            (false, false)
+        }
+        NodeKind::Spacer => (true, true),
+        // Fixed images are sized by their style already
+        NodeKind::Image { resizable, .. } => (*resizable, *resizable),
+        NodeKind::Shape { .. } | NodeKind::Canvas { .. } => (true, true),
+        NodeKind::HStack { .. } | NodeKind::VStack { .. } => {
+            let (full_width, full_height) =
+                update_children(node, tree, available_width, available_height);
+            tree.set_size(
+                node.taffy_id,
+                fill_dimension(full_width),
+                fill_dimension(full_height),
+            );
+            (full_width, full_height)
+        }
+        NodeKind::BackgroundColor { .. }
+        | NodeKind::Background { .. }
+        | NodeKind::CornerRadius { .. }
+        | NodeKind::Clip => {
+            let (full_width, full_height) =
+                update_children(node, tree, available_width, available_height);
+            if full_width || full_height {
+                tree.set_size(
+                    node.taffy_id,
+                    fill_dimension(full_width),
+                    fill_dimension(full_height),
+                );
+            }
+            (full_width, full_height)
+        }
+        NodeKind::Border { width, .. } => {
+            let (full_width, full_height) = update_children(
+                node,
+                tree,
+                available_width - 2.0 * width,
+                available_height - 2.0 * width,
+            );
+            if full_width || full_height {
+                tree.set_size(
+                    node.taffy_id,
+                    fill_dimension(full_width),
+                    fill_dimension(full_height),
+                );
+            }
+            (full_width, full_height)
+        }
+        NodeKind::Padding {
+            top,
+            left,
+            bottom,
+            right,
+        } => {
+            let (full_width, full_height) = update_children(
+                node,
+                tree,
+                available_width - (left + right),
+                available_height - (top + bottom),
+            );
+            if full_width || full_height {
+                tree.set_size(
+                    node.taffy_id,
+                    fill_dimension(full_width),
+                    fill_dimension(full_height),
+                );
+            }
+            (full_width, full_height)
+        }
+        _ => update_children(node, tree, available_width, available_height),
+    }
+}
+
+fn update_children(
+    node: &ShadowNode,
+    tree: &ShadowTree,
+    available_width: f64,
+    available_height: f64,
+) -> (bool, bool) {
+    let mut full_width = false;
+    let mut full_height = false;
+    for child in &node.children {
+        let (child_w, child_h) = update_layout(child, tree, available_width, available_height);
+        full_width = child_w || full_width;
+        full_height = child_h || full_height;
+    }
+    (full_width, full_height)
+}
+
+fn fill_dimension(full: bool) -> taffy::Dimension {
+    if full {
+        taffy::Dimension::percent(1.0)
+    } else {
+        taffy::Dimension::auto()
+    }
+}
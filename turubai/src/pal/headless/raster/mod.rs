@@ -0,0 +1,565 @@
+//! A software renderer for laid out shadow trees, enabled by the `raster`
+//! feature. It paints what a window shows into a [`Bitmap`] without a
+//! display, with the same built-in text metrics the headless layout uses,
+//! so screenshots come out the same on every machine.
+//!
+//! ```ignore
+//! let window = Context::new(ColorScheme::Light).render_application(&app, 400.0, 300.0);
+//! window.snapshot().save_png("screenshot.png")?;
+//! ```
+
+mod paint;
+mod text;
+
+use tiny_skia::{FillRule, Mask, Pixmap, PixmapPaint, Transform};
+
+use crate::{
+    canvas::DrawCommand,
+    color::{Color, ColorScheme},
+    elements::TextLineStyle,
+    environment::Environment,
+    image::Bitmap,
+    postprocessing::{AffineTransform, BorderStyle, CornerRadii},
+    shadow::{NodeKind, ShadowNode, ShadowTree},
+    shape::{Path, Stroke},
+};
+
+use paint::{conv_color, conv_path, conv_transform, Painter};
+
+/// Paint `root` and its descendants as laid out in `tree`, over the
+/// window background, into `width` x `height` logical pixels. The bitmap
+/// has the environment's scale factor. The layout overlay is painted on
+/// top if the environment asks for it.
+pub fn render(tree: &ShadowTree, root: &ShadowNode, width: f64, height: f64) -> Bitmap {
+    let environment = tree.environment();
+    let scale_factor = environment.scale_factor();
+    let (pixel_width, pixel_height) = (
+        (width * scale_factor).ceil().max(0.0) as u32,
+        (height * scale_factor).ceil().max(0.0) as u32,
+    );
+    let Some(mut pixmap) = Pixmap::new(pixel_width, pixel_height) else {
+        return Bitmap::new(0, 0, vec![]).unwrap().with_scale(scale_factor);
+    };
+    let scheme = environment.color_scheme();
+    pixmap.fill(conv_color(
+        &environment.themed_color(&Color::WindowBackground),
+        scheme,
+    ));
+
+    let renderer = Renderer {
+        tree,
+        painter: Painter::new(environment),
+    };
+    let transform = Transform::from_scale(scale_factor as f32, scale_factor as f32);
+    renderer.render_node(&mut pixmap, root, transform, None);
+    if environment.debug_layout() {
+        let window = Path::new().rect(0.0, 0.0, width, height);
+        let clip = intersect(None, &pixmap, &window, transform);
+        let overlay = tree.debug_overlay(root, None);
+        renderer.replay(&mut pixmap, &overlay, transform, clip);
+    }
+
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let pixel = pixel.demultiply();
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+        })
+        .collect();
+    Bitmap::new(pixel_width, pixel_height, pixels)
+        .unwrap()
+        .with_scale(scale_factor)
+}
+
+struct Renderer<'a> {
+    tree: &'a ShadowTree,
+    painter: Painter<'a>,
+}
+
+impl Renderer<'_> {
+    fn environment(&self) -> &Environment {
+        self.tree.environment()
+    }
+
+    fn scheme(&self) -> ColorScheme {
+        self.environment().color_scheme()
+    }
+
+    /// `parent` maps the parent's coordinates to device pixels.
+    fn render_node(
+        &self,
+        canvas: &mut Pixmap,
+        node: &ShadowNode,
+        parent: Transform,
+        clip: Option<&Mask>,
+    ) {
+        let Some(local) = self.tree.get_transform(node) else {
+            return;
+        };
+        let transform = parent.pre_concat(conv_transform(&local));
+        let effects = &node.effects;
+        if effects.is_empty() {
+            self.render_contents(canvas, node, transform, clip);
+            return;
+        }
+        if effects.opacity <= 0.0 {
+            return;
+        }
+
+        // Effects apply to the node and its children as a whole, so they
+        // are painted on their own layer first
+        let Some(mut layer) = Pixmap::new(canvas.width(), canvas.height()) else {
+            return;
+        };
+        self.render_contents(&mut layer, node, transform, clip);
+        let scale_factor = self.environment().scale_factor();
+        if effects.blur > 0.0 {
+            // Like CSS `filter: blur()`, the radius is the standard deviation
+            paint::blur(&mut layer, effects.blur * scale_factor);
+        }
+
+        let paint = PixmapPaint {
+            opacity: effects.opacity,
+            ..PixmapPaint::default()
+        };
+        // Like chained CSS `drop-shadow()` filters, each shadow is cast by
+        // everything painted so far. The last one goes first, so the first
+        // ends up at the bottom.
+        for shadow in effects.shadows.iter().rev() {
+            let mut silhouette = paint::tinted(&layer, conv_color(&shadow.color, self.scheme()));
+            // The blur reaches as far as the radius
+            paint::blur(&mut silhouette, shadow.radius * scale_factor / 2.0);
+            let (x, y) = shadow.offset;
+            let offset = Transform::from_translate(
+                (transform.sx * x as f32 + transform.kx * y as f32).round(),
+                (transform.ky * x as f32 + transform.sy * y as f32).round(),
+            );
+            let Some(mut shadowed) = Pixmap::new(canvas.width(), canvas.height()) else {
+                return;
+            };
+            let opaque = PixmapPaint::default();
+            shadowed.draw_pixmap(0, 0, silhouette.as_ref(), &opaque, offset, clip);
+            shadowed.draw_pixmap(0, 0, layer.as_ref(), &opaque, Transform::identity(), None);
+            layer = shadowed;
+        }
+        canvas.draw_pixmap(0, 0, layer.as_ref(), &paint, Transform::identity(), None);
+    }
+
+    fn render_contents(
+        &self,
+        canvas: &mut Pixmap,
+        node: &ShadowNode,
+        transform: Transform,
+        clip: Option<&Mask>,
+    ) {
+        let Some(layout) = self.tree.get_layout(node.taffy_id) else {
+            return;
+        };
+        let (width, height) = (layout.size.width as f64, layout.size.height as f64);
+        let bounds = (0.0, 0.0, width, height);
+        let scheme = self.scheme();
+        let mut child_clip = None;
+
+        match &node.kind {
+            NodeKind::Text {
+                content,
+                font,
+                color,
+                decoration,
+            } => {
+                let lines = text::draw_text(
+                    canvas,
+                    content,
+                    font,
+                    conv_color(color, scheme),
+                    transform,
+                    clip,
+                );
+                for (line, kind) in [
+                    (&decoration.underline, text::Decoration::Underline),
+                    (&decoration.strike_through, text::Decoration::StrikeThrough),
+                ] {
+                    if line.style == TextLineStyle::None {
+                        continue;
+                    }
+                    text::draw_decoration(
+                        canvas,
+                        &lines,
+                        kind,
+                        &line.style,
+                        conv_color(&line.color, scheme),
+                        transform,
+                        clip,
+                    );
+                }
+            }
+            NodeKind::Image {
+                bitmap: Some(bitmap),
+                resizable,
+                aspect_ratio,
+                interpolation,
+                ..
+            } => {
+                let rect = if *resizable {
+                    aspect_ratio.place(bitmap.logical_size(), (width, height))
+                } else {
+                    bounds
+                };
+                // Filled pictures are cropped to the node
+                self.painter.draw_bitmap(
+                    canvas,
+                    bitmap,
+                    rect,
+                    bounds,
+                    *interpolation,
+                    transform,
+                    clip,
+                );
+            }
+            NodeKind::Shape {
+                shape,
+                fill,
+                stroke,
+            } => {
+                let path = shape.path(width, height);
+                if let Some(fill) = fill {
+                    self.painter
+                        .fill(canvas, &path, fill, bounds, transform, clip);
+                }
+                if let Some(stroke) = stroke {
+                    self.painter
+                        .stroke(canvas, &path, stroke, bounds, transform, clip);
+                }
+            }
+            NodeKind::Canvas { draw } => {
+                // Drawings stay inside the canvas, like a native drawing area
+                let clip = intersect(
+                    clip,
+                    canvas,
+                    &Path::new().rect(0.0, 0.0, width, height),
+                    transform,
+                );
+                let commands = draw.record(width, height, self.environment());
+                self.replay(canvas, &commands, transform, clip);
+            }
+            NodeKind::BackgroundColor { color } => {
+                let path = Path::new().rect(0.0, 0.0, width, height);
+                self.painter
+                    .fill(canvas, &path, &(*color).into(), bounds, transform, clip);
+            }
+            NodeKind::Background { fill } => {
+                let path = Path::new().rect(0.0, 0.0, width, height);
+                self.painter
+                    .fill(canvas, &path, fill, bounds, transform, clip);
+            }
+            NodeKind::Border {
+                width: border_width,
+                color,
+                style,
+                radii,
+            } => self.render_border(
+                canvas,
+                (width, height),
+                *border_width,
+                color,
+                *style,
+                radii,
+                transform,
+                clip,
+            ),
+            NodeKind::CornerRadius { radii } => {
+                let path = rounded_rect(width, height, radii);
+                child_clip = Some(intersect(clip, canvas, &path, transform));
+            }
+            NodeKind::Clip => {
+                let path = Path::new().rect(0.0, 0.0, width, height);
+                child_clip = Some(intersect(clip, canvas, &path, transform));
+            }
+            _ => {}
+        }
+
+        let clip = child_clip.as_ref().or(clip);
+        for child in &node.children {
+            self.render_node(canvas, child, transform, clip);
+        }
+    }
+
+    /// Borders are drawn inside the node, around the inset child.
+    #[allow(clippy::too_many_arguments)]
+    fn render_border(
+        &self,
+        canvas: &mut Pixmap,
+        (width, height): (f64, f64),
+        border_width: f64,
+        color: &Color,
+        style: BorderStyle,
+        radii: &CornerRadii,
+        transform: Transform,
+        clip: Option<&Mask>,
+    ) {
+        if border_width <= 0.0 {
+            return;
+        }
+        // Strokes of `line` centered `inset` into the node
+        let outline = |inset: f64, line: f64, dash: Vec<f64>| {
+            let radii = CornerRadii {
+                top_leading: (radii.top_leading - inset).max(0.0),
+                top_trailing: (radii.top_trailing - inset).max(0.0),
+                bottom_trailing: (radii.bottom_trailing - inset).max(0.0),
+                bottom_leading: (radii.bottom_leading - inset).max(0.0),
+            };
+            let path = rounded_rect(width - 2.0 * inset, height - 2.0 * inset, &radii)
+                .transformed(&AffineTransform::translation(inset as f32, inset as f32));
+            (path, Stroke::new(*color, line).dashed(dash))
+        };
+        let strokes = match style {
+            BorderStyle::Solid => vec![outline(border_width / 2.0, border_width, vec![])],
+            BorderStyle::Dashed => vec![outline(
+                border_width / 2.0,
+                border_width,
+                vec![border_width * 3.0, border_width * 2.0],
+            )],
+            BorderStyle::Dotted => vec![outline(
+                border_width / 2.0,
+                border_width,
+                vec![border_width, border_width],
+            )],
+            // Two lines a third of the width each, with a gap between them
+            BorderStyle::Double => vec![
+                outline(border_width / 6.0, border_width / 3.0, vec![]),
+                outline(border_width * 5.0 / 6.0, border_width / 3.0, vec![]),
+            ],
+        };
+        let bounds = (0.0, 0.0, width, height);
+        for (path, stroke) in strokes {
+            self.painter
+                .stroke(canvas, &path, &stroke, bounds, transform, clip);
+        }
+    }
+
+    /// Play back what a canvas recorded, in the canvas' coordinates.
+    fn replay(
+        &self,
+        canvas: &mut Pixmap,
+        commands: &[DrawCommand],
+        transform: Transform,
+        clip: Mask,
+    ) {
+        let mut transform = transform;
+        let mut clip = clip;
+        let mut saved = vec![];
+        for command in commands {
+            match command {
+                DrawCommand::Save => saved.push((transform, clip.clone())),
+                DrawCommand::Restore => {
+                    if let Some((saved_transform, saved_clip)) = saved.pop() {
+                        transform = saved_transform;
+                        clip = saved_clip;
+                    }
+                }
+                DrawCommand::Transform(local) => {
+                    transform = transform.pre_concat(conv_transform(local));
+                }
+                DrawCommand::Clip(path) => {
+                    clip = intersect(Some(&clip), canvas, path, transform);
+                }
+                DrawCommand::Fill { path, paint } => {
+                    if let Some(bounds) = path.bounds() {
+                        self.painter
+                            .fill(canvas, path, paint, bounds, transform, Some(&clip));
+                    }
+                }
+                DrawCommand::Stroke { path, stroke } => {
+                    if let Some(bounds) = path.bounds() {
+                        self.painter
+                            .stroke(canvas, path, stroke, bounds, transform, Some(&clip));
+                    }
+                }
+                DrawCommand::Text {
+                    content,
+                    font,
+                    color,
+                    origin: (x, y),
+                } => {
+                    text::draw_text(
+                        canvas,
+                        content,
+                        font,
+                        conv_color(color, self.scheme()),
+                        transform.pre_translate(*x as f32, *y as f32),
+                        Some(&clip),
+                    );
+                }
+                DrawCommand::Image {
+                    bitmap,
+                    rect,
+                    interpolation,
+                } => self.painter.draw_bitmap(
+                    canvas,
+                    bitmap,
+                    *rect,
+                    *rect,
+                    *interpolation,
+                    transform,
+                    Some(&clip),
+                ),
+            }
+        }
+    }
+}
+
+/// `clip` narrowed down to the inside of `path`, or just the inside of
+/// `path` if nothing was clipped yet.
+fn intersect(clip: Option<&Mask>, canvas: &Pixmap, path: &Path, transform: Transform) -> Mask {
+    let mut mask = match clip {
+        Some(clip) => clip.clone(),
+        None => {
+            let mut mask = Mask::new(canvas.width(), canvas.height()).unwrap();
+            mask.data_mut().fill(255);
+            mask
+        }
+    };
+    match conv_path(path) {
+        Some(path) => mask.intersect_path(&path, FillRule::Winding, true, transform),
+        None => mask.clear(),
+    }
+    mask
+}
+
+/// A `width` x `height` rectangle with each corner rounded by its radius,
+/// at most half the shorter side.
+fn rounded_rect(width: f64, height: f64, radii: &CornerRadii) -> Path {
+    if radii.is_zero() {
+        return Path::new().rect(0.0, 0.0, width, height);
+    }
+    let limit = width.min(height).max(0.0) / 2.0;
+    let r = |radius: f64| radius.clamp(0.0, limit);
+    let (top_leading, top_trailing, bottom_trailing, bottom_leading) = (
+        r(radii.top_leading),
+        r(radii.top_trailing),
+        r(radii.bottom_trailing),
+        r(radii.bottom_leading),
+    );
+    Path::new()
+        .arc(
+            (width - top_trailing, top_trailing),
+            top_trailing,
+            270.0,
+            360.0,
+        )
+        .arc(
+            (width - bottom_trailing, height - bottom_trailing),
+            bottom_trailing,
+            0.0,
+            90.0,
+        )
+        .arc(
+            (bottom_leading, height - bottom_leading),
+            bottom_leading,
+            90.0,
+            180.0,
+        )
+        .arc((top_leading, top_leading), top_leading, 180.0, 270.0)
+        .close()
+}
+
+#[test]
+fn test_raster() {
+    use crate::{
+        composition::HStack,
+        elements::{Canvas, Element, Modifiers, Rectangle, RoundedRectangle, Text},
+        image::ImageSource,
+        pal::headless::Context,
+        postprocessing::{corner_radius, fill},
+    };
+
+    let modifiers = Modifiers::default();
+    let rectangle = fill(
+        Color::SystemRed,
+        Box::new(RoundedRectangle::new(10.0, modifiers.fork())),
+        modifiers.fork(),
+    );
+    let canvas = Canvas::new(
+        |cx| {
+            let (width, height) = cx.size();
+            cx.fill(
+                &Path::new().rect(0.0, 0.0, width, height),
+                Color::SystemBlue,
+            );
+            // Clipped to the canvas
+            cx.fill(
+                &Path::new().rect(-50.0, 0.0, 10.0, 10.0),
+                Color::SystemGreen,
+            );
+        },
+        modifiers.fork(),
+    );
+    let row: Box<dyn Element> = Box::new(HStack::new(
+        modifiers.clone(),
+        vec![rectangle, Box::new(canvas)],
+    ));
+    let window = Context::new(ColorScheme::Light)
+        .with_scale_factor(2.0)
+        .render(row, 100.0, 50.0);
+    let bitmap = window.snapshot();
+    assert_eq!(
+        (bitmap.width(), bitmap.height(), bitmap.scale()),
+        (200, 100, 2.0)
+    );
+
+    let rgba = |color: Color| {
+        let rgba = window.resolve_color(&color);
+        [rgba.r, rgba.g, rgba.b, rgba.a].map(|channel| (channel * 255.0).round() as u8)
+    };
+    let near = |a: [u8; 4], b: [u8; 4]| a.iter().zip(b).all(|(a, b)| a.abs_diff(b) <= 1);
+    assert!(near(bitmap.pixel(50, 50).unwrap(), rgba(Color::SystemRed)));
+    assert!(near(
+        bitmap.pixel(150, 50).unwrap(),
+        rgba(Color::SystemBlue)
+    ));
+    // The rounded corner shows the window behind it
+    assert!(near(
+        bitmap.pixel(0, 0).unwrap(),
+        rgba(Color::WindowBackground)
+    ));
+    assert!(near(bitmap.pixel(100, 0).unwrap(), rgba(Color::SystemBlue)));
+    assert!(!near(
+        bitmap.pixel(99, 0).unwrap(),
+        rgba(Color::SystemGreen)
+    ));
+
+    // PNGs decode to the same pixels
+    let png = bitmap.encode_png().unwrap();
+    let decoded = ImageSource::bytes(png).load(1.0).unwrap();
+    assert_eq!(decoded.pixels(), bitmap.pixels());
+
+    // Corner radii clip their child
+    let rectangle = fill(
+        Color::SystemRed,
+        Box::new(Rectangle::new(modifiers.fork())),
+        modifiers.fork(),
+    );
+    let rounded = Box::new(corner_radius(20.0, rectangle, modifiers.fork()));
+    let window = Context::new(ColorScheme::Light).render(rounded, 100.0, 50.0);
+    let bitmap = window.snapshot();
+    assert!(near(
+        bitmap.pixel(1, 1).unwrap(),
+        rgba(Color::WindowBackground)
+    ));
+    assert!(near(bitmap.pixel(50, 1).unwrap(), rgba(Color::SystemRed)));
+
+    // Text is painted even without a registered face
+    let text: Box<dyn Element> = Box::new(Text::new("Hello", modifiers.fork()));
+    let window = Context::new(ColorScheme::Dark).render(text, 100.0, 40.0);
+    let bitmap = window.snapshot();
+    let text_color = {
+        let rgba = window.resolve_color(&Color::Text);
+        [rgba.r, rgba.g, rgba.b, rgba.a].map(|channel| (channel * 255.0).round() as u8)
+    };
+    let painted = (0..bitmap.height())
+        .flat_map(|y| (0..bitmap.width()).map(move |x| (x, y)))
+        .filter(|(x, y)| near(bitmap.pixel(*x, *y).unwrap(), text_color))
+        .count();
+    assert!(painted > 20);
+}
@@ -0,0 +1,446 @@
+use tiny_skia::{
+    ColorU8, FillRule, FilterQuality, GradientStop, LinearGradient, Mask, Paint, Pattern, Pixmap,
+    Point, PremultipliedColorU8, RadialGradient, Rect, Shader, SpreadMode, StrokeDash, Transform,
+};
+
+use crate::{
+    color::{Color, ColorScheme, Rgba},
+    elements::Interpolation,
+    environment::Environment,
+    fill::{linear_gradient_line, radial_gradient_radius, ColorStop, Fill, ImageTiling},
+    image::{Bitmap, ImageSource},
+    postprocessing::AffineTransform,
+    shape::{Path, PathCommand, Stroke},
+};
+
+/// Paints fills, strokes and pictures. Gradients and image fills span
+/// `bounds`, given as `(x, y, width, height)` in the coordinates of the
+/// path.
+pub(super) struct Painter<'a> {
+    environment: &'a Environment,
+}
+
+impl<'a> Painter<'a> {
+    pub fn new(environment: &'a Environment) -> Self {
+        Self { environment }
+    }
+
+    pub fn fill(
+        &self,
+        canvas: &mut Pixmap,
+        path: &Path,
+        fill: &Fill,
+        bounds: (f64, f64, f64, f64),
+        transform: Transform,
+        clip: Option<&Mask>,
+    ) {
+        let Some(path) = conv_path(path) else {
+            return;
+        };
+        self.with_paint(fill, bounds, |paint| {
+            canvas.fill_path(&path, paint, FillRule::Winding, transform, clip)
+        });
+    }
+
+    pub fn stroke(
+        &self,
+        canvas: &mut Pixmap,
+        path: &Path,
+        stroke: &Stroke,
+        bounds: (f64, f64, f64, f64),
+        transform: Transform,
+        clip: Option<&Mask>,
+    ) {
+        if stroke.width <= 0.0 {
+            return;
+        }
+        let Some(path) = conv_path(path) else {
+            return;
+        };
+        let line = tiny_skia::Stroke {
+            width: stroke.width as f32,
+            dash: conv_dash(&stroke.dash),
+            ..Default::default()
+        };
+        self.with_paint(&stroke.paint, bounds, |paint| {
+            canvas.stroke_path(&path, paint, &line, transform, clip)
+        });
+    }
+
+    /// Draw `bitmap` stretched to `rect`, only where it overlaps `visible`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_bitmap(
+        &self,
+        canvas: &mut Pixmap,
+        bitmap: &Bitmap,
+        (x, y, width, height): (f64, f64, f64, f64),
+        (left, top, visible_width, visible_height): (f64, f64, f64, f64),
+        interpolation: Interpolation,
+        transform: Transform,
+        clip: Option<&Mask>,
+    ) {
+        let Some(pixmap) = conv_pixmap(bitmap) else {
+            return;
+        };
+        let Some(area) = Rect::from_ltrb(
+            x.max(left) as f32,
+            y.max(top) as f32,
+            (x + width).min(left + visible_width) as f32,
+            (y + height).min(top + visible_height) as f32,
+        ) else {
+            return;
+        };
+        let placement = Transform::from_row(
+            (width / bitmap.width() as f64) as f32,
+            0.0,
+            0.0,
+            (height / bitmap.height() as f64) as f32,
+            x as f32,
+            y as f32,
+        );
+        let quality = match interpolation {
+            Interpolation::Nearest => FilterQuality::Nearest,
+            Interpolation::Linear => FilterQuality::Bilinear,
+        };
+        let paint = Paint {
+            shader: Pattern::new(pixmap.as_ref(), SpreadMode::Pad, quality, 1.0, placement),
+            ..Default::default()
+        };
+        canvas.fill_rect(area, &paint, transform, clip);
+    }
+
+    /// Call `f` with a paint for `fill` over `bounds`.
+    fn with_paint(
+        &self,
+        fill: &Fill,
+        (x, y, width, height): (f64, f64, f64, f64),
+        f: impl FnOnce(&Paint),
+    ) {
+        let scheme = self.environment.color_scheme();
+        let gradient_stops = |stops: &[ColorStop]| {
+            stops
+                .iter()
+                .map(|stop| GradientStop::new(stop.offset, conv_color(&stop.color, scheme)))
+                .collect::<Vec<_>>()
+        };
+        let point = |px: f64, py: f64| Point::from_xy((x + px) as f32, (y + py) as f32);
+
+        let shader = match fill {
+            Fill::Color(color) => Some(Shader::SolidColor(conv_color(color, scheme))),
+            Fill::LinearGradient { angle, stops } => {
+                let ((x0, y0), (x1, y1)) = linear_gradient_line(*angle, width, height);
+                LinearGradient::new(
+                    point(x0, y0),
+                    point(x1, y1),
+                    gradient_stops(stops),
+                    SpreadMode::Pad,
+                    Transform::identity(),
+                )
+            }
+            Fill::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => {
+                let center_point = point(center.x as f64 * width, center.y as f64 * height);
+                RadialGradient::new(
+                    center_point,
+                    center_point,
+                    radial_gradient_radius(*center, *radius, width, height) as f32,
+                    gradient_stops(stops),
+                    SpreadMode::Pad,
+                    Transform::identity(),
+                )
+            }
+            Fill::ConicGradient {
+                center,
+                angle,
+                stops,
+            } => {
+                // tiny-skia has no sweep gradient, so it is rendered into a
+                // picture at device resolution
+                let scale_factor = self.environment.scale_factor();
+                let center = (center.x as f64 * width, center.y as f64 * height);
+                if let Some(pixmap) =
+                    conic_gradient(center, *angle, stops, width, height, scale_factor, scheme)
+                {
+                    let scale = (1.0 / scale_factor) as f32;
+                    let placement = Transform::from_row(scale, 0.0, 0.0, scale, x as f32, y as f32);
+                    return f(&Paint {
+                        shader: Pattern::new(
+                            pixmap.as_ref(),
+                            SpreadMode::Pad,
+                            FilterQuality::Bilinear,
+                            1.0,
+                            placement,
+                        ),
+                        ..Default::default()
+                    });
+                }
+                None
+            }
+            Fill::Image { path, tiling } => {
+                let bitmap = match ImageSource::path(path).load(self.environment.scale_factor()) {
+                    Ok(bitmap) => bitmap,
+                    Err(err) => {
+                        eprintln!("[turubai] {err} ({})", path.display());
+                        return;
+                    }
+                };
+                let Some(pixmap) = conv_pixmap(&bitmap) else {
+                    return;
+                };
+                let (scale_x, scale_y, spread) = match tiling {
+                    ImageTiling::Stretch => (
+                        width / bitmap.width() as f64,
+                        height / bitmap.height() as f64,
+                        SpreadMode::Pad,
+                    ),
+                    ImageTiling::Tile => (
+                        1.0 / bitmap.scale(),
+                        1.0 / bitmap.scale(),
+                        SpreadMode::Repeat,
+                    ),
+                };
+                let placement = Transform::from_row(
+                    scale_x as f32,
+                    0.0,
+                    0.0,
+                    scale_y as f32,
+                    x as f32,
+                    y as f32,
+                );
+                return f(&Paint {
+                    shader: Pattern::new(
+                        pixmap.as_ref(),
+                        spread,
+                        FilterQuality::Bilinear,
+                        1.0,
+                        placement,
+                    ),
+                    ..Default::default()
+                });
+            }
+        };
+
+        // Gradients tiny-skia can't make, e.g. over an empty area, are
+        // painted with their first color
+        let shader = shader.unwrap_or_else(|| {
+            let color = fill
+                .stops()
+                .and_then(|stops| stops.first())
+                .map(|stop| conv_color(&stop.color, scheme))
+                .unwrap_or(tiny_skia::Color::TRANSPARENT);
+            Shader::SolidColor(color)
+        });
+        f(&Paint {
+            shader,
+            anti_alias: true,
+            ..Default::default()
+        });
+    }
+}
+
+pub(super) fn conv_color(color: &Color, scheme: ColorScheme) -> tiny_skia::Color {
+    let rgba = color.resolve(scheme);
+    tiny_skia::Color::from_rgba(
+        rgba.r.clamp(0.0, 1.0),
+        rgba.g.clamp(0.0, 1.0),
+        rgba.b.clamp(0.0, 1.0),
+        rgba.a.clamp(0.0, 1.0),
+    )
+    .unwrap_or(tiny_skia::Color::TRANSPARENT)
+}
+
+pub(super) fn conv_transform(transform: &AffineTransform) -> Transform {
+    Transform::from_row(
+        transform.a,
+        transform.b,
+        transform.c,
+        transform.d,
+        transform.e,
+        transform.f,
+    )
+}
+
+/// `None` for paths without any area to paint, which tiny-skia rejects.
+pub(super) fn conv_path(path: &Path) -> Option<tiny_skia::Path> {
+    let mut builder = tiny_skia::PathBuilder::new();
+    for command in path.commands() {
+        match *command {
+            PathCommand::MoveTo { x, y } => builder.move_to(x as f32, y as f32),
+            PathCommand::LineTo { x, y } => builder.line_to(x as f32, y as f32),
+            PathCommand::QuadTo { x1, y1, x, y } => {
+                builder.quad_to(x1 as f32, y1 as f32, x as f32, y as f32)
+            }
+            PathCommand::CubicTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => builder.cubic_to(
+                x1 as f32, y1 as f32, x2 as f32, y2 as f32, x as f32, y as f32,
+            ),
+            PathCommand::Close => builder.close(),
+        }
+    }
+    builder.finish()
+}
+
+/// Like cairo and CSS, an odd number of lengths is repeated to make it even.
+fn conv_dash(dash: &[f64]) -> Option<StrokeDash> {
+    if dash.is_empty() {
+        return None;
+    }
+    let mut lengths: Vec<f32> = dash.iter().map(|length| *length as f32).collect();
+    if lengths.len() % 2 == 1 {
+        lengths.extend_from_within(..);
+    }
+    StrokeDash::new(lengths, 0.0)
+}
+
+/// `None` for empty bitmaps.
+pub(super) fn conv_pixmap(bitmap: &Bitmap) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(bitmap.width(), bitmap.height())?;
+    for (pixel, rgba) in pixmap
+        .pixels_mut()
+        .iter_mut()
+        .zip(bitmap.pixels().chunks_exact(4))
+    {
+        *pixel = ColorU8::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]).premultiply();
+    }
+    Some(pixmap)
+}
+
+/// A sweep around `center` clockwise from `angle` degrees off the top,
+/// rendered at `scale_factor` pixels per logical pixel.
+fn conic_gradient(
+    (center_x, center_y): (f64, f64),
+    angle: f32,
+    stops: &[ColorStop],
+    width: f64,
+    height: f64,
+    scale_factor: f64,
+    scheme: ColorScheme,
+) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(
+        (width * scale_factor).ceil() as u32,
+        (height * scale_factor).ceil() as u32,
+    )?;
+    let pixmap_width = pixmap.width() as usize;
+    for (i, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let x = ((i % pixmap_width) as f64 + 0.5) / scale_factor - center_x;
+        let y = ((i / pixmap_width) as f64 + 0.5) / scale_factor - center_y;
+        // 0 degrees points up, y points down
+        let degrees = x.atan2(-y).to_degrees() - angle as f64;
+        let rgba = color_at(stops, (degrees.rem_euclid(360.0) / 360.0) as f32, scheme);
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        *pixel = ColorU8::from_rgba(
+            channel(rgba.r),
+            channel(rgba.g),
+            channel(rgba.b),
+            channel(rgba.a),
+        )
+        .premultiply();
+    }
+    Some(pixmap)
+}
+
+/// The color `offset` of the way along a gradient.
+fn color_at(stops: &[ColorStop], offset: f32, scheme: ColorScheme) -> Rgba {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return Rgba::default();
+    };
+    if offset <= first.offset {
+        return first.color.resolve(scheme);
+    }
+    for pair in stops.windows(2) {
+        let (from, to) = (&pair[0], &pair[1]);
+        if offset <= to.offset {
+            let t = if to.offset > from.offset {
+                (offset - from.offset) / (to.offset - from.offset)
+            } else {
+                1.0
+            };
+            let (from, to) = (from.color.resolve(scheme), to.color.resolve(scheme));
+            let mix = |a: f32, b: f32| a + (b - a) * t;
+            return Rgba::new(
+                mix(from.r, to.r),
+                mix(from.g, to.g),
+                mix(from.b, to.b),
+                mix(from.a, to.a),
+            );
+        }
+    }
+    last.color.resolve(scheme)
+}
+
+/// The shape of what was painted on `layer`, filled with `color`.
+pub(super) fn tinted(layer: &Pixmap, color: tiny_skia::Color) -> Pixmap {
+    let mut tinted = layer.clone();
+    for pixel in tinted.pixels_mut() {
+        let alpha = pixel.alpha() as f32 / 255.0 * color.alpha();
+        let channel = |value: f32| (value * alpha * 255.0).round() as u8;
+        *pixel = PremultipliedColorU8::from_rgba(
+            channel(color.red()),
+            channel(color.green()),
+            channel(color.blue()),
+            channel(1.0),
+        )
+        .unwrap_or(PremultipliedColorU8::TRANSPARENT);
+    }
+    tinted
+}
+
+/// An approximate gaussian blur with a standard deviation of `sigma`
+/// device pixels, made of three box blurs in each direction.
+pub(super) fn blur(pixmap: &mut Pixmap, sigma: f64) {
+    if sigma < 0.5 {
+        return;
+    }
+    // Three passes of a box 2r + 1 wide have a variance of (w² - 1) / 4
+    let radius = (((4.0 * sigma * sigma + 1.0).sqrt() - 1.0) / 2.0)
+        .round()
+        .max(1.0) as usize;
+    let (width, height) = (pixmap.width() as usize, pixmap.height() as usize);
+    for _ in 0..3 {
+        box_blur(pixmap.data_mut(), width, height, radius, true);
+        box_blur(pixmap.data_mut(), width, height, radius, false);
+    }
+}
+
+/// Average every channel over `radius` pixels on either side along rows
+/// or columns. Pixels outside count as transparent. Premultiplied colors
+/// stay valid since every channel is averaged the same way.
+fn box_blur(data: &mut [u8], width: usize, height: usize, radius: usize, horizontal: bool) {
+    let (lines, length, line_step, step) = if horizontal {
+        (height, width, width * 4, 4)
+    } else {
+        (width, height, 4, width * 4)
+    };
+    let window = (2 * radius + 1) as u32;
+    let mut line = vec![0u8; length * 4];
+    for index in 0..lines {
+        let start = index * line_step;
+        for i in 0..length {
+            let pixel = start + i * step;
+            line[i * 4..i * 4 + 4].copy_from_slice(&data[pixel..pixel + 4]);
+        }
+        for channel in 0..4 {
+            let mut sum: u32 = (0..=radius.min(length - 1))
+                .map(|i| line[i * 4 + channel] as u32)
+                .sum();
+            for i in 0..length {
+                data[start + i * step + channel] = ((sum + window / 2) / window) as u8;
+                if i + radius + 1 < length {
+                    sum += line[(i + radius + 1) * 4 + channel] as u32;
+                }
+                if i >= radius {
+                    sum -= line[(i - radius) * 4 + channel] as u32;
+                }
+            }
+        }
+    }
+}
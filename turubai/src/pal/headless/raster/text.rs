@@ -0,0 +1,198 @@
+use tiny_skia::{FillRule, Mask, Paint, PathBuilder, Pixmap, Rect, Shader, StrokeDash, Transform};
+
+use crate::{
+    elements::TextLineStyle,
+    font::{registry, Font},
+    pal::headless::measure::{estimated_advance, LINE_HEIGHT, POINTS_TO_PIXELS},
+};
+
+/// Where a line of text was set, in the text's coordinates.
+pub(super) struct Line {
+    baseline: f64,
+    width: f64,
+    /// The font size in logical pixels
+    size: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Decoration {
+    Underline,
+    StrikeThrough,
+}
+
+/// Paint `content` with its top leading corner at the origin, one line per
+/// `\n`, the way the built-in metrics measure it. Fonts with a registered
+/// face are drawn with its outlines, others as a block per character so
+/// pictures don't depend on the fonts installed.
+pub(super) fn draw_text(
+    canvas: &mut Pixmap,
+    content: &str,
+    font: &Font,
+    color: tiny_skia::Color,
+    transform: Transform,
+    clip: Option<&Mask>,
+) -> Vec<Line> {
+    let size = font.size() as f64 * POINTS_TO_PIXELS;
+    let (_, face) = registry::resolve(font);
+    let face = face
+        .as_ref()
+        .and_then(|face| ttf_parser::Face::parse(&face.data, face.index).ok());
+
+    let mut lines = vec![];
+    let mut builder = PathBuilder::new();
+    match face {
+        Some(face) => {
+            let scale = size / face.units_per_em() as f64;
+            let line_height = (face.ascender() - face.descender() + face.line_gap()) as f64 * scale;
+            let mut outline = Outline {
+                builder: &mut builder,
+                scale,
+                origin: (0.0, 0.0),
+            };
+            for (i, text) in content.split('\n').enumerate() {
+                let baseline = i as f64 * line_height + face.ascender() as f64 * scale;
+                let mut pen = 0.0;
+                for glyph in text.chars().filter_map(|c| face.glyph_index(c)) {
+                    outline.origin = (pen, baseline);
+                    face.outline_glyph(glyph, &mut outline);
+                    pen += face.glyph_hor_advance(glyph).unwrap_or(0) as f64 * scale;
+                }
+                lines.push(Line {
+                    baseline,
+                    width: pen,
+                    size,
+                });
+            }
+        }
+        None => {
+            for (i, text) in content.split('\n').enumerate() {
+                let baseline = (i as f64 + 0.8) * size * LINE_HEIGHT;
+                let mut pen = 0.0;
+                for c in text.chars() {
+                    let advance = estimated_advance(c, font);
+                    let block = Rect::from_xywh(
+                        (pen + advance * 0.1) as f32,
+                        (baseline - size * 0.7) as f32,
+                        (advance * 0.8) as f32,
+                        (size * 0.7) as f32,
+                    );
+                    if let Some(block) = block.filter(|_| !c.is_whitespace()) {
+                        builder.push_rect(block);
+                    }
+                    pen += advance;
+                }
+                lines.push(Line {
+                    baseline,
+                    width: pen,
+                    size,
+                });
+            }
+        }
+    }
+
+    if let Some(path) = builder.finish() {
+        let paint = Paint {
+            shader: Shader::SolidColor(color),
+            anti_alias: true,
+            ..Default::default()
+        };
+        canvas.fill_path(&path, &paint, FillRule::Winding, transform, clip);
+    }
+    lines
+}
+
+/// Draw an underline or strike through across every line.
+pub(super) fn draw_decoration(
+    canvas: &mut Pixmap,
+    lines: &[Line],
+    decoration: Decoration,
+    style: &TextLineStyle,
+    color: tiny_skia::Color,
+    transform: Transform,
+    clip: Option<&Mask>,
+) {
+    let paint = Paint {
+        shader: Shader::SolidColor(color),
+        anti_alias: true,
+        ..Default::default()
+    };
+    for line in lines.iter().filter(|line| line.width > 0.0) {
+        let mut thickness = (line.size / 14.0).max(1.0);
+        if *style == TextLineStyle::Thick {
+            thickness *= 2.0;
+        }
+        let y = match decoration {
+            Decoration::Underline => line.baseline + line.size * 0.12,
+            Decoration::StrikeThrough => line.baseline - line.size * 0.3,
+        };
+        let offsets: &[f64] = match style {
+            TextLineStyle::Double => &[0.0, thickness * 2.0],
+            _ => &[0.0],
+        };
+        let dash = match style {
+            TextLineStyle::Dotted => StrokeDash::new(vec![thickness as f32; 2], 0.0),
+            TextLineStyle::Dashed => {
+                StrokeDash::new(vec![thickness as f32 * 3.0, thickness as f32 * 2.0], 0.0)
+            }
+            _ => None,
+        };
+        let stroke = tiny_skia::Stroke {
+            width: thickness as f32,
+            dash,
+            ..Default::default()
+        };
+        for offset in offsets {
+            let mut builder = PathBuilder::new();
+            builder.move_to(0.0, (y + offset) as f32);
+            builder.line_to(line.width as f32, (y + offset) as f32);
+            if let Some(path) = builder.finish() {
+                canvas.stroke_path(&path, &paint, &stroke, transform, clip);
+            }
+        }
+    }
+}
+
+/// Adds glyph outlines, which are in font units with y pointing up, to a
+/// path in logical pixels.
+struct Outline<'a> {
+    builder: &'a mut PathBuilder,
+    scale: f64,
+    /// Where the glyph's baseline starts
+    origin: (f64, f64),
+}
+
+impl Outline<'_> {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (self.origin.0 + x as f64 * self.scale) as f32,
+            (self.origin.1 - y as f64 * self.scale) as f32,
+        )
+    }
+}
+
+impl ttf_parser::OutlineBuilder for Outline<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let ((x1, y1), (x, y)) = (self.point(x1, y1), self.point(x, y));
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let ((x1, y1), (x2, y2), (x, y)) =
+            (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
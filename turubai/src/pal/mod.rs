@@ -4,6 +4,8 @@ mod apple;
 #[cfg(feature = "gtk")]
 mod gtk;
 
+pub mod headless;
+
 use crate::{Application, Backend};
 
 pub fn takeover(app: Box<dyn Application>) -> ! {
@@ -0,0 +1,80 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{elements::{Element, Modifiers}, environment::Environment, fill::Fill, shadow::ShadowDescriptor};
+
+pub struct BackgroundInner {
+    fill: Fill,
+    modifiers: Modifiers,
+    child: Box<dyn Element>
+}
+
+/// Paints a [`Fill`] (a color, gradient or image) behind its child.
+pub struct Background {
+    inner: Arc<Mutex<BackgroundInner>>
+}
+
+impl Background {
+    pub fn new(fill: impl Into<Fill>, child: Box<dyn Element>) -> Self {
+        let inner = BackgroundInner {
+            fill: fill.into(),
+            modifiers: Modifiers::default(),
+            child
+        };
+        Self::from(inner)
+    }
+
+    pub fn turubai_new_with_1_args(
+        fill: impl Into<Fill>,
+        modifiers: Modifiers,
+        children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        let mut children = children(modifiers.clone());
+        Self::new(fill, children.pop().unwrap()).with_modifiers(modifiers)
+    }
+}
+
+impl Background {
+    fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        self.inner.lock().unwrap().modifiers = modifiers;
+        self
+    }
+}
+
+impl Element for Background {
+    fn name(&self) -> &'static str {
+        "background"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Background"
+    }
+
+    fn child_count(&self) -> usize {
+        1
+    }
+
+    fn for_each_child(&self, f: &mut dyn FnMut(&dyn Element)) {
+        f(self.inner.lock().unwrap().child.as_ref())
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
+
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
+        let fill = self.inner.lock().unwrap().fill.map_colors(|color| env.themed_color(color));
+        ShadowDescriptor::background(fill)
+    }
+}
+
+impl From<BackgroundInner> for Background {
+    fn from(value: BackgroundInner) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(value)),
+        }
+    }
+}
+
+pub fn background(fill: impl Into<Fill>, child: Box<dyn Element>, modifiers: Modifiers) -> Background {
+    Background::new(fill, child).with_modifiers(modifiers)
+}
@@ -1,9 +1,10 @@
 use std::sync::{Arc, Mutex};
 
-use crate::{color::Color, elements::{Element, Modifiers}, shadow::ShadowDescriptor};
+use crate::{color::Color, elements::{Element, Modifiers}, environment::Environment, shadow::ShadowDescriptor};
 
 pub struct BackgroundColorInner {
     color: Color,
+    modifiers: Modifiers,
     child: Box<dyn Element>
 }
 
@@ -15,6 +16,7 @@ impl BackgroundColor {
     pub fn new(color: &Color, child: Box<dyn Element>) -> Self {
         let inner = BackgroundColorInner {
             color: color.clone(),
+            modifiers: Modifiers::default(),
             child: child
         };
         Self::from(inner)
@@ -26,7 +28,14 @@ impl BackgroundColor {
         children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
     ) -> Self {
         let mut children = children(modifiers.clone());
-        Self::new(&color, children.pop().unwrap())
+        Self::new(&color, children.pop().unwrap()).with_modifiers(modifiers)
+    }
+}
+
+impl BackgroundColor {
+    fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        self.inner.lock().unwrap().modifiers = modifiers;
+        self
     }
 }
 
@@ -47,8 +56,12 @@ impl Element for BackgroundColor {
         f(self.inner.lock().unwrap().child.as_ref())
     }
 
-    fn shadow_descriptor(&self) -> crate::shadow::ShadowDescriptor {
-        ShadowDescriptor::background_color(self.inner.lock().unwrap().color.clone())
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
+
+    fn shadow_descriptor(&self, env: &Environment) -> crate::shadow::ShadowDescriptor {
+        ShadowDescriptor::background_color(env.themed_color(&self.inner.lock().unwrap().color))
     }
 }
 
@@ -61,8 +74,8 @@ impl From<BackgroundColorInner> for BackgroundColor {
     }
 }
 
-pub fn background_color(color: Color, child: Box<dyn Element>, _modifiers: Modifiers) -> BackgroundColor {
-    BackgroundColor::new(&color, child)
+pub fn background_color(color: Color, child: Box<dyn Element>, modifiers: Modifiers) -> BackgroundColor {
+    BackgroundColor::new(&color, child).with_modifiers(modifiers)
 }
 
 
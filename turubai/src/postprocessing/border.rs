@@ -0,0 +1,159 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    color::Color,
+    elements::{Element, Modifiers},
+    environment::Environment,
+    postprocessing::CornerRadii,
+    shadow::ShadowDescriptor,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BorderStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    Double,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BorderModifiers {
+    /// Rounds the border's corners; `None` uses the theme's border radius
+    pub radius: Option<f64>,
+}
+
+pub struct BorderInner {
+    width: f64,
+    color: Color,
+    style: BorderStyle,
+    modifiers: Modifiers,
+    child: Box<dyn Element>,
+}
+
+/// Strokes a border around its child. The border takes up space: the child
+/// is inset by its width on every side.
+pub struct Border {
+    inner: Arc<Mutex<BorderInner>>,
+}
+
+impl Border {
+    pub fn new(width: f64, color: Color, style: BorderStyle, child: Box<dyn Element>) -> Self {
+        let inner = BorderInner {
+            width,
+            color,
+            style,
+            modifiers: Modifiers::default(),
+            child,
+        };
+        Self::from(inner)
+    }
+
+    pub fn turubai_new_with_3_args(
+        width: f64,
+        color: Color,
+        style: BorderStyle,
+        modifiers: Modifiers,
+        children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        let mut children = children(modifiers.clone());
+        Self::new(width, color, style, children.pop().unwrap()).with_modifiers(modifiers)
+    }
+
+    fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        self.inner.lock().unwrap().modifiers = modifiers;
+        self
+    }
+}
+
+impl Element for Border {
+    fn name(&self) -> &'static str {
+        "border"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Border"
+    }
+
+    fn child_count(&self) -> usize {
+        1
+    }
+
+    fn for_each_child(&self, f: &mut dyn FnMut(&dyn Element)) {
+        f(self.inner.lock().unwrap().child.as_ref())
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
+
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
+        let inner = self.inner.lock().unwrap();
+        // Read when the tree is built, so stylesheet rules reach it
+        let radius = inner.modifiers.lock().unwrap().border.radius;
+        let radius = radius.unwrap_or(env.theme.radii.border);
+        ShadowDescriptor::border(
+            inner.width,
+            env.themed_color(&inner.color),
+            inner.style,
+            CornerRadii::all(radius),
+        )
+    }
+}
+
+impl From<BorderInner> for Border {
+    fn from(value: BorderInner) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(value)),
+        }
+    }
+}
+
+pub fn border(
+    width: f64,
+    color: Color,
+    style: BorderStyle,
+    child: Box<dyn Element>,
+    modifiers: Modifiers,
+) -> Border {
+    Border::new(width, color, style, child).with_modifiers(modifiers)
+}
+
+#[test]
+fn test_border_layout() {
+    use crate::{
+        color::{Color, ColorScheme},
+        elements::{Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::{border, clip, corner_radius, BorderStyle},
+        shadow::NodeKind,
+    };
+
+    let modifiers = Modifiers::default();
+    let text = Box::new(Text::new("Hello", modifiers.fork()));
+    let rounded = corner_radius(8.0, text, modifiers.fork());
+    let bordered = border(
+        2.0,
+        Color::Separator,
+        BorderStyle::Solid,
+        Box::new(rounded),
+        modifiers.fork(),
+    );
+    let window = Context::new(ColorScheme::Light).render(
+        Box::new(clip(Box::new(bordered), modifiers.fork())),
+        400.0,
+        300.0,
+    );
+
+    let border_node = &window.root.children[0];
+    let inner_node = &border_node.children[0];
+    let border_layout = window.layout(border_node).unwrap();
+    let inner_layout = window.layout(inner_node).unwrap();
+
+    assert!(matches!(border_node.kind, NodeKind::Border { width, .. } if width == 2.0));
+    assert_eq!(inner_layout.location.x, 2.0);
+    assert_eq!(inner_layout.location.y, 2.0);
+    assert_eq!(border_layout.size.width, inner_layout.size.width + 4.0);
+    assert_eq!(border_layout.size.height, inner_layout.size.height + 4.0);
+}
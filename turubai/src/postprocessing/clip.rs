@@ -0,0 +1,58 @@
+use crate::{
+    elements::{Element, Modifiers},
+    environment::Environment,
+    shadow::ShadowDescriptor,
+};
+
+/// Hides whatever part of its child is drawn outside of its bounds.
+pub struct Clip {
+    modifiers: Modifiers,
+    child: Box<dyn Element>,
+}
+
+impl Clip {
+    pub fn new(child: Box<dyn Element>) -> Self {
+        Self {
+            modifiers: Modifiers::default(),
+            child,
+        }
+    }
+
+    pub fn turubai_new_with_0_args(
+        modifiers: Modifiers,
+        children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        let mut children = children(modifiers.clone());
+        clip(children.pop().unwrap(), modifiers)
+    }
+}
+
+impl Element for Clip {
+    fn name(&self) -> &'static str {
+        "clip"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Clip"
+    }
+
+    fn child_count(&self) -> usize {
+        1
+    }
+
+    fn for_each_child(&self, f: &mut dyn FnMut(&dyn Element)) {
+        f(self.child.as_ref())
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.modifiers.clone())
+    }
+
+    fn shadow_descriptor(&self, _env: &Environment) -> ShadowDescriptor {
+        ShadowDescriptor::clip()
+    }
+}
+
+pub fn clip(child: Box<dyn Element>, modifiers: Modifiers) -> Clip {
+    Clip { modifiers, child }
+}
@@ -0,0 +1,156 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    elements::{Element, Modifiers},
+    environment::Environment,
+    shadow::ShadowDescriptor,
+};
+
+/// A radius for each corner, in logical pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CornerRadii {
+    pub top_leading: f64,
+    pub top_trailing: f64,
+    pub bottom_trailing: f64,
+    pub bottom_leading: f64,
+}
+
+impl CornerRadii {
+    pub fn all(radius: f64) -> Self {
+        Self {
+            top_leading: radius,
+            top_trailing: radius,
+            bottom_trailing: radius,
+            bottom_leading: radius,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CornerRadiiModifiers {
+    /// `None`, with no corner set either, uses the theme's corner radius
+    pub all: Option<f64>,
+    pub top_leading: Option<f64>,
+    pub top_trailing: Option<f64>,
+    pub bottom_trailing: Option<f64>,
+    pub bottom_leading: Option<f64>,
+}
+
+impl CornerRadiiModifiers {
+    /// The radii set, with corners that aren't set using `all`. `fallback`
+    /// is used for `all` when no radius is set at all.
+    pub fn resolve(&self, fallback: f64) -> CornerRadii {
+        let corners = [
+            self.top_leading,
+            self.top_trailing,
+            self.bottom_trailing,
+            self.bottom_leading,
+        ];
+        let all = match self.all {
+            Some(all) => all,
+            None if corners.iter().all(Option::is_none) => fallback,
+            None => 0.0,
+        };
+        CornerRadii {
+            top_leading: self.top_leading.unwrap_or(all),
+            top_trailing: self.top_trailing.unwrap_or(all),
+            bottom_trailing: self.bottom_trailing.unwrap_or(all),
+            bottom_leading: self.bottom_leading.unwrap_or(all),
+        }
+    }
+}
+
+pub struct CornerRadiusInner {
+    /// `None` to read the `corner_radii` modifiers when the tree is built
+    radii: Option<CornerRadii>,
+    modifiers: Modifiers,
+    child: Box<dyn Element>,
+}
+
+/// Rounds the corners of its child, clipping anything outside of them.
+pub struct CornerRadius {
+    inner: Arc<Mutex<CornerRadiusInner>>,
+}
+
+impl CornerRadius {
+    pub fn new(radii: CornerRadii, child: Box<dyn Element>) -> Self {
+        let inner = CornerRadiusInner {
+            radii: Some(radii),
+            modifiers: Modifiers::default(),
+            child,
+        };
+        Self::from(inner)
+    }
+
+    pub fn turubai_new_with_1_args(
+        radius: f64,
+        modifiers: Modifiers,
+        children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
+    ) -> Self {
+        let mut children = children(modifiers.clone());
+        Self::new(CornerRadii::all(radius), children.pop().unwrap()).with_modifiers(modifiers)
+    }
+
+    fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        self.inner.lock().unwrap().modifiers = modifiers;
+        self
+    }
+}
+
+impl Element for CornerRadius {
+    fn name(&self) -> &'static str {
+        "corner_radius"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Corner Radius"
+    }
+
+    fn child_count(&self) -> usize {
+        1
+    }
+
+    fn for_each_child(&self, f: &mut dyn FnMut(&dyn Element)) {
+        f(self.inner.lock().unwrap().child.as_ref())
+    }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
+
+    fn shadow_descriptor(&self, env: &Environment) -> ShadowDescriptor {
+        let inner = self.inner.lock().unwrap();
+        let radii = inner.radii.unwrap_or_else(|| {
+            let modifiers = inner.modifiers.lock().unwrap();
+            modifiers.corner_radii.resolve(env.theme.radii.corner)
+        });
+        ShadowDescriptor::corner_radius(radii)
+    }
+}
+
+impl From<CornerRadiusInner> for CornerRadius {
+    fn from(value: CornerRadiusInner) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(value)),
+        }
+    }
+}
+
+/// Round every corner by `radius`.
+pub fn corner_radius(radius: f64, child: Box<dyn Element>, modifiers: Modifiers) -> CornerRadius {
+    CornerRadius::new(CornerRadii::all(radius), child).with_modifiers(modifiers)
+}
+
+/// Round each corner separately, e.g. `.corner_radii(top_leading: 8.0, top_trailing: 8.0)`.
+pub fn corner_radii(child: Box<dyn Element>, modifiers: Modifiers) -> CornerRadius {
+    CornerRadius::from(CornerRadiusInner {
+        radii: None,
+        modifiers,
+        child,
+    })
+}
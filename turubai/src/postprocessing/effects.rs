@@ -0,0 +1,174 @@
+use crate::{
+    color::Color,
+    elements::{Element, Modifiers},
+};
+
+/// A shadow cast by what an element paints, not its layout box, offset
+/// from it and blurred by `radius`, like CSS `filter: drop-shadow()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropShadow {
+    pub color: Color,
+    pub radius: f64,
+    /// `(x, y)` in logical pixels, positive values move the shadow right and down
+    pub offset: (f64, f64),
+}
+
+/// Paint-only effects of an element. They are drawn around or over the
+/// element's layout box and never change its size or position, so they
+/// are recorded on the element's own shadow node instead of wrapping it.
+///
+/// Unlike other modifiers they are not inherited: a faded stack already
+/// fades its children.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EffectModifiers {
+    /// From 0 (invisible) to 1 (opaque)
+    pub opacity: f32,
+    /// Painted in order, the first one at the bottom. Like chained
+    /// `drop-shadow()` filters, each is also cast by the shadows after it
+    pub shadows: Vec<DropShadow>,
+    /// Gaussian blur radius in logical pixels
+    pub blur: f64,
+}
+
+impl Default for EffectModifiers {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            shadows: vec![],
+            blur: 0.0,
+        }
+    }
+}
+
+impl EffectModifiers {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// How far the effects reach beyond the layout box on each side, as
+    /// `(left, top, right, bottom)`.
+    pub fn overflow(&self) -> (f64, f64, f64, f64) {
+        let mut overflow = (self.blur, self.blur, self.blur, self.blur);
+        // Each shadow reaches beyond everything painted before it
+        for shadow in &self.shadows {
+            let (x, y) = shadow.offset;
+            overflow.0 += (shadow.radius - x).max(0.0);
+            overflow.1 += (shadow.radius - y).max(0.0);
+            overflow.2 += (shadow.radius + x).max(0.0);
+            overflow.3 += (shadow.radius + y).max(0.0);
+        }
+        overflow
+    }
+}
+
+fn with_effects(
+    child: Box<dyn Element>,
+    effect: &str,
+    f: impl FnOnce(&mut EffectModifiers),
+) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => f(&mut modifiers.lock().unwrap().effects),
+        None => eprintln!("[turubai] {} can't be given {effect}", child.display_name()),
+    }
+    child
+}
+
+/// Fade the child. Applying it twice multiplies the opacities.
+pub fn opacity(opacity: f32, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    with_effects(child, "an opacity", |effects| {
+        effects.opacity *= opacity.clamp(0.0, 1.0)
+    })
+}
+
+/// Paint a drop shadow behind the child. Shadows stack, each one below and
+/// cast by the ones already given.
+pub fn shadow(
+    color: Color,
+    radius: f64,
+    offset: (f64, f64),
+    child: Box<dyn Element>,
+    _modifiers: Modifiers,
+) -> Box<dyn Element> {
+    with_effects(child, "a shadow", |effects| {
+        effects.shadows.push(DropShadow {
+            color,
+            radius: radius.max(0.0),
+            offset,
+        })
+    })
+}
+
+/// Blur the child, including its shadows.
+pub fn blur(radius: f64, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    with_effects(child, "a blur", |effects| effects.blur = radius.max(0.0))
+}
+
+#[test]
+fn test_effects() {
+    use crate::{
+        color::{Color, ColorScheme},
+        elements::{Element, Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::{blur, opacity, shadow, DropShadow, EffectModifiers},
+        theme::{Palette, Theme},
+    };
+
+    let card = |effects: bool| -> Box<dyn Element> {
+        let modifiers = Modifiers::default();
+        let text: Box<dyn Element> = Box::new(Text::new("Card", modifiers.fork()));
+        if !effects {
+            return text;
+        }
+        let text = opacity(0.5, text, modifiers.fork());
+        let text = opacity(0.5, text, modifiers.fork());
+        let text = shadow(Color::Separator, 4.0, (0.0, 2.0), text, modifiers.fork());
+        blur(1.0, text, modifiers.fork())
+    };
+
+    let plain = Context::new(ColorScheme::Light).render(card(false), 400.0, 300.0);
+    let mut window = Context::new(ColorScheme::Light).render(card(true), 400.0, 300.0);
+
+    // Effects only paint, the layout is the same as without them
+    let layout = *window.layout(&window.root).unwrap();
+    assert_eq!(layout, *plain.layout(&plain.root).unwrap());
+    assert!(plain.root.effects.is_empty());
+
+    let effects = &window.root.effects;
+    assert_eq!(effects.opacity, 0.25);
+    assert_eq!(effects.blur, 1.0);
+    assert_eq!(
+        effects.shadows,
+        vec![DropShadow {
+            color: Color::Separator,
+            radius: 4.0,
+            offset: (0.0, 2.0),
+        }]
+    );
+
+    let bounds = window.paint_bounds(&window.root).unwrap();
+    assert_eq!(bounds.left, layout.location.x - 5.0);
+    assert_eq!(bounds.top, layout.location.y - 3.0);
+    assert_eq!(bounds.bottom, layout.location.y + layout.size.height + 7.0);
+
+    // Like chained `drop-shadow()` filters, a shadow reaches past the others
+    let drop = DropShadow {
+        color: Color::Separator,
+        radius: 2.0,
+        offset: (4.0, 0.0),
+    };
+    let chained = EffectModifiers {
+        shadows: vec![drop; 2],
+        ..EffectModifiers::default()
+    };
+    assert_eq!(chained.overflow(), (0.0, 4.0, 12.0, 4.0));
+
+    // Shadow colors go through the palette
+    let palette = Palette {
+        separator: Color::SystemRed,
+        ..Palette::default()
+    };
+    window.set_theme(Theme::default().with_palette(palette));
+    assert_eq!(window.root.effects.shadows[0].color, Color::SystemRed);
+}
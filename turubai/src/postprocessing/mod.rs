@@ -1,11 +1,29 @@
 use crate::elements::Element;
 
+mod background;
 mod background_color;
+mod border;
+mod clip;
+mod corner_radius;
+mod effects;
 mod padding;
 mod frame;
+mod selector;
+mod shape;
+mod transform;
+mod transition;
 
+pub use background::*;
 pub use background_color::*;
+pub use border::*;
+pub use clip::*;
+pub use corner_radius::*;
+pub use effects::*;
 pub use padding::*;
 pub use frame::*;
+pub use selector::*;
+pub use shape::*;
+pub use transform::*;
+pub use transition::*;
 
 pub trait PostProcess: Element {}
@@ -1,4 +1,5 @@
 use crate::elements::{Element, Modifiers};
+use crate::environment::Environment;
 use crate::shadow::ShadowDescriptor;
 use crate::units::Unit;
 
@@ -45,12 +46,11 @@ impl PaddingModifiers {
     }
 }
 
+/// Insets its child. The insets are read from the `padding` modifiers
+/// when the shadow tree is built, so stylesheet rules can change them.
 pub struct Padding {
-    pub top: Unit,
-    pub bottom: Unit,
-    pub left: Unit,
-    pub right: Unit,
     pub child: Box<dyn Element>,
+    modifiers: Modifiers,
 }
 
 impl Padding {
@@ -61,13 +61,16 @@ impl Padding {
         right: Unit,
         child: Box<dyn Element>,
     ) -> Self {
-        Self {
-            top,
-            bottom,
-            left,
-            right,
-            child,
+        let modifiers = Modifiers::default();
+        {
+            let mut lock = modifiers.lock().unwrap();
+            let pm = &mut lock.padding;
+            pm.top = Some(top);
+            pm.bottom = Some(bottom);
+            pm.left = Some(left);
+            pm.right = Some(right);
         }
+        Self { child, modifiers }
     }
 }
 
@@ -80,15 +83,21 @@ impl Element for Padding {
         "Padding"
     }
 
-    fn shadow_descriptor(&self) -> crate::shadow::ShadowDescriptor {
+    fn shadow_descriptor(&self, env: &Environment) -> crate::shadow::ShadowDescriptor {
+        let pm = self.modifiers.lock().unwrap().padding;
+        let inset = |unit: Unit| env.resolve_unit(unit).to_pixels(None);
         ShadowDescriptor::padding(
-            self.top.to_pixels(None),
-            self.bottom.to_pixels(None),
-            self.left.to_pixels(None),
-            self.right.to_pixels(None),
+            inset(pm.resolve_top()),
+            inset(pm.resolve_bottom()),
+            inset(pm.resolve_left()),
+            inset(pm.resolve_right()),
         )
     }
 
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.modifiers.clone())
+    }
+
     fn child_count(&self) -> usize {
         1
     }
@@ -99,13 +108,5 @@ impl Element for Padding {
 }
 
 pub fn padding(child: Box<dyn Element>, modifiers: Modifiers) -> Padding {
-    let lock = modifiers.lock().unwrap();
-    let pm = &lock.padding;
-    Padding::new(
-        pm.resolve_top(),
-        pm.resolve_bottom(),
-        pm.resolve_left(),
-        pm.resolve_right(),
-        child,
-    )
+    Padding { child, modifiers }
 }
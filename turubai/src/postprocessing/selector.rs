@@ -0,0 +1,30 @@
+use crate::elements::{Element, Modifiers};
+
+/// Give the child an id that stylesheet rules can select with `#id`.
+pub fn id(id: &str, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => modifiers.lock().unwrap().selector.id = Some(id.to_string()),
+        None => eprintln!("[turubai] {} can't be given an id", child.display_name()),
+    }
+    child
+}
+
+/// Give the child a key telling it apart from its siblings whenever the
+/// interface is rebuilt, e.g. the id of the item it shows, so it keeps its
+/// identity when siblings before it come and go.
+pub fn key(key: impl ToString, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => modifiers.lock().unwrap().selector.key = Some(key.to_string()),
+        None => eprintln!("[turubai] {} can't be given a key", child.display_name()),
+    }
+    child
+}
+
+/// Add a class that stylesheet rules can select with `.class`.
+pub fn class(class: &str, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => modifiers.lock().unwrap().selector.classes.push(class.to_string()),
+        None => eprintln!("[turubai] {} can't be given a class", child.display_name()),
+    }
+    child
+}
@@ -0,0 +1,32 @@
+use crate::{
+    elements::{Element, Modifiers},
+    fill::Fill,
+    shape::Stroke,
+};
+
+/// Paint the inside of the child shape.
+pub fn fill(paint: impl Into<Fill>, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => modifiers.lock().unwrap().shape.fill = Some(paint.into()),
+        None => eprintln!("[turubai] {} can't be filled", child.display_name()),
+    }
+    child
+}
+
+/// Draw the outline of the child shape `width` logical pixels wide,
+/// dashed unless `dash` is empty.
+pub fn stroke(
+    paint: impl Into<Fill>,
+    width: f64,
+    dash: impl Into<Vec<f64>>,
+    child: Box<dyn Element>,
+    _modifiers: Modifiers,
+) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => {
+            modifiers.lock().unwrap().shape.stroke = Some(Stroke::new(paint, width).dashed(dash))
+        }
+        None => eprintln!("[turubai] {} can't be stroked", child.display_name()),
+    }
+    child
+}
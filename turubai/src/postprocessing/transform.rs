@@ -0,0 +1,259 @@
+use crate::{
+    elements::{Element, Modifiers},
+    fill::UnitPoint,
+};
+
+/// A 2D affine transform mapping `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffineTransform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl AffineTransform {
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self {
+            e: x,
+            f: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn scaling(x: f32, y: f32) -> Self {
+        Self {
+            a: x,
+            d: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotation by `degrees`, clockwise on screen as y points down.
+    pub fn rotation(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// `self` followed by `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            a: other.a * self.a + other.c * self.b,
+            b: other.b * self.a + other.d * self.b,
+            c: other.a * self.c + other.c * self.d,
+            d: other.b * self.c + other.d * self.d,
+            e: other.a * self.e + other.c * self.f + other.e,
+            f: other.b * self.e + other.d * self.f + other.f,
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    /// `None` when the transform collapses everything onto a line or point,
+    /// e.g. after scaling by zero.
+    pub fn invert(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            e: -(a * self.e + c * self.f),
+            f: -(b * self.e + d * self.f),
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+}
+
+/// One step of an element's transform. Anchors are relative to the
+/// element's own size, e.g. [`UnitPoint::CENTER`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransformOp {
+    Offset { x: f64, y: f64 },
+    Scale { x: f64, y: f64, anchor: UnitPoint },
+    /// Clockwise, in degrees
+    Rotate { angle: f64, anchor: UnitPoint },
+}
+
+/// Transforms applied on top of an element's laid out position. Like
+/// effects they are paint-only: siblings are laid out as if the element
+/// weren't transformed. They are not inherited, as children already move
+/// with their parent.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransformModifiers {
+    /// Applied in order, the first one closest to the element
+    pub ops: Vec<TransformOp>,
+}
+
+impl TransformModifiers {
+    pub fn is_identity(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// The transform for an element of `width` x `height`, in its own
+    /// coordinates (before its layout position is added).
+    pub fn resolve(&self, width: f32, height: f32) -> AffineTransform {
+        let around = |anchor: &UnitPoint, transform: AffineTransform| {
+            let (x, y) = (anchor.x * width, anchor.y * height);
+            AffineTransform::translation(-x, -y)
+                .then(&transform)
+                .then(&AffineTransform::translation(x, y))
+        };
+
+        self.ops.iter().fold(AffineTransform::IDENTITY, |acc, op| {
+            let step = match op {
+                TransformOp::Offset { x, y } => AffineTransform::translation(*x as f32, *y as f32),
+                TransformOp::Scale { x, y, anchor } => {
+                    around(anchor, AffineTransform::scaling(*x as f32, *y as f32))
+                }
+                TransformOp::Rotate { angle, anchor } => {
+                    around(anchor, AffineTransform::rotation(*angle as f32))
+                }
+            };
+            acc.then(&step)
+        })
+    }
+}
+
+fn with_transform(child: Box<dyn Element>, op: TransformOp) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => modifiers.lock().unwrap().transform.ops.push(op),
+        None => eprintln!("[turubai] {} can't be transformed", child.display_name()),
+    }
+    child
+}
+
+/// Move the child by `(x, y)` logical pixels without moving its siblings.
+pub fn offset(x: f64, y: f64, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    with_transform(child, TransformOp::Offset { x, y })
+}
+
+/// Scale the child around `anchor`.
+pub fn scale(
+    x: f64,
+    y: f64,
+    anchor: UnitPoint,
+    child: Box<dyn Element>,
+    _modifiers: Modifiers,
+) -> Box<dyn Element> {
+    with_transform(child, TransformOp::Scale { x, y, anchor })
+}
+
+/// Rotate the child clockwise by `angle` degrees around `anchor`.
+pub fn rotate(
+    angle: f64,
+    anchor: UnitPoint,
+    child: Box<dyn Element>,
+    _modifiers: Modifiers,
+) -> Box<dyn Element> {
+    with_transform(child, TransformOp::Rotate { angle, anchor })
+}
+
+#[test]
+fn test_transforms() {
+    use taffy::Layout;
+
+    use crate::{
+        color::ColorScheme,
+        composition::HStack,
+        elements::{Element, Modifiers, Text},
+        fill::UnitPoint,
+        pal::headless::Context,
+        postprocessing::{offset, rotate, scale},
+    };
+
+    let row = |transformed: bool| -> Box<dyn Element> {
+        let modifiers = Modifiers::default();
+        let text = |content| -> Box<dyn Element> { Box::new(Text::new(content, modifiers.fork())) };
+        let (left, middle) = (text("Left"), text("Middle"));
+        let (left, middle) = match transformed {
+            true => (
+                scale(2.0, 2.0, UnitPoint::TOP_LEADING, left, modifiers.fork()),
+                offset(0.0, 100.0, middle, modifiers.fork()),
+            ),
+            false => (left, middle),
+        };
+        Box::new(HStack::new(modifiers.clone(), vec![left, middle]))
+    };
+
+    let plain = Context::new(ColorScheme::Light).render(row(false), 400.0, 300.0);
+    let window = Context::new(ColorScheme::Light).render(row(true), 400.0, 300.0);
+
+    // Siblings are laid out as if nothing was transformed
+    for (node, plain_node) in window.root.children.iter().zip(&plain.root.children) {
+        assert_eq!(window.layout(node), plain.layout(plain_node));
+    }
+
+    let left = &window.root.children[0];
+    let middle = &window.root.children[1];
+    let left_layout = *window.layout(left).unwrap();
+    let middle_layout = *window.layout(middle).unwrap();
+    // The stack is the root, at the window's origin
+    let center = |layout: &Layout| {
+        (
+            layout.location.x + layout.size.width / 2.0,
+            layout.location.y + layout.size.height / 2.0,
+        )
+    };
+
+    // The middle text moved down and out of the stack, and is hit there
+    let (x, y) = center(&middle_layout);
+    assert!(!std::ptr::eq(window.hit_test(x, y).unwrap(), middle));
+    assert!(std::ptr::eq(window.hit_test(x, y + 100.0).unwrap(), middle));
+
+    // The left text grew over the middle one's original place
+    let (x, y) = center(&left_layout);
+    let (x, y) = (x + left_layout.size.width / 2.0 + 1.0, y);
+    assert!(std::ptr::eq(window.hit_test(x, y).unwrap(), left));
+
+    // A wide text turned on its side is hit above and below its layout box
+    let modifiers = Modifiers::default();
+    let text = Box::new(Text::new("Spinning", modifiers.fork()));
+    let spun = rotate(90.0, UnitPoint::CENTER, text, modifiers.fork());
+    let window = Context::new(ColorScheme::Light).render(spun, 400.0, 300.0);
+    let layout = *window.layout(&window.root).unwrap();
+    assert!(layout.size.width > layout.size.height);
+    let (x, y) = center(&layout);
+    let below = y + layout.size.width / 2.0 - 1.0;
+    assert!(below > y + layout.size.height);
+    assert!(window.hit_test(x, below).is_some());
+    assert!(window.hit_test(x + layout.size.width / 2.0 - 1.0, y).is_none());
+}
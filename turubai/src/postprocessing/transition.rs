@@ -0,0 +1,18 @@
+use crate::{
+    animation::{Transition, TransitionModifiers},
+    elements::{Element, Modifiers},
+};
+
+/// Run `transition` when an animated change inserts or removes the child.
+pub fn transition(transition: Transition, child: Box<dyn Element>, _modifiers: Modifiers) -> Box<dyn Element> {
+    match child.modifiers() {
+        Some(modifiers) => {
+            modifiers.lock().unwrap().transition = TransitionModifiers {
+                insertion: transition,
+                removal: transition,
+            }
+        }
+        None => eprintln!("[turubai] {} can't be given a transition", child.display_name()),
+    }
+    child
+}
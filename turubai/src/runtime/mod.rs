@@ -3,7 +3,10 @@ use std::sync::Arc;
 use std::sync::Mutex;
 
 use crate::elements::{Element, Modifiers};
-use crate::shadow::ShadowDescriptor;
+use crate::environment::Environment;
+use crate::shadow::{NodeIdentity, ShadowDescriptor};
+use crate::stylesheet::Stylesheet;
+use crate::theme::Theme;
 use crate::Application;
 
 pub struct WindowTemplate {
@@ -13,6 +16,8 @@ pub struct WindowTemplate {
 struct WindowTemplateInner {
     id: String,
     title: String,
+    theme: Option<Theme>,
+    stylesheet: Option<Stylesheet>,
     modifiers: Modifiers,
     child: Option<Box<dyn Element>>,
 }
@@ -38,8 +43,12 @@ impl WindowTemplate {
         modifiers: Modifiers,
         children: impl FnOnce(Modifiers) -> Vec<Box<dyn Element>>,
     ) -> Self {
-        let mods = modifiers.lock().unwrap();
+        let mut mods = modifiers.lock().unwrap();
         let title = mods.window_template.title.to_string();
+        let theme = mods.window_template.theme.clone();
+        let stylesheet = mods.window_template.stylesheet.clone();
+        // Windows are told apart by their id
+        mods.selector.key = Some(id.to_string());
         std::mem::drop(mods);
 
         let children = children(modifiers.clone());
@@ -49,6 +58,8 @@ impl WindowTemplate {
         let inner = WindowTemplateInner {
             id: id.to_string(),
             title,
+            theme,
+            stylesheet,
             modifiers,
             child,
         };
@@ -58,8 +69,14 @@ impl WindowTemplate {
         }
     }
 
-    pub fn identifier(&self) -> usize {
-        self.inner.as_ref() as *const _ as usize
+    /// The identity of the window's shadow node, the same whenever the
+    /// window is built again with the same id.
+    pub fn identifier(&self) -> NodeIdentity {
+        let key = self.modifiers().and_then(|modifiers| {
+            let selector = &modifiers.lock().unwrap().selector;
+            selector.key.clone().or_else(|| selector.id.clone())
+        });
+        NodeIdentity::new(None, self.display_name(), key.as_deref(), 0)
     }
 
     pub fn title(&self) -> String {
@@ -69,6 +86,16 @@ impl WindowTemplate {
     pub fn set_title(&self, title: impl Into<String>) {
         self.inner.lock().unwrap().title = title.into();
     }
+
+    pub fn theme(&self) -> Option<Theme> {
+        self.inner.lock().unwrap().theme.clone()
+    }
+
+    /// Replace the window's theme. Takes effect the next time the shadow
+    /// tree is built from this window.
+    pub fn set_theme(&self, theme: Option<Theme>) {
+        self.inner.lock().unwrap().theme = theme;
+    }
 }
 
 impl Element for WindowTemplate {
@@ -80,11 +107,27 @@ impl Element for WindowTemplate {
         "Window"
     }
 
-    fn shadow_descriptor(&self) -> ShadowDescriptor {
+    fn shadow_descriptor(&self, _env: &Environment) -> ShadowDescriptor {
         let inner = self.inner.lock().unwrap();
         ShadowDescriptor::window(inner.title.clone())
     }
 
+    fn environment(&self, env: &Environment) -> Option<Environment> {
+        let inner = self.inner.lock().unwrap();
+        if inner.theme.is_none() && inner.stylesheet.is_none() {
+            return None;
+        }
+
+        let mut env = env.clone();
+        if let Some(theme) = &inner.theme {
+            env = env.with_theme(theme.clone());
+        }
+        if let Some(stylesheet) = &inner.stylesheet {
+            env = env.with_stylesheet(stylesheet.clone());
+        }
+        Some(env)
+    }
+
     fn child_count(&self) -> usize {
         self.inner.lock().unwrap().child.is_some() as usize
     }
@@ -95,11 +138,19 @@ impl Element for WindowTemplate {
             f(child.as_ref());
         }
     }
+
+    fn modifiers(&self) -> Option<Modifiers> {
+        Some(self.inner.lock().unwrap().modifiers.clone())
+    }
 }
 
 #[derive(Default, Clone, PartialEq)]
 pub struct WindowModifiers {
     pub title: &'static str,
+    /// Overrides the application's theme for this window
+    pub theme: Option<Theme>,
+    /// Overrides the application's stylesheet for this window
+    pub stylesheet: Option<Stylesheet>,
 }
 
 pub fn turubai_main<T: Application + 'static>(app: T) -> ! {
@@ -0,0 +1,283 @@
+//! Outlines drawn over a window to show how it was laid out.
+//!
+//! Every node's frame is outlined in a color that changes with its depth,
+//! padding is tinted green and the space between the children of a stack
+//! pink. The node under the pointer is labelled with the display name of
+//! its element and its size. Backends paint the overlay when the
+//! environment asks for it, which they do when `TURUBAI_DEBUG_LAYOUT=1`:
+//!
+//! ```text
+//! $ TURUBAI_DEBUG_LAYOUT=1 cargo run --features gtk --example spacers
+//! ```
+
+use taffy::{Display, FlexDirection};
+
+use crate::{
+    canvas::{DrawCommand, DrawContext},
+    color::Color,
+    font::{Font, FontWeight, GenericFamily},
+    postprocessing::AffineTransform,
+    shape::{Path, Stroke},
+};
+
+use super::{ShadowNode, ShadowTree};
+
+/// Set to `1` to draw the layout overlay in native windows.
+pub const DEBUG_LAYOUT_ENV: &str = "TURUBAI_DEBUG_LAYOUT";
+
+/// Whether [`DEBUG_LAYOUT_ENV`] asks for the layout overlay.
+pub fn debug_layout_from_env() -> bool {
+    std::env::var(DEBUG_LAYOUT_ENV).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Frame outlines, one per depth and then again from the start
+const FRAME_COLORS: [(u8, u8, u8); 5] = [
+    (230, 57, 70),
+    (244, 162, 97),
+    (42, 157, 143),
+    (69, 123, 157),
+    (131, 56, 236),
+];
+
+impl ShadowTree {
+    /// The layout overlay for `root` and its descendants, in the
+    /// coordinates of `root`'s parent, i.e. the window's. `pointer` is
+    /// where the pointer is, if it is over the window.
+    pub fn debug_overlay(
+        &self,
+        root: &ShadowNode,
+        pointer: Option<(f32, f32)>,
+    ) -> Vec<DrawCommand> {
+        let mut context = DrawContext::new(0.0, 0.0, self.environment().clone());
+        let hovered = pointer.and_then(|(x, y)| self.hit_test(root, x, y));
+        let mut label = None;
+        self.debug_node(
+            &mut context,
+            root,
+            AffineTransform::default(),
+            0,
+            hovered,
+            &mut label,
+        );
+        if let Some((node, transform)) = label {
+            self.debug_label(&mut context, node, &transform);
+        }
+        context.into_commands()
+    }
+
+    /// Draw the overlay of `node`, whose parent's coordinates map to the
+    /// window's with `parent`. Remembers where the hovered node is in
+    /// `label`, for its label to be drawn on top of everything.
+    fn debug_node<'a>(
+        &self,
+        context: &mut DrawContext,
+        node: &'a ShadowNode,
+        parent: AffineTransform,
+        depth: usize,
+        hovered: Option<&ShadowNode>,
+        label: &mut Option<(&'a ShadowNode, AffineTransform)>,
+    ) {
+        let (Some(layout), Some(local)) =
+            (self.get_layout(node.taffy_id), self.get_transform(node))
+        else {
+            return;
+        };
+        let transform = local.then(&parent);
+        let (width, height) = (layout.size.width as f64, layout.size.height as f64);
+
+        context.save();
+        context.transform(&transform);
+
+        // Padding, between the border and the content box
+        let padding_color = Color::new_with_alpha(80, 200, 120, 0.3);
+        let (left, top) = (layout.border.left as f64, layout.border.top as f64);
+        let (right, bottom) = (
+            width - layout.border.right as f64,
+            height - layout.border.bottom as f64,
+        );
+        let insets = layout.padding;
+        let (inner_top, inner_bottom) = (top + insets.top as f64, bottom - insets.bottom as f64);
+        let (inner_left, inner_right) = (left + insets.left as f64, right - insets.right as f64);
+        for (x, y, w, h) in [
+            (left, top, right - left, inner_top - top),
+            (left, inner_bottom, right - left, bottom - inner_bottom),
+            (left, inner_top, inner_left - left, inner_bottom - inner_top),
+            (
+                inner_right,
+                inner_top,
+                right - inner_right,
+                inner_bottom - inner_top,
+            ),
+        ] {
+            if w > 0.0 && h > 0.0 {
+                context.fill(&Path::new().rect(x, y, w, h), padding_color);
+            }
+        }
+
+        // Gaps between the children of a stack, along its main axis
+        if node.style.display == Display::Flex {
+            let horizontal = matches!(
+                node.style.flex_direction,
+                FlexDirection::Row | FlexDirection::RowReverse
+            );
+            let mut spans: Vec<(f64, f64)> = node
+                .children
+                .iter()
+                .filter_map(|child| self.get_layout(child.taffy_id))
+                .map(|child| match horizontal {
+                    true => (
+                        child.location.x as f64,
+                        (child.location.x + child.size.width) as f64,
+                    ),
+                    false => (
+                        child.location.y as f64,
+                        (child.location.y + child.size.height) as f64,
+                    ),
+                })
+                .collect();
+            spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let gap_color = Color::new_with_alpha(236, 72, 153, 0.3);
+            let (cross_start, cross_end) = match horizontal {
+                true => (inner_top, inner_bottom),
+                false => (inner_left, inner_right),
+            };
+            for pair in spans.windows(2) {
+                let (start, end) = (pair[0].1, pair[1].0);
+                if end - start <= 0.0 || cross_end <= cross_start {
+                    continue;
+                }
+                let gap = match horizontal {
+                    true => {
+                        Path::new().rect(start, cross_start, end - start, cross_end - cross_start)
+                    }
+                    false => {
+                        Path::new().rect(cross_start, start, cross_end - cross_start, end - start)
+                    }
+                };
+                context.fill(&gap, gap_color);
+            }
+        }
+
+        // The frame, inside the node's bounds so neighbours don't overlap
+        if width > 0.0 && height > 0.0 {
+            let (r, g, b) = FRAME_COLORS[depth % FRAME_COLORS.len()];
+            let outline =
+                Path::new().rect(0.5, 0.5, (width - 1.0).max(0.0), (height - 1.0).max(0.0));
+            context.stroke(
+                &outline,
+                &Stroke::new(Color::new_with_alpha(r, g, b, 0.9), 1.0),
+            );
+        }
+        context.restore();
+
+        if hovered.is_some_and(|hovered| std::ptr::eq(hovered, node)) {
+            *label = Some((node, transform));
+        }
+        for child in &node.children {
+            self.debug_node(context, child, transform, depth + 1, hovered, label);
+        }
+    }
+
+    /// Label `node` with its element and size, above it if there is room
+    /// and inside it otherwise.
+    fn debug_label(
+        &self,
+        context: &mut DrawContext,
+        node: &ShadowNode,
+        transform: &AffineTransform,
+    ) {
+        let Some(layout) = self.get_layout(node.taffy_id) else {
+            return;
+        };
+        let content = format!(
+            "{} {}x{}",
+            node.element, layout.size.width, layout.size.height
+        );
+        let font = Font::new_with_fallbacks(
+            &[GenericFamily::Monospace.css_name()],
+            9,
+            FontWeight::Regular,
+            false,
+        );
+        let (text_width, text_height) = context.measure_text(&content, &font);
+        let (width, height) = (text_width + 6.0, text_height + 2.0);
+
+        let (_, top) = transform.apply(0.0, 0.0);
+        let y = match top as f64 >= height {
+            true => -height,
+            false => 0.0,
+        };
+        context.saved(|context| {
+            context.transform(transform);
+            context.fill(
+                &Path::new().rect(0.0, y, width, height),
+                Color::new_with_alpha(20, 20, 20, 0.85),
+            );
+            context.fill_text(&content, &font, Color::new(255, 255, 255), 3.0, y + 1.0);
+        });
+    }
+}
+
+#[test]
+fn test_debug_overlay() {
+    use crate::{
+        color::ColorScheme,
+        composition::VStack,
+        elements::{Element, Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::Padding,
+        Unit,
+    };
+
+    let modifiers = Modifiers::default();
+    modifiers.lock().unwrap().v_stack.spacing = Unit::Pixels(8.0);
+    let children: Vec<Box<dyn Element>> = vec![
+        Box::new(Text::new("Hello", modifiers.fork())),
+        Box::new(Text::new("World", modifiers.fork())),
+    ];
+    let stack = Box::new(VStack::new(modifiers, children));
+    let inset = Unit::Pixels(10.0);
+    let padding = Box::new(Padding::new(inset, inset, inset, inset, stack));
+    let window = Context::new(ColorScheme::Light).render(padding, 200.0, 100.0);
+    let text = &window.root.children[0].children[1];
+    assert_eq!(text.element, "Text");
+
+    let overlay = window.tree.debug_overlay(&window.root, None);
+    let strokes = overlay
+        .iter()
+        .filter(|command| matches!(command, DrawCommand::Stroke { .. }))
+        .count();
+    assert_eq!(strokes, 4);
+    let fills: Vec<_> = overlay
+        .iter()
+        .filter_map(|command| match command {
+            DrawCommand::Fill { path, .. } => path.bounds(),
+            _ => None,
+        })
+        .collect();
+    // The padding on each side, then the spacing between the texts
+    let width = window.layout(&window.root).unwrap().size.width as f64;
+    let hello = window.layout(&window.root.children[0].children[0]).unwrap();
+    let hello_bottom = (hello.location.y + hello.size.height) as f64;
+    assert_eq!(fills.len(), 5);
+    assert_eq!(fills[0], (0.0, 0.0, width, 10.0));
+    assert_eq!(fills[4].1, hello_bottom);
+    assert_eq!(fills[4].3, 8.0);
+    assert!(!overlay
+        .iter()
+        .any(|command| matches!(command, DrawCommand::Text { .. })));
+
+    // Pointing at the second text labels it
+    let layout = window.layout(text).unwrap();
+    let overlay = window
+        .tree
+        .debug_overlay(&window.root, Some((15.0, 10.0 + layout.location.y + 1.0)));
+    let label = overlay.iter().find_map(|command| match command {
+        DrawCommand::Text { content, .. } => Some(content.clone()),
+        _ => None,
+    });
+    assert_eq!(
+        label.unwrap(),
+        format!("Text {}x{}", layout.size.width, layout.size.height)
+    );
+}
@@ -0,0 +1,80 @@
+use std::fmt::Write;
+
+use crate::shape::Shape;
+
+use super::{NodeKind, ShadowNode, ShadowTree};
+
+impl ShadowTree {
+    /// Describe `node` and its descendants as laid out, one line per node
+    /// indented by depth:
+    ///
+    /// ```text
+    /// VStack [0,0 800x600]
+    ///   Text "Hello" [312,0 176x19]
+    /// ```
+    ///
+    /// Positions are relative to the parent, like [`ShadowTree::get_layout`],
+    /// so the dump of a subtree doesn't change when it moves. Meant for
+    /// layout tests, whose expectations stay readable in diffs.
+    pub fn dump(&self, node: &ShadowNode) -> String {
+        let mut out = String::new();
+        self.dump_recursive(node, 0, &mut out);
+        out
+    }
+
+    fn dump_recursive(&self, node: &ShadowNode, depth: usize, out: &mut String) {
+        let _ = write!(
+            out,
+            "{:indent$}{}",
+            "",
+            node.kind.label(),
+            indent = depth * 2
+        );
+        match self.get_layout(node.taffy_id) {
+            Some(layout) => {
+                let _ = writeln!(
+                    out,
+                    " [{},{} {}x{}]",
+                    layout.location.x, layout.location.y, layout.size.width, layout.size.height
+                );
+            }
+            None => out.push_str(" [not laid out]\n"),
+        }
+        for child in &node.children {
+            self.dump_recursive(child, depth + 1, out);
+        }
+    }
+}
+
+impl NodeKind {
+    /// The element a node came from, with the content that tells it apart
+    /// from its siblings, e.g. `Text "Hello"`.
+    pub fn label(&self) -> String {
+        match self {
+            NodeKind::Window { title } => format!("Window {title:?}"),
+            NodeKind::Text { content, .. } => format!("Text {content:?}"),
+            NodeKind::HStack { .. } => "HStack".into(),
+            NodeKind::VStack { .. } => "VStack".into(),
+            NodeKind::Spacer => "Spacer".into(),
+            NodeKind::Image { alt, .. } if alt.is_empty() => "Image".into(),
+            NodeKind::Image { alt, .. } => format!("Image {alt:?}"),
+            NodeKind::Shape { shape, .. } => match shape {
+                Shape::Rectangle => "Rectangle",
+                Shape::RoundedRectangle { .. } => "RoundedRectangle",
+                Shape::Circle => "Circle",
+                Shape::Capsule => "Capsule",
+                Shape::Path(_) => "Path",
+            }
+            .into(),
+            NodeKind::Canvas { .. } => "Canvas".into(),
+            NodeKind::BackgroundColor { .. } => "BackgroundColor".into(),
+            NodeKind::Background { .. } => "Background".into(),
+            NodeKind::Border { .. } => "Border".into(),
+            NodeKind::CornerRadius { .. } => "CornerRadius".into(),
+            NodeKind::Clip => "Clip".into(),
+            NodeKind::Padding { .. } => "Padding".into(),
+            NodeKind::Frame { .. } => "Frame".into(),
+            NodeKind::View => "View".into(),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+
+/// Which node a shadow node is across rebuilds of the shadow tree, unlike
+/// its taffy `NodeId`, which is new every time.
+///
+/// It is derived from the identity of the node's parent, the element it was
+/// built from and either its key, if it has one, or else its index among
+/// its siblings. A node keeps its identity when siblings before it come and
+/// go only if it has a key:
+///
+/// ```ignore
+/// VStack {
+///     Text("{}", item.name).key(item.id),
+///     Text("Save").id("save-button"),
+/// }
+/// ```
+///
+/// An id given for stylesheets serves as the key of an element without
+/// one. Keys should be unique among siblings. Identities are the same on
+/// every run and platform, so they can be written down in tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeIdentity(u64);
+
+impl NodeIdentity {
+    /// The identity of a node built from the element called `element`, the
+    /// `index`th child of the node identified by `parent`, or the root if
+    /// `parent` is `None`.
+    pub fn new(parent: Option<NodeIdentity>, element: &str, key: Option<&str>, index: usize) -> Self {
+        let mut hash = Fnv::new();
+        if let Some(parent) = parent {
+            hash.write(&parent.0.to_le_bytes());
+        }
+        hash.write(element.as_bytes());
+        match key {
+            Some(key) => {
+                hash.write(&[1]);
+                hash.write(key.as_bytes());
+            }
+            None => {
+                hash.write(&[0]);
+                hash.write(&(index as u64).to_le_bytes());
+            }
+        }
+        Self(hash.finish())
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for NodeIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// 64 bit FNV-1a, which unlike std's hasher is specified to stay the same.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[test]
+fn test_identity() {
+    use std::time::Duration;
+
+    use crate::{
+        animation::{with_animation, Animation},
+        color::ColorScheme,
+        composition::VStack,
+        elements::{Element, Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::key,
+        runtime::WindowTemplate,
+        shadow::ShadowTree,
+        state::State,
+    };
+
+    let window = |id: &str, child: Box<dyn Element>| {
+        WindowTemplate::turubai_new_with_1_args(id, Modifiers::default(), move |_| vec![child])
+    };
+    let text = || -> Box<dyn Element> { Box::new(Text::new("Hello", Modifiers::default())) };
+    let main = window("main", text());
+    let node = ShadowTree::new().create_node_from_element(&main);
+    assert_eq!(node.identity, main.identifier());
+    assert_eq!(main.identifier(), window("main", text()).identifier());
+    assert_ne!(main.identifier(), window("settings", text()).identifier());
+    // Without a key, a node is identified by its position
+    assert_eq!(
+        node.children[0].identity,
+        NodeIdentity::new(Some(node.identity), "Text", None, 0)
+    );
+
+    let items = State::new(vec!["a", "b", "c"]);
+    let markup = {
+        let items = items.clone();
+        move || -> Box<dyn Element> {
+            let modifiers = Modifiers::default();
+            let children = items
+                .get()
+                .into_iter()
+                .map(|item| key(item, Box::new(Text::new(item, modifiers.fork())), modifiers.fork()))
+                .collect();
+            Box::new(window("main", Box::new(VStack::new(modifiers, children))))
+        }
+    };
+    let mut window = Context::new(ColorScheme::Light).render_markup(markup, 400.0, 300.0);
+    let position = |window: &crate::pal::headless::Window, key: &str| {
+        let node = window
+            .root
+            .children
+            .iter()
+            .find(|node| node.key.as_deref() == Some(key))
+            .unwrap();
+        (node.identity, window.layout(node).unwrap().location.y)
+    };
+    let (c, c_y) = position(&window, "c");
+    let (a, a_y) = position(&window, "a");
+
+    // Keyed nodes keep their identity when they move, and move smoothly
+    with_animation(Animation::linear(0.4), || items.set(vec!["c", "a", "b"]));
+    window.update();
+    assert_eq!(window.root.children[0].identity, c);
+    assert_eq!(window.root.children[1].identity, a);
+    assert!(window.animator().removals().is_empty());
+    assert_eq!(position(&window, "c").1, c_y);
+
+    window.advance(Duration::from_millis(400));
+    assert_eq!(position(&window, "c").1, a_y);
+    assert!(!window.animator().is_animating());
+}
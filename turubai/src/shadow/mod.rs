@@ -1,14 +1,33 @@
 mod conv;
+mod debug;
+mod dump;
+mod identity;
 mod node;
+mod query;
+#[cfg(feature = "serde")]
+mod save;
 
+pub use debug::{debug_layout_from_env, DEBUG_LAYOUT_ENV};
+pub use identity::NodeIdentity;
 pub use node::*;
+pub use query::{NodeHandle, Query};
+#[cfg(feature = "serde")]
+pub use save::*;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
-use taffy::{AvailableSpace, Dimension, Layout, LengthPercentage, NodeId, Size, Style, TaffyTree};
+use taffy::{Dimension, Layout, NodeId, Size, TaffyTree};
+
+use crate::animation::TransitionModifiers;
+use crate::environment::Environment;
+use crate::postprocessing::{AffineTransform, EffectModifiers, TransformModifiers};
+use crate::stylesheet::StyleSubject;
 
 /// The shadow tree holds the platform-agnostic representation of the UI.
 /// It owns the layout tree (Taffy) and maps layout nodes to shadow nodes.
+///
+/// All layout happens in logical pixels. Computed layouts are snapped to the
+/// device pixel grid described by the tree's [`Environment`].
 pub struct ShadowTree {
     /// Taffy layout tree for flexbox computations
     taffy: RefCell<TaffyTree<()>>,
@@ -16,17 +35,41 @@ pub struct ShadowTree {
     root: Option<ShadowNode>,
     /// Maps Taffy NodeIds to their computed layouts (after layout pass)
     layouts: HashMap<NodeId, Layout>,
+    /// Layouts shown instead of the computed ones while they animate
+    presented_layouts: HashMap<NodeId, Layout>,
+    /// Backend supplied values handed to elements while building
+    environment: Environment,
 }
 
 impl ShadowTree {
     pub fn new() -> Self {
+        Self::with_environment(Environment::default())
+    }
+
+    pub fn with_environment(environment: Environment) -> Self {
+        // Taffy rounds to whole logical pixels; we snap to device pixels ourselves.
+        let mut taffy = TaffyTree::new();
+        taffy.disable_rounding();
+
         Self {
-            taffy: RefCell::new(TaffyTree::new()),
+            taffy: RefCell::new(taffy),
             root: None,
             layouts: HashMap::new(),
+            presented_layouts: HashMap::new(),
+            environment,
         }
     }
 
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Replace the environment. Takes effect for nodes created and layouts
+    /// computed after this call.
+    pub fn set_environment(&mut self, environment: Environment) {
+        self.environment = environment;
+    }
+
     /// Build the shadow tree from an element tree
     pub fn build_from_element(&mut self, element: &dyn crate::elements::Element) -> &ShadowNode {
         let node = self.create_node_from_element(element);
@@ -36,14 +79,81 @@ impl ShadowTree {
 
     /// Create a shadow node from an element
     pub fn create_node_from_element(&self, element: &dyn crate::elements::Element) -> ShadowNode {
-        // Get the shadow descriptor from the element
-        let descriptor = element.shadow_descriptor();
+        self.create_node(element, &self.environment, &mut Vec::new(), None, 0)
+    }
+
+    /// `ancestors` holds the style subjects of the element's ancestors, root
+    /// first, and `parent` the identity of its parent, of which it is the
+    /// `index`th child.
+    fn create_node(
+        &self,
+        element: &dyn crate::elements::Element,
+        env: &Environment,
+        ancestors: &mut Vec<StyleSubject>,
+        parent: Option<NodeIdentity>,
+        index: usize,
+    ) -> ShadowNode {
+        let scoped = element.environment(env);
+        let env = scoped.as_ref().unwrap_or(env);
+
+        let modifiers = element.modifiers();
+        let mut key = None;
+        let subject = match &modifiers {
+            Some(modifiers) => {
+                let selector = modifiers.lock().unwrap().selector.clone();
+                key = selector.key.or_else(|| selector.id.clone());
+                StyleSubject {
+                    name: element.name().into(),
+                    id: selector.id,
+                    classes: selector.classes,
+                }
+            }
+            None => StyleSubject {
+                name: element.name().into(),
+                ..Default::default()
+            },
+        };
+
+        // Get the shadow descriptor and paint-only values from the element,
+        // with matching stylesheet rules applied to its modifiers for the duration
+        let (descriptor, mut effects, transform, transition) = match &modifiers {
+            Some(modifiers) if !env.stylesheet.is_empty() => {
+                let original = modifiers.lock().unwrap().clone();
+                env.stylesheet
+                    .apply(&mut modifiers.lock().unwrap(), &subject, ancestors);
+                let descriptor = element.shadow_descriptor(env);
+                let inner = std::mem::replace(&mut *modifiers.lock().unwrap(), original);
+                (descriptor, inner.effects, inner.transform, inner.transition)
+            }
+            Some(modifiers) => {
+                let (effects, transform, transition) = {
+                    let inner = modifiers.lock().unwrap();
+                    (inner.effects.clone(), inner.transform.clone(), inner.transition)
+                };
+                (element.shadow_descriptor(env), effects, transform, transition)
+            }
+            None => (
+                element.shadow_descriptor(env),
+                EffectModifiers::default(),
+                TransformModifiers::default(),
+                TransitionModifiers::default(),
+            ),
+        };
+        // Shadows follow the theme like every other color
+        for shadow in &mut effects.shadows {
+            shadow.color = env.themed_color(&shadow.color);
+        }
+
+        let identity = NodeIdentity::new(parent, element.display_name(), key.as_deref(), index);
 
         // Create child shadow nodes recursively
         let mut children: Vec<ShadowNode> = Vec::with_capacity(element.child_count());
+        ancestors.push(subject);
         element.for_each_child(&mut |child| {
-            children.push(self.create_node_from_element(child));
+            let index = children.len();
+            children.push(self.create_node(child, env, ancestors, Some(identity), index));
         });
+        let subject = ancestors.pop().unwrap();
 
         // Create Taffy node for layout
         let child_taffy_ids: Vec<NodeId> = children.iter().map(|c| c.taffy_id).collect();
@@ -56,12 +166,19 @@ impl ShadowTree {
         ShadowNode {
             taffy_id,
             kind: descriptor.kind,
+            element: element.display_name().into(),
+            identity,
+            key,
+            subject,
             style: descriptor.style,
+            effects,
+            transform,
+            transition,
             children,
         }
     }
 
-    /// Compute layout for the entire tree
+    /// Compute layout for the entire tree. The available space is given in logical pixels.
     pub fn compute_layout(
         &mut self,
         root: &ShadowNode,
@@ -79,15 +196,25 @@ impl ShadowTree {
             .expect("Failed to compute layout");
 
         // Cache computed layouts
-        self.cache_layouts_recursive(&root);
+        self.cache_layouts_recursive(&root, 0.0, 0.0);
     }
 
-    fn cache_layouts_recursive(&mut self, node: &ShadowNode) {
-        if let Ok(layout) = self.taffy.borrow().layout(node.taffy_id) {
-            self.layouts.insert(node.taffy_id, *layout);
-        }
+    /// Store the snapped layout of `node`, whose parent's unsnapped origin
+    /// in window coordinates is (`parent_x`, `parent_y`).
+    fn cache_layouts_recursive(&mut self, node: &ShadowNode, parent_x: f32, parent_y: f32) {
+        let Ok(layout) = self.taffy.borrow().layout(node.taffy_id).copied() else {
+            return;
+        };
+
+        let x = parent_x + layout.location.x;
+        let y = parent_y + layout.location.y;
+        self.layouts.insert(
+            node.taffy_id,
+            snap_layout(&layout, &self.environment, parent_x, parent_y),
+        );
+
         for child in &node.children {
-            self.cache_layouts_recursive(child);
+            self.cache_layouts_recursive(child, x, y);
         }
     }
 
@@ -100,17 +227,97 @@ impl ShadowTree {
         let _ = self.taffy.borrow_mut().set_style(id, style);
     }
 
-    /// Get the computed layout for a node
+    /// Get the layout of a node as it is on screen, in logical pixels
+    /// relative to its parent. While a change animates this lags behind
+    /// the computed layout.
     pub fn get_layout(&self, taffy_id: NodeId) -> Option<&Layout> {
+        self.presented_layouts
+            .get(&taffy_id)
+            .or_else(|| self.layouts.get(&taffy_id))
+    }
+
+    /// Get the computed layout for a node, where it ends up once animations
+    /// are over.
+    pub fn get_computed_layout(&self, taffy_id: NodeId) -> Option<&Layout> {
         self.layouts.get(&taffy_id)
     }
 
+    /// Show `layout` instead of the computed layout, or stop doing so.
+    pub fn set_presented_layout(&mut self, taffy_id: NodeId, layout: Option<Layout>) {
+        match layout {
+            Some(layout) => self.presented_layouts.insert(taffy_id, layout),
+            None => self.presented_layouts.remove(&taffy_id),
+        };
+    }
+
+    /// The transform mapping `node`'s own coordinates to its parent's: its
+    /// user transform followed by its layout position.
+    pub fn get_transform(&self, node: &ShadowNode) -> Option<AffineTransform> {
+        let layout = self.get_layout(node.taffy_id)?;
+        let translation = AffineTransform::translation(layout.location.x, layout.location.y);
+        Some(
+            node.transform
+                .resolve(layout.size.width, layout.size.height)
+                .then(&translation),
+        )
+    }
+
+    /// The topmost node at `(x, y)` among `node` and its descendants, in
+    /// the coordinates of `node`'s parent. Transforms are honored, and
+    /// children are hit even where they stick out of their parent.
+    pub fn hit_test<'a>(&self, node: &'a ShadowNode, x: f32, y: f32) -> Option<&'a ShadowNode> {
+        let layout = self.get_layout(node.taffy_id)?;
+        // A node scaled down to nothing can't be hit
+        let (x, y) = self.get_transform(node)?.invert()?.apply(x, y);
+
+        // Later children are painted on top
+        let hit = node
+            .children
+            .iter()
+            .rev()
+            .find_map(|child| self.hit_test(child, x, y));
+        if hit.is_some() {
+            return hit;
+        }
+
+        let inside = x >= 0.0 && y >= 0.0 && x < layout.size.width && y < layout.size.height;
+        inside.then_some(node)
+    }
+
     /// Get the root node
     pub fn root(&self) -> Option<&ShadowNode> {
         self.root.as_ref()
     }
 }
 
+/// Snap a layout to the device pixel grid. Edges are rounded in window
+/// coordinates (rather than rounding sizes) so that adjacent nodes never
+/// overlap or leave gaps.
+fn snap_layout(layout: &Layout, env: &Environment, parent_x: f32, parent_y: f32) -> Layout {
+    let x = parent_x + layout.location.x;
+    let y = parent_y + layout.location.y;
+    let snap_rect = |rect: &taffy::Rect<f32>| taffy::Rect {
+        left: env.snap(x + rect.left) - env.snap(x),
+        right: env.snap(x + layout.size.width) - env.snap(x + layout.size.width - rect.right),
+        top: env.snap(y + rect.top) - env.snap(y),
+        bottom: env.snap(y + layout.size.height) - env.snap(y + layout.size.height - rect.bottom),
+    };
+
+    Layout {
+        location: taffy::Point {
+            x: env.snap(x) - env.snap(parent_x),
+            y: env.snap(y) - env.snap(parent_y),
+        },
+        size: Size {
+            width: env.snap(x + layout.size.width) - env.snap(x),
+            height: env.snap(y + layout.size.height) - env.snap(y),
+        },
+        border: snap_rect(&layout.border),
+        padding: snap_rect(&layout.padding),
+        ..*layout
+    }
+}
+
 impl Default for ShadowTree {
     fn default() -> Self {
         Self::new()
@@ -119,41 +326,33 @@ impl Default for ShadowTree {
 
 #[test]
 fn test_centering() {
-    let mut tree = taffy::TaffyTree::<()>::new();
-
-    let child = tree.new_with_children(Style::default(), &[]).unwrap();
-    let h_stack = tree
-        .new_with_children(
-            Style {
-                justify_content: Some(taffy::JustifyContent::Center),
-                ..Default::default()
-            },
-            &[child],
-        )
-        .unwrap();
-
-    let v_stack = tree
-        .new_with_children(
-            Style {
-                justify_content: Some(taffy::JustifyContent::Center),
-                ..Default::default()
-            },
-            &[h_stack],
-        )
-        .unwrap();
+    use crate::color::ColorScheme;
+    use crate::composition::{HStack, Spacer, VStack};
+    use crate::elements::{Element, Modifiers, Text};
+    use crate::pal::headless::Context;
 
-    tree.compute_layout(
-        v_stack,
-        Size {
-            width: AvailableSpace::Definite(800.0),
-            height: AvailableSpace::Definite(600.0),
-        },
-    )
-    .unwrap();
+    let modifiers = Modifiers::default();
+    let spacer = || -> Box<dyn Element> {
+        Box::new(Spacer::turubai_new_with_0_args(Modifiers::default(), |_| vec![]))
+    };
+    let row = HStack::new(
+        modifiers.fork(),
+        vec![spacer(), Box::new(Text::new("Hello", modifiers.fork())), spacer()],
+    );
+    let column = VStack::new(modifiers, vec![Box::new(row)]);
 
-    let v = tree.layout(v_stack).unwrap();
-    assert_eq!(v.size.width, 800.0);
-    assert_eq!(v.size.height, 600.0);
+    // Measuring text is up to the backend
+    let window = Context::new(ColorScheme::Light).render(Box::new(column), 800.0, 600.0);
+    crate::assert_layout!(
+        window,
+        r#"
+        VStack [0,0 800x600]
+          HStack [0,0 800x600]
+            Spacer [0,0 380x0]
+            Text "Hello" [380,0 40x21]
+            Spacer [420,0 380x0]
+        "#,
+    );
 }
 
 #[test]
@@ -171,7 +370,7 @@ fn test_background_layout() {
         fn display_name(&self) -> &'static str {
             "Mock"
         }
-        fn shadow_descriptor(&self) -> ShadowDescriptor {
+        fn shadow_descriptor(&self, _env: &crate::environment::Environment) -> ShadowDescriptor {
             ShadowDescriptor::view()
         }
     }
@@ -211,3 +410,71 @@ fn test_background_layout() {
         "BackgroundColor should stretch children horizontally"
     );
 }
+
+#[test]
+fn test_pixel_snapping() {
+    use crate::elements::Element;
+    use crate::environment::Environment;
+    use crate::postprocessing::Padding;
+    use crate::Unit;
+
+    struct FixedElement(f32);
+    impl Element for FixedElement {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+        fn display_name(&self) -> &'static str {
+            "Fixed"
+        }
+        fn shadow_descriptor(&self, _env: &Environment) -> ShadowDescriptor {
+            ShadowDescriptor::view().with_style(|style| {
+                style.size = Size {
+                    width: Dimension::length(self.0),
+                    height: Dimension::length(self.0),
+                }
+            })
+        }
+    }
+
+    let inset = Unit::Pixels(0.3);
+    let padding = Padding::new(inset, inset, inset, inset, Box::new(FixedElement(10.3)));
+
+    let mut tree = ShadowTree::with_environment(Environment::default().with_scale_factor(2.0));
+    let root = tree.create_node_from_element(&padding);
+    tree.compute_layout(&root, 100.0, 100.0);
+
+    // Edges at 0.3 and 10.6 logical pixels land on 0.5 and 10.5 at 2x.
+    let child = tree.get_layout(root.children[0].taffy_id).unwrap();
+    assert_eq!(child.location.x, 0.5);
+    assert_eq!(child.location.y, 0.5);
+    assert_eq!(child.size.width, 10.0);
+    assert_eq!(child.size.height, 10.0);
+
+    let outer = tree.get_layout(root.taffy_id).unwrap();
+    assert_eq!(outer.size.width, 11.0);
+}
+
+#[test]
+fn test_gradient_background() {
+    use crate::color::Color;
+    use crate::elements::{Modifiers, Text};
+    use crate::fill::{ColorStop, Fill};
+
+    let stops = ColorStop::evenly(&[Color::SystemRed, Color::SystemOrange, Color::SystemYellow]);
+    assert_eq!(stops[1].offset, 0.5);
+
+    let text = Box::new(Text::new("Sunset", Modifiers::default()));
+    let bg = crate::postprocessing::background(
+        Fill::linear_gradient(90.0, stops.clone()),
+        text,
+        Modifiers::default(),
+    );
+
+    let tree = ShadowTree::new();
+    let root_node = tree.create_node_from_element(&bg);
+    match &root_node.kind {
+        NodeKind::Background { fill } => assert_eq!(fill.stops(), Some(stops.as_slice())),
+        other => panic!("Root node is not Background: {:?}", other),
+    }
+    assert_eq!(root_node.children.len(), 1);
+}
@@ -1,29 +1,58 @@
+use std::{borrow::Cow, sync::Arc};
+
 use taffy::{FlexDirection, NodeId, Style};
 
 use crate::{
+    animation::TransitionModifiers,
+    canvas::Drawing,
     color::Color,
     composition::{HorizontalAlignment, VerticalAlignment},
-    elements::TextDecoration,
+    elements::{ContentMode, ImageModifiers, Interpolation, TextDecoration},
+    fill::Fill,
     font::Font,
-    shadow::conv::{conv_h_alignment, conv_v_alignment},
+    image::Bitmap,
+    shape::{Shape, Stroke},
+    stylesheet::StyleSubject,
+    postprocessing::{BorderStyle, CornerRadii, EffectModifiers, TransformModifiers},
+    shadow::{
+        conv::{conv_h_alignment, conv_v_alignment},
+        NodeIdentity,
+    },
     Unit,
 };
 
 /// A node in the shadow tree - platform agnostic description of a UI element
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ShadowNode {
     /// Taffy node ID for layout computation
     pub taffy_id: NodeId,
     /// What kind of node this is
     pub kind: NodeKind,
+    /// The display name of the element the node was built from, e.g.
+    /// `Padding`. Borrowed from the element unless the node was restored
+    pub element: Cow<'static, str>,
+    /// Which node this is across rebuilds
+    pub identity: NodeIdentity,
+    /// Given with `.key()`, or else `.id()`
+    pub key: Option<String>,
+    /// The element's name, id and classes, which stylesheet rules and
+    /// queries match
+    pub subject: StyleSubject,
     /// Layout style (flexbox properties)
     pub style: Style,
+    /// Opacity, shadows and blur. Paint-only, they take no part in layout
+    pub effects: EffectModifiers,
+    /// Offset, scale and rotation on top of the laid out position
+    pub transform: TransformModifiers,
+    /// How the node enters and leaves during animated changes
+    pub transition: TransitionModifiers,
     /// Child nodes
     pub children: Vec<ShadowNode>,
 }
 
 /// The type of shadow node - describes what native view to create
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeKind {
     /// A window container
     Window {
@@ -47,9 +76,45 @@ pub enum NodeKind {
         alignment: HorizontalAlignment,
     },
     Spacer,
+    /// A picture, `None` if it couldn't be loaded
+    Image {
+        bitmap: Option<Arc<Bitmap>>,
+        alt: String,
+        resizable: bool,
+        aspect_ratio: ContentMode,
+        interpolation: Interpolation,
+    },
+    /// A shape filling the node, painted inside with `fill` and along
+    /// its outline with `stroke`
+    Shape {
+        shape: Shape,
+        fill: Option<Fill>,
+        stroke: Option<Stroke>,
+    },
+    /// Paints whatever `draw` records at the node's size
+    Canvas {
+        draw: Drawing,
+    },
     BackgroundColor {
         color: Color,
     },
+    /// Paints a color, gradient or image behind its only child
+    Background {
+        fill: Fill,
+    },
+    /// Strokes a border around its only child, which is inset by `width`
+    Border {
+        width: f64,
+        color: Color,
+        style: BorderStyle,
+        radii: CornerRadii,
+    },
+    /// Rounds and clips its only child
+    CornerRadius {
+        radii: CornerRadii,
+    },
+    /// Clips its only child to its bounds
+    Clip,
     Padding {
         top: f64,
         left: f64,
@@ -75,6 +140,7 @@ pub struct ShadowDescriptor {
 }
 
 impl ShadowDescriptor {
+    /// Sized to the measured text, like an image that isn't resizable.
     pub fn text(
         content: impl Into<String>,
         font: Font,
@@ -88,7 +154,11 @@ impl ShadowDescriptor {
                 color,
                 decoration,
             },
-            style: Style::default(),
+            // Measured, so never squeezed below the size of the text
+            style: Style {
+                flex_shrink: 0.0,
+                ..Default::default()
+            },
         }
     }
 
@@ -132,6 +202,55 @@ impl ShadowDescriptor {
         }
     }
 
+    /// Sized to the picture unless it is resizable, then it grows like a
+    /// spacer and may shrink to nothing.
+    pub fn image(bitmap: Option<Arc<Bitmap>>, modifiers: &ImageModifiers) -> Self {
+        let style = if modifiers.resizable {
+            flexible_style()
+        } else {
+            let (width, height) = bitmap
+                .as_ref()
+                .map(|bitmap| bitmap.logical_size())
+                .unwrap_or_default();
+            Style {
+                size: taffy::Size {
+                    width: taffy::Dimension::length(width as f32),
+                    height: taffy::Dimension::length(height as f32),
+                },
+                flex_shrink: 0.0,
+                ..Default::default()
+            }
+        };
+        Self {
+            kind: NodeKind::Image {
+                bitmap,
+                alt: modifiers.alt.clone(),
+                resizable: modifiers.resizable,
+                aspect_ratio: modifiers.aspect_ratio,
+                interpolation: modifiers.interpolation,
+            },
+            style,
+        }
+    }
+
+    pub fn shape(shape: Shape, fill: Option<Fill>, stroke: Option<Stroke>) -> Self {
+        Self {
+            kind: NodeKind::Shape {
+                shape,
+                fill,
+                stroke,
+            },
+            style: flexible_style(),
+        }
+    }
+
+    pub fn canvas(draw: Drawing) -> Self {
+        Self {
+            kind: NodeKind::Canvas { draw },
+            style: flexible_style(),
+        }
+    }
+
     pub fn background_color(color: Color) -> Self {
         Self {
             kind: NodeKind::BackgroundColor { color },
@@ -145,6 +264,62 @@ impl ShadowDescriptor {
         }
     }
 
+    pub fn background(fill: Fill) -> Self {
+        Self {
+            kind: NodeKind::Background { fill },
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: Some(taffy::AlignItems::Stretch),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn border(width: f64, color: Color, style: BorderStyle, radii: CornerRadii) -> Self {
+        let length = taffy::LengthPercentage::length(width as f32);
+        Self {
+            kind: NodeKind::Border {
+                width,
+                color,
+                style,
+                radii,
+            },
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: Some(taffy::AlignItems::Stretch),
+                border: taffy::Rect {
+                    top: length,
+                    left: length,
+                    bottom: length,
+                    right: length,
+                },
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn corner_radius(radii: CornerRadii) -> Self {
+        Self {
+            kind: NodeKind::CornerRadius { radii },
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: Some(taffy::AlignItems::Stretch),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn clip() -> Self {
+        Self {
+            kind: NodeKind::Clip,
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: Some(taffy::AlignItems::Stretch),
+                ..Default::default()
+            },
+        }
+    }
+
     pub fn padding(top: f64, left: f64, bottom: f64, right: f64) -> Self {
         Self {
             kind: NodeKind::Padding {
@@ -194,3 +369,15 @@ impl ShadowDescriptor {
         self
     }
 }
+
+/// Grows like a spacer along the stack and, unlike a spacer, also fills the
+/// stack's other axis. Shrinks to nothing if there is no room.
+fn flexible_style() -> Style {
+    Style {
+        flex_grow: 1.0,
+        flex_shrink: 1.0,
+        align_self: Some(taffy::AlignSelf::Stretch),
+        min_size: taffy::Size::zero(),
+        ..Default::default()
+    }
+}
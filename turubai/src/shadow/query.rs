@@ -0,0 +1,282 @@
+//! Finding nodes in a laid out tree, for tests and automation.
+//!
+//! A [`Query`] matches nodes by what they were built from and a search
+//! returns [`NodeHandle`]s, which carry where each node is on screen:
+//!
+//! ```ignore
+//! use turubai::shadow::Query::*;
+//!
+//! let save = window.find_by_text("Save").unwrap();
+//! let (x, y) = save.center().unwrap();
+//! assert_eq!(window.hit_test(x, y).unwrap().identity, save.identity());
+//! assert_eq!(window.find_all(Name("text")).len(), 3);
+//! ```
+
+use taffy::Rect;
+
+use crate::{
+    postprocessing::AffineTransform,
+    stylesheet::{Selector, StyleSubject},
+};
+
+use super::{NodeIdentity, NodeKind, ShadowNode, ShadowTree};
+
+/// What to look for in [`ShadowTree::find`] and [`ShadowTree::find_all`].
+#[derive(Clone, Copy)]
+pub enum Query<'a> {
+    /// Nodes given this id with `.id()`
+    Id(&'a str),
+    /// Nodes given this key with `.key()`, or else this id
+    Key(&'a str),
+    /// Nodes built from elements stylesheets call this, e.g. `text` or
+    /// `v_stack`
+    Name(&'a str),
+    /// Text nodes showing exactly this
+    Text(&'a str),
+    Identity(NodeIdentity),
+    /// Nodes a stylesheet rule with this selector would style, e.g.
+    /// `#sidebar text.muted`
+    Selector(&'a Selector),
+    Predicate(&'a dyn Fn(&ShadowNode) -> bool),
+}
+
+impl Query<'_> {
+    /// Whether `node` matches, where `ancestors` are the subjects of the
+    /// nodes above it, outermost first.
+    fn matches(&self, node: &ShadowNode, ancestors: &[StyleSubject]) -> bool {
+        match *self {
+            Query::Id(id) => node.subject.id.as_deref() == Some(id),
+            Query::Key(key) => node.key.as_deref() == Some(key),
+            Query::Name(name) => node.subject.name == name,
+            Query::Text(text) => {
+                matches!(&node.kind, NodeKind::Text { content, .. } if content == text)
+            }
+            Query::Identity(identity) => node.identity == identity,
+            Query::Selector(selector) => selector.matches(&node.subject, ancestors),
+            Query::Predicate(predicate) => predicate(node),
+        }
+    }
+}
+
+/// A node found by a query, with where it was found.
+#[derive(Debug, Clone)]
+pub struct NodeHandle<'a> {
+    pub node: &'a ShadowNode,
+    /// Indices among siblings leading from the root of the search to the
+    /// node, empty for the root itself
+    pub path: Vec<usize>,
+    /// The node's box in the coordinates of the root's parent, i.e. the
+    /// window's, or the bounds of it if transformed. `None` if the node
+    /// hasn't been laid out
+    pub frame: Option<Rect<f32>>,
+}
+
+impl NodeHandle<'_> {
+    pub fn kind(&self) -> &NodeKind {
+        &self.node.kind
+    }
+
+    pub fn identity(&self) -> NodeIdentity {
+        self.node.identity
+    }
+
+    /// What a text node shows, `None` for other nodes.
+    pub fn text(&self) -> Option<&str> {
+        match &self.node.kind {
+            NodeKind::Text { content, .. } => Some(content),
+            _ => None,
+        }
+    }
+
+    /// The middle of the node's frame, where a pointer would press it.
+    pub fn center(&self) -> Option<(f32, f32)> {
+        self.frame.map(|frame| {
+            (
+                (frame.left + frame.right) / 2.0,
+                (frame.top + frame.bottom) / 2.0,
+            )
+        })
+    }
+}
+
+impl ShadowTree {
+    /// The first node among `root` and its descendants that matches
+    /// `query`, parents before their children and earlier siblings first.
+    pub fn find<'a>(&self, root: &'a ShadowNode, query: Query) -> Option<NodeHandle<'a>> {
+        let mut search = Search::new(query, true);
+        search.visit(self, root, AffineTransform::default());
+        search.found.pop()
+    }
+
+    /// Every node among `root` and its descendants that matches `query`,
+    /// in the order of [`ShadowTree::find`].
+    pub fn find_all<'a>(&self, root: &'a ShadowNode, query: Query) -> Vec<NodeHandle<'a>> {
+        let mut search = Search::new(query, false);
+        search.visit(self, root, AffineTransform::default());
+        search.found
+    }
+
+    /// The first text node showing exactly `text`.
+    pub fn find_by_text<'a>(&self, root: &'a ShadowNode, text: &str) -> Option<NodeHandle<'a>> {
+        self.find(root, Query::Text(text))
+    }
+}
+
+struct Search<'a, 'q> {
+    query: Query<'q>,
+    /// Stop at the first match
+    first: bool,
+    path: Vec<usize>,
+    ancestors: Vec<StyleSubject>,
+    found: Vec<NodeHandle<'a>>,
+}
+
+impl<'a, 'q> Search<'a, 'q> {
+    fn new(query: Query<'q>, first: bool) -> Self {
+        Self {
+            query,
+            first,
+            path: vec![],
+            ancestors: vec![],
+            found: vec![],
+        }
+    }
+
+    /// Look through `node`, whose parent's coordinates map to the root's
+    /// parent's with `parent`. Returns `false` once the search is over.
+    fn visit(&mut self, tree: &ShadowTree, node: &'a ShadowNode, parent: AffineTransform) -> bool {
+        let transform = tree.get_transform(node).map(|local| local.then(&parent));
+        if self.query.matches(node, &self.ancestors) {
+            let frame = transform
+                .zip(tree.get_layout(node.taffy_id))
+                .map(|(transform, layout)| {
+                    let (width, height) = (layout.size.width, layout.size.height);
+                    let corners = [(0.0, 0.0), (width, 0.0), (0.0, height), (width, height)]
+                        .map(|(x, y)| transform.apply(x, y));
+                    let (xs, ys) = (
+                        corners.map(|corner| corner.0),
+                        corners.map(|corner| corner.1),
+                    );
+                    Rect {
+                        left: xs.into_iter().fold(f32::INFINITY, f32::min),
+                        right: xs.into_iter().fold(f32::NEG_INFINITY, f32::max),
+                        top: ys.into_iter().fold(f32::INFINITY, f32::min),
+                        bottom: ys.into_iter().fold(f32::NEG_INFINITY, f32::max),
+                    }
+                });
+            self.found.push(NodeHandle {
+                node,
+                path: self.path.clone(),
+                frame,
+            });
+            if self.first {
+                return false;
+            }
+        }
+
+        self.ancestors.push(node.subject.clone());
+        let mut more = true;
+        for (index, child) in node.children.iter().enumerate() {
+            self.path.push(index);
+            more = self.visit(tree, child, transform.unwrap_or(parent));
+            self.path.pop();
+            if !more {
+                break;
+            }
+        }
+        self.ancestors.pop();
+        more
+    }
+}
+
+#[test]
+fn test_query() {
+    use crate::{
+        color::ColorScheme,
+        composition::{HStack, VStack},
+        elements::{Element, Modifiers, Text},
+        fill::UnitPoint,
+        pal::headless::Context,
+        postprocessing::{class, id, scale, Padding},
+        Unit,
+    };
+
+    let modifiers = Modifiers::default();
+    let text =
+        |content: &str| -> Box<dyn Element> { Box::new(Text::new(content, modifiers.fork())) };
+    let buttons: Vec<Box<dyn Element>> = vec![
+        class("secondary", text("Cancel"), modifiers.fork()),
+        scale(
+            2.0,
+            2.0,
+            UnitPoint::TOP_LEADING,
+            text("Save"),
+            modifiers.fork(),
+        ),
+    ];
+    let inset = Unit::Pixels(10.0);
+    let children: Vec<Box<dyn Element>> = vec![
+        id("title", text("Title"), modifiers.fork()),
+        Box::new(Padding::new(
+            inset,
+            inset,
+            inset,
+            inset,
+            Box::new(HStack::new(modifiers.fork(), buttons)),
+        )),
+    ];
+    let stack = Box::new(VStack::new(modifiers, children));
+    let window = Context::new(ColorScheme::Light).render(stack, 400.0, 300.0);
+
+    use Query::*;
+    let texts = window.find_all(Name("text"));
+    let contents: Vec<_> = texts.iter().filter_map(|handle| handle.text()).collect();
+    assert_eq!(contents, ["Title", "Cancel", "Save"]);
+    assert_eq!(window.find(Id("title")).unwrap().path, [0]);
+    assert_eq!(window.find(Key("title")).unwrap().path, [0]);
+    assert!(window.find(Id("missing")).is_none());
+
+    let selector = crate::stylesheet::Selector::parse("h_stack > text.secondary").unwrap();
+    let cancel = window.find(Query::Selector(&selector)).unwrap();
+    assert_eq!(cancel.text(), Some("Cancel"));
+    assert_eq!(cancel.path, [1, 0, 0]);
+    let pairs = |node: &ShadowNode| node.children.len() == 2;
+    let paths: Vec<_> = window
+        .find_all(Predicate(&pairs))
+        .into_iter()
+        .map(|handle| handle.path)
+        .collect();
+    assert_eq!(paths, [vec![], vec![1, 0]]);
+
+    // Frames are in window coordinates, with transforms applied
+    let padding = &window.root.children[1];
+    let (row, stack) = (
+        window.layout(padding).unwrap(),
+        window.layout(&padding.children[0]).unwrap(),
+    );
+    let cancel_layout = window.layout(&padding.children[0].children[0]).unwrap();
+    let frame = cancel.frame.unwrap();
+    assert_eq!(
+        frame.left,
+        row.location.x + stack.location.x + cancel_layout.location.x
+    );
+    assert_eq!(
+        frame.top,
+        row.location.y + stack.location.y + cancel_layout.location.y
+    );
+    assert_eq!(frame.right - frame.left, cancel_layout.size.width);
+    assert_eq!(stack.location.x, 10.0);
+
+    let save = window.find_by_text("Save").unwrap();
+    assert_eq!(
+        window.find(Identity(save.identity())).unwrap().path,
+        save.path
+    );
+    let save_layout = window.layout(save.node).unwrap();
+    let frame = save.frame.unwrap();
+    assert_eq!(frame.right - frame.left, save_layout.size.width * 2.0);
+    let (x, y) = save.center().unwrap();
+    assert_eq!(window.hit_test(x, y).unwrap().identity, save.identity());
+    let (x, y) = (frame.left + 1.0, frame.top + 1.0);
+    assert_eq!(window.hit_test(x, y).unwrap().identity, save.identity());
+}
@@ -0,0 +1,281 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use taffy::{Layout, NodeId, Point, Rect, Size, Style};
+
+use crate::{
+    animation::TransitionModifiers,
+    environment::Environment,
+    postprocessing::{EffectModifiers, TransformModifiers},
+    stylesheet::StyleSubject,
+};
+
+use super::{NodeIdentity, NodeKind, ShadowNode, ShadowTree};
+
+/// A shadow node and its computed layout in a form serde can write and
+/// read, made by [`ShadowTree::save`]. Paint-only values are left out while
+/// they have no effect, so saved trees diff well.
+///
+/// Canvas closures can't be saved: a restored canvas draws nothing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedNode {
+    pub kind: NodeKind,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub element: String,
+    pub identity: NodeIdentity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// The element's name for stylesheets, e.g. `text`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+    pub style: Style,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub effects: EffectModifiers,
+    #[serde(default, skip_serializing_if = "TransformModifiers::is_identity")]
+    pub transform: TransformModifiers,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub transition: TransitionModifiers,
+    /// `None` if the node hasn't been laid out
+    pub layout: Option<SavedLayout>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SavedNode>,
+}
+
+/// The parts of a computed layout backends use, relative to the parent
+/// like [`ShadowTree::get_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedLayout {
+    pub location: Point<f32>,
+    pub size: Size<f32>,
+    #[serde(default = "zero", skip_serializing_if = "is_zero")]
+    pub border: Rect<f32>,
+    #[serde(default = "zero", skip_serializing_if = "is_zero")]
+    pub padding: Rect<f32>,
+}
+
+impl From<&Layout> for SavedLayout {
+    fn from(layout: &Layout) -> Self {
+        Self {
+            location: layout.location,
+            size: layout.size,
+            border: layout.border,
+            padding: layout.padding,
+        }
+    }
+}
+
+impl From<SavedLayout> for Layout {
+    fn from(saved: SavedLayout) -> Self {
+        Layout {
+            location: saved.location,
+            size: saved.size,
+            border: saved.border,
+            padding: saved.padding,
+            ..Layout::new()
+        }
+    }
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn zero() -> Rect<f32> {
+    Rect::zero()
+}
+
+fn is_zero(rect: &Rect<f32>) -> bool {
+    *rect == Rect::zero()
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Json(serde_json::Error),
+    Ron(ron::Error),
+    /// RON that couldn't be read, with where the problem is
+    RonSyntax(ron::error::SpannedError),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Json(err) => write!(f, "invalid shadow tree JSON: {err}"),
+            SaveError::Ron(err) => write!(f, "could not write shadow tree RON: {err}"),
+            SaveError::RonSyntax(err) => write!(f, "invalid shadow tree RON: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Json(err) => Some(err),
+            SaveError::Ron(err) => Some(err),
+            SaveError::RonSyntax(err) => Some(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> Self {
+        SaveError::Json(err)
+    }
+}
+
+impl From<ron::Error> for SaveError {
+    fn from(err: ron::Error) -> Self {
+        SaveError::Ron(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        SaveError::RonSyntax(err)
+    }
+}
+
+impl ShadowTree {
+    /// Copy `node` and its descendants along with their computed layouts.
+    pub fn save(&self, node: &ShadowNode) -> SavedNode {
+        SavedNode {
+            kind: node.kind.clone(),
+            element: node.element.to_string(),
+            identity: node.identity,
+            key: node.key.clone(),
+            name: node.subject.name.to_string(),
+            id: node.subject.id.clone(),
+            classes: node.subject.classes.clone(),
+            style: node.style.clone(),
+            effects: node.effects.clone(),
+            transform: node.transform.clone(),
+            transition: node.transition,
+            layout: self
+                .get_computed_layout(node.taffy_id)
+                .map(SavedLayout::from),
+            children: node.children.iter().map(|child| self.save(child)).collect(),
+        }
+    }
+
+    /// Build a tree from a saved one, laid out the way it was saved. It
+    /// can be laid out again with [`ShadowTree::compute_layout`], but text
+    /// and images keep the sizes they were measured at.
+    pub fn restore(saved: &SavedNode, environment: Environment) -> (ShadowTree, ShadowNode) {
+        let mut tree = ShadowTree::with_environment(environment);
+        let root = tree.restore_node(saved);
+        (tree, root)
+    }
+
+    fn restore_node(&mut self, saved: &SavedNode) -> ShadowNode {
+        let children: Vec<ShadowNode> = saved
+            .children
+            .iter()
+            .map(|child| self.restore_node(child))
+            .collect();
+        let child_taffy_ids: Vec<NodeId> = children.iter().map(|c| c.taffy_id).collect();
+        let taffy_id = self
+            .taffy
+            .borrow_mut()
+            .new_with_children(saved.style.clone(), &child_taffy_ids)
+            .expect("Failed to create taffy node");
+        if let Some(layout) = saved.layout {
+            self.layouts.insert(taffy_id, layout.into());
+        }
+
+        ShadowNode {
+            taffy_id,
+            kind: saved.kind.clone(),
+            element: saved.element.clone().into(),
+            identity: saved.identity,
+            key: saved.key.clone(),
+            subject: StyleSubject {
+                name: saved.name.clone().into(),
+                id: saved.id.clone(),
+                classes: saved.classes.clone(),
+            },
+            style: saved.style.clone(),
+            effects: saved.effects.clone(),
+            transform: saved.transform.clone(),
+            transition: saved.transition,
+            children,
+        }
+    }
+
+    /// [`ShadowTree::save`] as pretty printed JSON.
+    pub fn to_json(&self, node: &ShadowNode) -> Result<String, SaveError> {
+        Ok(serde_json::to_string_pretty(&self.save(node))?)
+    }
+
+    /// [`ShadowTree::restore`] from JSON made by [`ShadowTree::to_json`].
+    pub fn from_json(
+        json: &str,
+        environment: Environment,
+    ) -> Result<(ShadowTree, ShadowNode), SaveError> {
+        let saved: SavedNode = serde_json::from_str(json)?;
+        Ok(Self::restore(&saved, environment))
+    }
+
+    /// [`ShadowTree::save`] as pretty printed RON.
+    pub fn to_ron(&self, node: &ShadowNode) -> Result<String, SaveError> {
+        // Taffy reads its lengths back as bare numbers
+        let config = ron::ser::PrettyConfig::default()
+            .extensions(ron::extensions::Extensions::UNWRAP_NEWTYPES);
+        Ok(ron::ser::to_string_pretty(&self.save(node), config)?)
+    }
+
+    /// [`ShadowTree::restore`] from RON made by [`ShadowTree::to_ron`].
+    pub fn from_ron(
+        ron: &str,
+        environment: Environment,
+    ) -> Result<(ShadowTree, ShadowNode), SaveError> {
+        let saved: SavedNode = ron::from_str(ron)?;
+        Ok(Self::restore(&saved, environment))
+    }
+}
+
+#[test]
+fn test_save() {
+    use crate::{
+        color::{Color, ColorScheme},
+        composition::HStack,
+        elements::{Canvas, Element, Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::{opacity, Border, BorderStyle},
+    };
+
+    let modifiers = Modifiers::default();
+    let text = opacity(
+        0.5,
+        Box::new(Text::new("Saved", modifiers.fork())),
+        modifiers.fork(),
+    );
+    let canvas: Box<dyn Element> = Box::new(Canvas::new(|_| {}, modifiers.fork()));
+    let row = Box::new(HStack::new(modifiers, vec![text, canvas]));
+    let border = Box::new(Border::new(2.0, Color::SystemRed, BorderStyle::Solid, row));
+    let window = Context::new(ColorScheme::Light).render(border, 200.0, 100.0);
+
+    let saved = window.tree.save(&window.root);
+    assert_eq!(saved.children[0].layout.unwrap().size.width, 196.0);
+    assert_eq!(saved.layout.unwrap().border, Rect::length(2.0));
+
+    let json = window.tree.to_json(&window.root).unwrap();
+    let (tree, root) = ShadowTree::from_json(&json, Environment::default()).unwrap();
+    assert_eq!(tree.to_json(&root).unwrap(), json);
+    assert_eq!(tree.dump(&root), window.dump_layout());
+    assert!(matches!(
+        &root.children[0].children[1].kind,
+        NodeKind::Canvas { .. }
+    ));
+
+    let ron = window.tree.to_ron(&window.root).unwrap();
+    let (tree, root) = ShadowTree::from_ron(&ron, Environment::default()).unwrap();
+    assert_eq!(tree.to_ron(&root).unwrap(), ron);
+
+    assert!(matches!(
+        ShadowTree::from_ron("Text", Environment::default()),
+        Err(SaveError::RonSyntax(_))
+    ));
+}
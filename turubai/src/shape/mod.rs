@@ -0,0 +1,167 @@
+//! Geometry of the shape elements and how their outlines are painted.
+
+mod path;
+
+pub use path::*;
+
+use crate::fill::Fill;
+
+/// The outline of a shape element, resolved against its frame when it is
+/// drawn.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shape {
+    Rectangle,
+    RoundedRectangle { radius: f64 },
+    /// The largest circle that fits, centered
+    Circle,
+    /// A rectangle whose shorter sides are half circles
+    Capsule,
+    /// A path in the element's own coordinates, which isn't scaled to
+    /// the frame
+    Path(Path),
+}
+
+impl Shape {
+    /// The outline for a frame of `width` x `height`.
+    pub fn path(&self, width: f64, height: f64) -> Path {
+        match self {
+            Shape::Rectangle => Path::new().rect(0.0, 0.0, width, height),
+            Shape::RoundedRectangle { radius } => {
+                Path::new().rounded_rect(0.0, 0.0, width, height, *radius)
+            }
+            Shape::Circle => {
+                let size = width.min(height);
+                Path::new().ellipse((width - size) / 2.0, (height - size) / 2.0, size, size)
+            }
+            Shape::Capsule => {
+                Path::new().rounded_rect(0.0, 0.0, width, height, width.min(height) / 2.0)
+            }
+            Shape::Path(path) => path.clone(),
+        }
+    }
+}
+
+/// How the outline of a shape is drawn.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stroke {
+    pub paint: Fill,
+    /// In logical pixels, centered on the outline
+    pub width: f64,
+    /// Alternating lengths of dashes and gaps, solid if empty
+    pub dash: Vec<f64>,
+}
+
+impl Stroke {
+    pub fn new(paint: impl Into<Fill>, width: f64) -> Self {
+        Self {
+            paint: paint.into(),
+            width: width.max(0.0),
+            dash: vec![],
+        }
+    }
+
+    pub fn dashed(mut self, dash: impl Into<Vec<f64>>) -> Self {
+        self.dash = dash.into();
+        self
+    }
+}
+
+#[test]
+fn test_paths() {
+    let close = |a: (f64, f64, f64, f64), b: (f64, f64, f64, f64)| {
+        (a.0 - b.0).abs() < 1e-4
+            && (a.1 - b.1).abs() < 1e-4
+            && (a.2 - b.2).abs() < 1e-4
+            && (a.3 - b.3).abs() < 1e-4
+    };
+
+    let triangle = Path::new().move_to(0.0, 10.0).line_to(5.0, 0.0).line_to(10.0, 10.0).close();
+    assert_eq!(triangle.commands().len(), 4);
+    assert_eq!(triangle.bounds(), Some((0.0, 0.0, 10.0, 10.0)));
+    assert_eq!(Path::new().bounds(), None);
+
+    // A path without a start starts at its first point
+    let line = Path::new().line_to(3.0, 4.0);
+    assert_eq!(line.commands(), &[PathCommand::MoveTo { x: 3.0, y: 4.0 }]);
+
+    // A half circle below its center, in two quarter curves that end on
+    // the circle
+    let arc = Path::new().arc((10.0, 10.0), 5.0, 0.0, 180.0);
+    assert_eq!(arc.commands().len(), 3);
+    assert!(close(arc.bounds().unwrap(), (5.0, 10.0, 10.0, 5.0)));
+    match arc.commands()[1] {
+        PathCommand::CubicTo { x, y, .. } => assert!((x - 10.0).abs() < 1e-9 && (y - 15.0).abs() < 1e-9),
+        other => panic!("expected a curve, got {other:?}"),
+    }
+
+    // Circles are centered in their frame, capsules are fully rounded
+    let circle = Shape::Circle.path(100.0, 50.0);
+    assert!(close(circle.bounds().unwrap(), (25.0, 0.0, 50.0, 50.0)));
+    let capsule = Shape::Capsule.path(100.0, 50.0);
+    assert!(close(capsule.bounds().unwrap(), (0.0, 0.0, 100.0, 50.0)));
+    assert_eq!(
+        Shape::RoundedRectangle { radius: 0.0 }.path(4.0, 2.0),
+        Shape::Rectangle.path(4.0, 2.0)
+    );
+}
+
+#[test]
+fn test_shapes() {
+    use crate::{
+        color::{Color, ColorScheme},
+        composition::VStack,
+        elements::{Circle, Element, Modifiers, Rectangle, Text},
+        fill::Fill,
+        pal::headless::Context,
+        postprocessing::{fill, stroke},
+        shadow::NodeKind,
+        shape::Stroke,
+    };
+
+    let modifiers = Modifiers::default();
+    let circle = fill(Color::SystemRed, Box::new(Circle::new(modifiers.fork())), modifiers.fork());
+    let circle = stroke(Color::Text, 2.0, [4.0, 2.0], circle, modifiers.fork());
+    let rectangle = stroke(
+        Color::SystemBlue,
+        1.0,
+        [],
+        Box::new(Rectangle::new(modifiers.fork())),
+        modifiers.fork(),
+    );
+    let text: Box<dyn Element> = Box::new(Text::new("Caption", modifiers.fork()));
+    let column = VStack::new(modifiers.clone(), vec![circle, rectangle, text]);
+    let window = Context::new(ColorScheme::Light).render(Box::new(column), 400.0, 300.0);
+
+    // Shapes share what the text leaves over and span the whole width
+    let circle = &window.root.children[0];
+    let rectangle = &window.root.children[1];
+    let text = &window.root.children[2];
+    let circle_layout = *window.layout(circle).unwrap();
+    let rectangle_layout = *window.layout(rectangle).unwrap();
+    let text_height = window.layout(text).unwrap().size.height;
+    assert_eq!(circle_layout.size.width, 400.0);
+    // Equal shares, give or take the pixel that snapping moves around
+    assert!((circle_layout.size.height - rectangle_layout.size.height).abs() <= 1.0);
+    assert_eq!(
+        circle_layout.size.height + rectangle_layout.size.height + text_height,
+        300.0
+    );
+
+    match &circle.kind {
+        NodeKind::Shape { fill, stroke, .. } => {
+            assert_eq!(fill, &Some(Fill::Color(Color::SystemRed)));
+            assert_eq!(stroke, &Some(Stroke::new(Color::Text, 2.0).dashed([4.0, 2.0])));
+        }
+        other => panic!("expected a shape, got {other:?}"),
+    }
+    // Stroked shapes are only filled if asked to
+    assert!(matches!(&rectangle.kind, NodeKind::Shape { fill: None, .. }));
+
+    let (x, y, width, height) = window.shape_path(circle).unwrap().bounds().unwrap();
+    assert!((width - circle_layout.size.height as f64).abs() < 1e-3);
+    assert!((x + width / 2.0 - 200.0).abs() < 1e-3);
+    assert!(y.abs() < 1e-3 && (height - width).abs() < 1e-3);
+    assert!(window.shape_path(text).is_none());
+}
@@ -0,0 +1,267 @@
+use crate::postprocessing::AffineTransform;
+
+/// One step of a [`Path`]. Arcs are turned into cubic curves when they are
+/// added, so renderers only deal with these.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathCommand {
+    MoveTo { x: f64, y: f64 },
+    LineTo { x: f64, y: f64 },
+    QuadTo { x1: f64, y1: f64, x: f64, y: f64 },
+    CubicTo {
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        x: f64,
+        y: f64,
+    },
+    Close,
+}
+
+/// An outline made of lines and curves, in logical pixels with y pointing
+/// down.
+///
+/// ```ignore
+/// let triangle = Path::new().move_to(0.0, 10.0).line_to(5.0, 0.0).line_to(10.0, 10.0).close();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Path {
+    commands: Vec<PathCommand>,
+    /// Where the current subpath started, for `close`
+    start: (f64, f64),
+    current: Option<(f64, f64)>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Start a new subpath at `(x, y)`.
+    pub fn move_to(mut self, x: f64, y: f64) -> Self {
+        self.commands.push(PathCommand::MoveTo { x, y });
+        self.start = (x, y);
+        self.current = Some((x, y));
+        self
+    }
+
+    /// A straight line to `(x, y)`. Starts the path there if it is empty.
+    pub fn line_to(mut self, x: f64, y: f64) -> Self {
+        if self.current.is_none() {
+            return self.move_to(x, y);
+        }
+        self.commands.push(PathCommand::LineTo { x, y });
+        self.current = Some((x, y));
+        self
+    }
+
+    /// A quadratic Bézier curve to `(x, y)` bent towards `(x1, y1)`.
+    pub fn quad_to(mut self, x1: f64, y1: f64, x: f64, y: f64) -> Self {
+        if self.current.is_none() {
+            self = self.move_to(x1, y1);
+        }
+        self.commands.push(PathCommand::QuadTo { x1, y1, x, y });
+        self.current = Some((x, y));
+        self
+    }
+
+    /// A cubic Bézier curve to `(x, y)` with control points `(x1, y1)` and
+    /// `(x2, y2)`.
+    pub fn cubic_to(mut self, x1: f64, y1: f64, x2: f64, y2: f64, x: f64, y: f64) -> Self {
+        if self.current.is_none() {
+            self = self.move_to(x1, y1);
+        }
+        self.commands.push(PathCommand::CubicTo {
+            x1,
+            y1,
+            x2,
+            y2,
+            x,
+            y,
+        });
+        self.current = Some((x, y));
+        self
+    }
+
+    /// A circular arc around `center` from `start` to `end` degrees, where
+    /// `0` points to the trailing side and angles grow clockwise. It runs
+    /// counterclockwise if `end` is less than `start`. A line joins the
+    /// current point to the start of the arc.
+    pub fn arc(mut self, center: (f64, f64), radius: f64, start: f64, end: f64) -> Self {
+        let (cx, cy) = center;
+        let point = |degrees: f64| {
+            let (sin, cos) = degrees.to_radians().sin_cos();
+            (cx + radius * cos, cy + radius * sin)
+        };
+
+        let (x, y) = point(start);
+        self = match self.current {
+            Some(_) => self.line_to(x, y),
+            None => self.move_to(x, y),
+        };
+
+        // Quarter circles or less are approximated closely by one cubic
+        let segments = ((end - start).abs() / 90.0).ceil().max(1.0) as usize;
+        let sweep = (end - start) / segments as f64;
+        let handle = 4.0 / 3.0 * (sweep.to_radians() / 4.0).tan() * radius;
+        for i in 0..segments {
+            let from = start + sweep * i as f64;
+            let to = from + sweep;
+            let (sin0, cos0) = from.to_radians().sin_cos();
+            let (sin1, cos1) = to.to_radians().sin_cos();
+            let (x0, y0) = point(from);
+            let (x1, y1) = point(to);
+            self = self.cubic_to(
+                x0 - handle * sin0,
+                y0 + handle * cos0,
+                x1 + handle * sin1,
+                y1 - handle * cos1,
+                x1,
+                y1,
+            );
+        }
+        self
+    }
+
+    /// Close the current subpath with a line back to where it started.
+    pub fn close(mut self) -> Self {
+        if self.current.is_some() {
+            self.commands.push(PathCommand::Close);
+            self.current = Some(self.start);
+        }
+        self
+    }
+
+    /// Add a closed rectangle.
+    pub fn rect(self, x: f64, y: f64, width: f64, height: f64) -> Self {
+        self.move_to(x, y)
+            .line_to(x + width, y)
+            .line_to(x + width, y + height)
+            .line_to(x, y + height)
+            .close()
+    }
+
+    /// Add a closed rectangle with corners rounded by `radius`, at most
+    /// half the shorter side.
+    pub fn rounded_rect(self, x: f64, y: f64, width: f64, height: f64, radius: f64) -> Self {
+        let r = radius.clamp(0.0, width.min(height).max(0.0) / 2.0);
+        if r == 0.0 {
+            return self.rect(x, y, width, height);
+        }
+        // Start a new subpath where the first arc starts
+        let mut path = self;
+        path.current = None;
+        path.arc((x + width - r, y + r), r, 270.0, 360.0)
+            .arc((x + width - r, y + height - r), r, 0.0, 90.0)
+            .arc((x + r, y + height - r), r, 90.0, 180.0)
+            .arc((x + r, y + r), r, 180.0, 270.0)
+            .close()
+    }
+
+    /// Add a closed ellipse filling the rectangle.
+    pub fn ellipse(self, x: f64, y: f64, width: f64, height: f64) -> Self {
+        let (rx, ry) = (width / 2.0, height / 2.0);
+        // A unit circle stretched to the radii
+        let circle = Path::new().arc((0.0, 0.0), 1.0, 0.0, 360.0).close();
+        let transform = AffineTransform::scaling(rx as f32, ry as f32)
+            .then(&AffineTransform::translation((x + rx) as f32, (y + ry) as f32));
+        self.append(&circle.transformed(&transform))
+    }
+
+    /// Add every subpath of `other`.
+    pub fn append(mut self, other: &Path) -> Self {
+        self.commands.extend_from_slice(&other.commands);
+        if other.current.is_some() {
+            self.start = other.start;
+            self.current = other.current;
+        }
+        self
+    }
+
+    /// The path with every point passed through `transform`.
+    pub fn transformed(&self, transform: &AffineTransform) -> Self {
+        let apply = |x: f64, y: f64| {
+            let (x, y) = transform.apply(x as f32, y as f32);
+            (x as f64, y as f64)
+        };
+        let commands = self
+            .commands
+            .iter()
+            .map(|command| match *command {
+                PathCommand::MoveTo { x, y } => {
+                    let (x, y) = apply(x, y);
+                    PathCommand::MoveTo { x, y }
+                }
+                PathCommand::LineTo { x, y } => {
+                    let (x, y) = apply(x, y);
+                    PathCommand::LineTo { x, y }
+                }
+                PathCommand::QuadTo { x1, y1, x, y } => {
+                    let ((x1, y1), (x, y)) = (apply(x1, y1), apply(x, y));
+                    PathCommand::QuadTo { x1, y1, x, y }
+                }
+                PathCommand::CubicTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    let ((x1, y1), (x2, y2), (x, y)) = (apply(x1, y1), apply(x2, y2), apply(x, y));
+                    PathCommand::CubicTo {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                    }
+                }
+                PathCommand::Close => PathCommand::Close,
+            })
+            .collect();
+        Self {
+            commands,
+            start: apply(self.start.0, self.start.1),
+            current: self.current.map(|(x, y)| apply(x, y)),
+        }
+    }
+
+    /// The smallest rectangle holding every point and control point, as
+    /// `(x, y, width, height)`. Curves never leave it.
+    pub fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        let points = self.commands.iter().flat_map(|command| match *command {
+            PathCommand::MoveTo { x, y } | PathCommand::LineTo { x, y } => vec![(x, y)],
+            PathCommand::QuadTo { x1, y1, x, y } => vec![(x1, y1), (x, y)],
+            PathCommand::CubicTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => vec![(x1, y1), (x2, y2), (x, y)],
+            PathCommand::Close => vec![],
+        });
+        let (mut left, mut top, mut right, mut bottom) =
+            (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for (x, y) in points {
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+        (left <= right).then_some((left, top, right - left, bottom - top))
+    }
+}
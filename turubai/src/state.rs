@@ -0,0 +1,109 @@
+//! Values that trigger a rebuild of the interface when they change.
+//!
+//! ```ignore
+//! let count = State::new(0);
+//! // in markup: Text("{}", count.get())
+//! with_animation(Animation::ease_in_out(0.3), || count.update(|n| *n += 1));
+//! ```
+//!
+//! Backends poll [`generation`] once per frame. When it moved, they call
+//! the markup again, diff the new shadow tree against the one on screen and
+//! animate the differences with the animation the change was made under.
+
+use std::{
+    cell::RefCell,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use crate::animation::{self, Animation};
+
+struct Change {
+    generation: u64,
+    /// `None` if it wasn't made inside
+    /// [`with_animation`](crate::animation::with_animation)
+    animation: Option<Animation>,
+}
+
+static LATEST: LazyLock<Mutex<Change>> = LazyLock::new(|| {
+    Mutex::new(Change {
+        generation: 0,
+        animation: None,
+    })
+});
+
+thread_local! {
+    /// The latest change made on this thread
+    static LOCAL: RefCell<Option<Change>> = const { RefCell::new(None) };
+}
+
+fn changed() {
+    let mut latest = LATEST.lock().unwrap();
+    latest.generation += 1;
+    latest.animation = animation::current();
+    LOCAL.with(|local| {
+        *local.borrow_mut() = Some(Change {
+            generation: latest.generation,
+            animation: latest.animation.clone(),
+        })
+    });
+}
+
+/// Make backends rebuild the interface as if state changed, e.g. because
+/// a file the markup is read from changed.
+pub fn invalidate() {
+    changed();
+}
+
+/// A counter bumped on every state change.
+pub fn generation() -> u64 {
+    LATEST.lock().unwrap().generation
+}
+
+/// The animation to apply for changes made after `generation`: that of
+/// the latest one made on this thread, or else the latest one overall.
+pub fn animation_since(generation: u64) -> Option<Animation> {
+    let local = LOCAL.with(|local| {
+        local
+            .borrow()
+            .as_ref()
+            .filter(|change| change.generation > generation)
+            .map(|change| change.animation.clone())
+    });
+    local.unwrap_or_else(|| LATEST.lock().unwrap().animation.clone())
+}
+
+/// A shared, observable value. Clones refer to the same value.
+#[derive(Debug, Default)]
+pub struct State<T> {
+    value: Arc<Mutex<T>>,
+}
+
+impl<T> Clone for State<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T: Clone> State<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Arc::new(Mutex::new(value)),
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.value.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, value: T) {
+        *self.value.lock().unwrap() = value;
+        changed();
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value.lock().unwrap());
+        changed();
+    }
+}
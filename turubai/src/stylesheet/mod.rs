@@ -0,0 +1,231 @@
+//! Rules that restyle elements by name, id and class without touching the
+//! markup.
+//!
+//! ```ignore
+//! let stylesheet = Stylesheet::new()
+//!     .rule("text.muted", |m| m.text.color = Color::SecondaryText)
+//!     .rule("#title", |m| m.text.style = TextStyle::Title)
+//!     .rule("v_stack > text", |m| m.text.font = Some(Font::new("Inter", 14, FontWeight::Regular, false)));
+//! ```
+//!
+//! Rules are applied onto an element's modifiers while its shadow node is
+//! built, after everything set in markup, so a matching rule wins over
+//! inline values. When several rules match, the more specific one wins and
+//! among equally specific rules the one added last does. Only elements
+//! carrying [`Modifiers`](crate::elements::Modifiers) can be restyled, and
+//! rules don't inherit: use a descendant selector to reach children.
+
+mod selector;
+
+pub use selector::{ParseSelectorError, Selector, StyleSubject};
+
+use std::{fmt, sync::Arc};
+
+use crate::elements::ModifiersInner;
+
+type Declarations = Arc<dyn Fn(&mut ModifiersInner) + Send + Sync>;
+
+/// The id and classes assigned with `.id()` and `.class()`, along with
+/// the key assigned with `.key()`, which belongs to the element the same
+/// way.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorModifiers {
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    /// Tells the element apart from its siblings across rebuilds, see
+    /// [`NodeIdentity`](crate::shadow::NodeIdentity)
+    pub key: Option<String>,
+}
+
+#[derive(Clone)]
+struct Rule {
+    selector: Selector,
+    declarations: Declarations,
+}
+
+/// An ordered list of rules.
+#[derive(Clone, Default)]
+pub struct Stylesheet {
+    rules: Arc<Vec<Rule>>,
+}
+
+impl Stylesheet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule. Panics if `selector` does not parse; use
+    /// [`Selector::parse`] with [`Stylesheet::add_rule`] to handle errors.
+    pub fn rule(
+        self,
+        selector: &str,
+        declarations: impl Fn(&mut ModifiersInner) + Send + Sync + 'static,
+    ) -> Self {
+        let selector = Selector::parse(selector).unwrap_or_else(|err| panic!("{err}"));
+        self.add_rule(selector, declarations)
+    }
+
+    pub fn add_rule(
+        mut self,
+        selector: Selector,
+        declarations: impl Fn(&mut ModifiersInner) + Send + Sync + 'static,
+    ) -> Self {
+        Arc::make_mut(&mut self.rules).push(Rule {
+            selector,
+            declarations: Arc::new(declarations),
+        });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Apply every rule matching `subject` to `modifiers`, least specific first.
+    pub fn apply(
+        &self,
+        modifiers: &mut ModifiersInner,
+        subject: &StyleSubject,
+        ancestors: &[StyleSubject],
+    ) {
+        let mut matching: Vec<&Rule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.selector.matches(subject, ancestors))
+            .collect();
+        // Stable, so equally specific rules keep their order.
+        matching.sort_by_key(|rule| rule.selector.specificity());
+
+        for rule in matching {
+            (rule.declarations)(modifiers);
+        }
+    }
+}
+
+impl fmt::Debug for Stylesheet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.rules.iter().map(|rule| &rule.selector))
+            .finish()
+    }
+}
+
+/// Rules hold closures, so two stylesheets are only equal if they share
+/// the same rules.
+impl PartialEq for Stylesheet {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.rules, &other.rules)
+    }
+}
+
+#[test]
+fn test_selectors() {
+    let subject = |name: &'static str, id: Option<&str>, classes: &[&str]| StyleSubject {
+        name: name.into(),
+        id: id.map(str::to_string),
+        classes: classes.iter().map(|class| class.to_string()).collect(),
+    };
+    let stack = subject("v_stack", Some("sidebar"), &[]);
+    let padding = subject("padding", None, &["card"]);
+    let text = subject("text", Some("title"), &["muted", "large"]);
+
+    let matches = |selector: &str, ancestors: &[StyleSubject]| {
+        Selector::parse(selector).unwrap().matches(&text, ancestors)
+    };
+    assert!(matches("text", &[]));
+    assert!(matches("*", &[]));
+    assert!(matches("#title", &[]));
+    assert!(matches("text.muted.large", &[]));
+    assert!(!matches("text.bold", &[]));
+    assert!(matches("v_stack text", &[stack.clone(), padding.clone()]));
+    assert!(matches("#sidebar .card > text", &[stack.clone(), padding.clone()]));
+    assert!(!matches("v_stack > text", &[stack.clone(), padding.clone()]));
+    assert!(!matches("h_stack text", &[stack.clone(), padding.clone()]));
+
+    assert_eq!(Selector::parse("v_stack > .muted#title").unwrap().specificity(), (1, 1, 1));
+    assert!(Selector::parse("> text").is_err());
+    assert!(Selector::parse("text >").is_err());
+    assert!(Selector::parse("text..muted").is_err());
+    assert!(Selector::parse("").is_err());
+    assert!(Selector::parse("text→muted").is_err());
+}
+
+#[test]
+fn test_stylesheet() {
+    use crate::{
+        color::{Color, ColorScheme},
+        composition::VStack,
+        elements::{Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::{class, id, padding},
+        shadow::{NodeKind, ShadowNode},
+    };
+
+    let modifiers = Modifiers::default();
+    let title = id("title", Box::new(Text::new("Title", modifiers.fork())), modifiers.fork());
+    let muted = class("muted", Box::new(Text::new("Muted", modifiers.fork())), modifiers.fork());
+    let padded = padding(Box::new(Text::new("Padded", modifiers.fork())), modifiers.fork());
+    let stack = VStack::new(modifiers.clone(), vec![title, muted, Box::new(padded)]);
+
+    let stylesheet = Stylesheet::new()
+        .rule("text", |m| m.text.color = Color::SystemGreen)
+        .rule("#title", |m| m.text.color = Color::SystemRed)
+        .rule("text.muted", |m| m.text.color = Color::SecondaryText)
+        .rule("v_stack > text", |m| m.text.color = Color::SystemBlue);
+    let mut window = Context::new(ColorScheme::Light)
+        .with_stylesheet(stylesheet)
+        .render(Box::new(stack), 400.0, 300.0);
+
+    let text_color = |node: &ShadowNode| match &node.kind {
+        NodeKind::Text { color, .. } => *color,
+        _ => unreachable!(),
+    };
+    let children = &window.root.children;
+    assert_eq!(text_color(&children[0]), Color::SystemRed);
+    assert_eq!(text_color(&children[1]), Color::SecondaryText);
+    assert_eq!(text_color(&children[2].children[0]), Color::SystemGreen);
+
+    // Rules are only in effect while the tree is built.
+    let environment = window.tree.environment().clone().with_stylesheet(Stylesheet::new());
+    window.set_environment(environment);
+    assert_eq!(text_color(&window.root.children[0]), Color::Text);
+}
+
+#[test]
+fn test_stylesheet_insets() {
+    use crate::{
+        color::{Color, ColorScheme},
+        elements::{Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::{border, padding, BorderStyle, CornerRadii},
+        shadow::NodeKind,
+        Unit,
+    };
+
+    let modifiers = Modifiers::default();
+    let text = Box::new(Text::new("Padded", modifiers.fork()));
+    let padded = Box::new(padding(text, modifiers.fork()));
+    let bordered = border(1.0, Color::Text, BorderStyle::Solid, padded, modifiers);
+
+    let stylesheet = Stylesheet::new()
+        .rule("padding", |m| m.padding.all = Unit::Pixels(20.0))
+        .rule("border", |m| m.border.radius = Some(6.0));
+    let window = Context::new(ColorScheme::Light)
+        .with_stylesheet(stylesheet)
+        .render(Box::new(bordered), 400.0, 300.0);
+
+    let root = &window.root;
+    assert!(matches!(
+        root.kind,
+        NodeKind::Border { radii, .. } if radii == CornerRadii::all(6.0)
+    ));
+    assert!(matches!(
+        root.children[0].kind,
+        NodeKind::Padding { top, left, bottom, right }
+            if [top, left, bottom, right] == [20.0; 4]
+    ));
+}
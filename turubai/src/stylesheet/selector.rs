@@ -0,0 +1,175 @@
+use std::{borrow::Cow, fmt};
+
+/// What a selector is matched against: an element's name together with the
+/// id and classes assigned to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyleSubject {
+    pub name: Cow<'static, str>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+}
+
+/// A simple selector such as `text`, `#title`, `.muted` or `text.muted`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Compound {
+    /// `None` for `*` or when only an id or classes are given
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl Compound {
+    fn matches(&self, subject: &StyleSubject) -> bool {
+        self.name.as_deref().is_none_or(|name| name == subject.name)
+            && self
+                .id
+                .as_ref()
+                .is_none_or(|id| subject.id.as_ref() == Some(id))
+            && self
+                .classes
+                .iter()
+                .all(|class| subject.classes.contains(class))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    /// `a b`: `b` anywhere below `a`
+    Descendant,
+    /// `a > b`: `b` directly below `a`
+    Child,
+}
+
+/// A parsed selector: simple selectors joined by descendant (` `) or
+/// child (`>`) combinators, e.g. `v_stack > text.muted` or `#sidebar text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    /// The rightmost simple selector, which the subject itself must match
+    subject: Compound,
+    /// The remaining simple selectors from right to left, each with the
+    /// combinator that joins it to the one on its right
+    ancestors: Vec<(Combinator, Compound)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSelectorError {
+    pub input: String,
+}
+
+impl fmt::Display for ParseSelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid selector: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for ParseSelectorError {}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+fn parse_compound(input: &str) -> Option<Compound> {
+    let mut compound = Compound::default();
+    let mut rest = input;
+
+    if let Some(after) = rest.strip_prefix('*') {
+        rest = after;
+    } else {
+        let end = rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len());
+        if end > 0 {
+            compound.name = Some(rest[..end].to_string());
+            rest = &rest[end..];
+        }
+    }
+
+    while let Some(marker) = rest.chars().next() {
+        let after = &rest[marker.len_utf8()..];
+        let end = after.find(|c| !is_ident_char(c)).unwrap_or(after.len());
+        if end == 0 {
+            return None;
+        }
+        let ident = after[..end].to_string();
+        match marker {
+            '#' if compound.id.is_none() => compound.id = Some(ident),
+            '.' => compound.classes.push(ident),
+            _ => return None,
+        }
+        rest = &after[end..];
+    }
+
+    Some(compound)
+}
+
+impl Selector {
+    pub fn parse(input: &str) -> Result<Self, ParseSelectorError> {
+        let err = || ParseSelectorError {
+            input: input.to_string(),
+        };
+
+        // Make `>` its own token so `a>b` and `a > b` read the same.
+        let spaced = input.replace('>', " > ");
+        let mut compounds = vec![];
+        // combinators[i] joins compounds[i] and compounds[i + 1]
+        let mut combinators = vec![];
+        let mut child = false;
+        for token in spaced.split_whitespace() {
+            if token == ">" {
+                if compounds.is_empty() || child {
+                    return Err(err());
+                }
+                child = true;
+                continue;
+            }
+            if !compounds.is_empty() {
+                combinators.push(if child {
+                    Combinator::Child
+                } else {
+                    Combinator::Descendant
+                });
+            }
+            compounds.push(parse_compound(token).ok_or_else(err)?);
+            child = false;
+        }
+        if child {
+            return Err(err());
+        }
+
+        let subject = compounds.pop().ok_or_else(err)?;
+        let ancestors = combinators.into_iter().zip(compounds).rev().collect();
+        Ok(Self { subject, ancestors })
+    }
+
+    /// `(ids, classes, names)`, compared like CSS specificity.
+    pub fn specificity(&self) -> (usize, usize, usize) {
+        let compounds = std::iter::once(&self.subject).chain(self.ancestors.iter().map(|(_, c)| c));
+        compounds.fold((0, 0, 0), |(ids, classes, names), compound| {
+            (
+                ids + compound.id.is_some() as usize,
+                classes + compound.classes.len(),
+                names + compound.name.is_some() as usize,
+            )
+        })
+    }
+
+    /// Whether `subject`, whose ancestors from the root down are
+    /// `ancestors`, is selected.
+    pub fn matches(&self, subject: &StyleSubject, ancestors: &[StyleSubject]) -> bool {
+        self.subject.matches(subject) && match_ancestors(&self.ancestors, ancestors)
+    }
+}
+
+fn match_ancestors(selectors: &[(Combinator, Compound)], ancestors: &[StyleSubject]) -> bool {
+    let Some(((combinator, compound), rest)) = selectors.split_first() else {
+        return true;
+    };
+
+    match combinator {
+        Combinator::Child => match ancestors.split_last() {
+            Some((parent, above)) => compound.matches(parent) && match_ancestors(rest, above),
+            None => false,
+        },
+        Combinator::Descendant => (0..ancestors.len()).rev().any(|i| {
+            compound.matches(&ancestors[i]) && match_ancestors(rest, &ancestors[..i])
+        }),
+    }
+}
@@ -0,0 +1,66 @@
+use crate::image::Bitmap;
+
+/// How two pictures of the same size differ.
+pub struct Comparison {
+    /// Number of pixels that differ noticeably
+    pub different_pixels: usize,
+    /// The expected picture faded to gray, with differing pixels in red
+    pub diff: Bitmap,
+}
+
+/// Compare pictures pixel by pixel in the YIQ color space, which weighs
+/// differences roughly the way eyes do. `threshold` goes from 0 (every
+/// change counts) to 1 (nothing counts); `0.1` lets antialiasing noise
+/// through but not a change of color. `None` if the sizes differ.
+pub fn compare(expected: &Bitmap, actual: &Bitmap, threshold: f64) -> Option<Comparison> {
+    if (expected.width(), expected.height()) != (actual.width(), actual.height()) {
+        return None;
+    }
+    // The largest possible delta, between black and white
+    let max_delta = 35215.0 * threshold * threshold;
+
+    let mut different_pixels = 0;
+    let mut pixels = Vec::with_capacity(expected.pixels().len());
+    for (expected, actual) in expected
+        .pixels()
+        .chunks_exact(4)
+        .zip(actual.pixels().chunks_exact(4))
+    {
+        if delta(expected, actual) > max_delta {
+            different_pixels += 1;
+            pixels.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let (y, _, _) = yiq(expected);
+            let faded = (255.0 - 0.1 * (255.0 - y)).round() as u8;
+            pixels.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+    let diff = Bitmap::new(expected.width(), expected.height(), pixels).unwrap();
+    Some(Comparison {
+        different_pixels,
+        diff,
+    })
+}
+
+/// The perceived difference of two RGBA pixels, after blending them onto
+/// white.
+fn delta(a: &[u8], b: &[u8]) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let (y1, i1, q1) = yiq(a);
+    let (y2, i2, q2) = yiq(b);
+    let (y, i, q) = (y1 - y2, i1 - i2, q1 - q2);
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+fn yiq(pixel: &[u8]) -> (f64, f64, f64) {
+    let alpha = pixel[3] as f64 / 255.0;
+    let blend = |channel: u8| 255.0 + (channel as f64 - 255.0) * alpha;
+    let (r, g, b) = (blend(pixel[0]), blend(pixel[1]), blend(pixel[2]));
+    (
+        0.29889531 * r + 0.58662247 * g + 0.11448223 * b,
+        0.59597799 * r - 0.27417610 * g - 0.32180189 * b,
+        0.21147017 * r - 0.52261711 * g + 0.31114694 * b,
+    )
+}
@@ -0,0 +1,160 @@
+//! Helpers for testing user interfaces headlessly.
+//!
+//! [`assert_layout!`](crate::assert_layout) compares the laid out tree of a
+//! headless window with a dump written inline in the test, see
+//! [`ShadowTree::dump`](crate::shadow::ShadowTree::dump):
+//!
+//! ```ignore
+//! let window = Context::new(ColorScheme::Light).render(element, 800.0, 600.0);
+//! assert_layout!(window, r#"
+//!     VStack [0,0 800x600]
+//!       Text "Hello" [312,0 176x19]
+//! "#);
+//! ```
+//!
+//! With the `raster` feature, [`assert_snapshot!`](crate::assert_snapshot)
+//! paints an element or an application and compares the picture with a
+//! golden image checked in under `tests/snapshots` in the crate being
+//! tested:
+//!
+//! ```ignore
+//! #[test]
+//! fn test_settings_page() {
+//!     assert_snapshot!(&SettingsApp, size = (800, 600));
+//!     assert_snapshot!(&SettingsApp, size = (800, 600), color_scheme = ColorScheme::Dark, name = "dark");
+//! }
+//! ```
+//!
+//! Goldens are named after the test function, plus `name` if a test takes
+//! more than one. When a picture doesn't match, the test fails and writes
+//! `<golden>.actual.png` and `<golden>.diff.png` next to the golden, with
+//! the differing pixels in red. Run the tests with
+//! `TURUBAI_UPDATE_SNAPSHOTS=1` to record new goldens or accept changes.
+
+#[cfg(feature = "raster")]
+mod diff;
+#[cfg(feature = "raster")]
+mod snapshot;
+
+#[cfg(feature = "raster")]
+pub use diff::{compare, Comparison};
+#[cfg(feature = "raster")]
+pub use snapshot::*;
+
+/// Set to anything but `0` to write goldens instead of comparing with them.
+pub const UPDATE_ENV: &str = "TURUBAI_UPDATE_SNAPSHOTS";
+
+/// Compare the layout of a headless window with an expected dump. The
+/// expectation may be indented to line up with the test.
+#[macro_export]
+macro_rules! assert_layout {
+    ($window:expr, $expected:expr $(,)?) => {
+        $crate::testing::assert_layout_eq(&$window.dump_layout(), $expected)
+    };
+}
+
+/// Compare a layout dump with an expected one, ignoring the indentation
+/// the expected lines have in common and blank lines around them.
+///
+/// # Panics
+///
+/// If the layouts differ, listing both with the differing lines marked.
+#[track_caller]
+pub fn assert_layout_eq(actual: &str, expected: &str) {
+    let actual = dedent(actual);
+    let expected = dedent(expected);
+    if actual == expected {
+        return;
+    }
+
+    let mut message = String::from("layouts differ (- expected, + actual):\n");
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                message += &format!("  {expected}\n");
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    message += &format!("- {expected}\n");
+                }
+                if let Some(actual) = actual {
+                    message += &format!("+ {actual}\n");
+                }
+            }
+        }
+    }
+    panic!("{message}");
+}
+
+/// Strip blank lines at either end, trailing whitespace and the
+/// indentation all lines share.
+fn dedent(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return String::new();
+    };
+    let lines = &lines[first..=last];
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_layout_dump() {
+    use crate::{
+        color::ColorScheme,
+        composition::{HStack, Spacer, VStack},
+        elements::{Element, Modifiers, Text},
+        pal::headless::Context,
+        postprocessing::Padding,
+        Unit,
+    };
+
+    let modifiers = Modifiers::default();
+    let title: Box<dyn Element> = Box::new(Text::new("Hello", modifiers.fork()));
+    let row: Box<dyn Element> = Box::new(HStack::new(
+        modifiers.fork(),
+        vec![
+            Box::new(Text::new("A", modifiers.fork())),
+            Box::new(Spacer::turubai_new_with_0_args(
+                modifiers.fork(),
+                |_| vec![],
+            )),
+            Box::new(Text::new("B", modifiers.fork())),
+        ],
+    ));
+    let inset = Unit::Pixels(8.0);
+    let row: Box<dyn Element> = Box::new(Padding::new(inset, inset, inset, inset, row));
+    let root = Box::new(VStack::new(modifiers, vec![title, row]));
+
+    let window = Context::new(ColorScheme::Light).render(root, 200.0, 100.0);
+    crate::assert_layout!(
+        window,
+        r#"
+        VStack [0,0 200x100]
+          Text "Hello" [0,0 40x21]
+          Padding [0,21 200x79]
+            HStack [8,8 184x63]
+              Text "A" [0,0 12x21]
+              Spacer [12,0 160x0]
+              Text "B" [172,0 12x21]
+        "#
+    );
+
+    let result =
+        std::panic::catch_unwind(|| assert_layout_eq("A [0,0 1x1]\nB [1,0 1x1]", "A [0,0 1x1]"));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.ends_with("  A [0,0 1x1]\n+ B [1,0 1x1]\n"));
+}
@@ -0,0 +1,310 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{compare, UPDATE_ENV};
+use crate::{
+    color::ColorScheme,
+    elements::Element,
+    image::{Bitmap, ImageSource},
+    pal::headless::{Context, Window},
+    Application,
+};
+
+/// Paint `subject` and compare it with its golden image. Options are set
+/// with `option = value` after it, see the methods of [`Snapshot`].
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($subject:expr $(, $option:ident = $value:expr)* $(,)?) => {{
+        fn snapshot() {}
+        $crate::testing::Snapshot::new(
+            ::std::any::type_name_of_val(&snapshot),
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"),
+        )
+        $(.$option($value))*
+        .assert($subject)
+    }};
+}
+
+/// Something a snapshot can be taken of.
+pub trait Subject {
+    fn snapshot(self, context: &Context, width: f64, height: f64) -> Bitmap;
+}
+
+impl Subject for Box<dyn Element> {
+    fn snapshot(self, context: &Context, width: f64, height: f64) -> Bitmap {
+        context.render(self, width, height).snapshot()
+    }
+}
+
+impl<A: Application> Subject for &A {
+    fn snapshot(self, context: &Context, width: f64, height: f64) -> Bitmap {
+        context.render_application(self, width, height).snapshot()
+    }
+}
+
+/// A window is painted as it is, ignoring the snapshot's size and context.
+impl Subject for &Window {
+    fn snapshot(self, _context: &Context, _width: f64, _height: f64) -> Bitmap {
+        Window::snapshot(self)
+    }
+}
+
+/// One comparison with a golden image, usually made by
+/// [`assert_snapshot!`](crate::assert_snapshot).
+pub struct Snapshot {
+    name: String,
+    directory: PathBuf,
+    size: (f64, f64),
+    color_scheme: ColorScheme,
+    scale_factor: f64,
+    threshold: f64,
+    max_different_pixels: usize,
+    update: bool,
+}
+
+impl Snapshot {
+    /// A snapshot named after `function`, a path such as
+    /// `my_crate::tests::test_layout::snapshot`, with its golden in
+    /// `directory`.
+    pub fn new(function: &str, directory: impl AsRef<Path>) -> Self {
+        // Drop the crate, closures and the marker function itself
+        let segments: Vec<&str> = function
+            .split("::")
+            .filter(|segment| *segment != "{{closure}}")
+            .collect();
+        let segments = match segments.len() {
+            0..=2 => &segments[..],
+            len => &segments[1..len - 1],
+        };
+        Self {
+            name: segments.join("__"),
+            directory: directory.as_ref().to_path_buf(),
+            size: (800.0, 600.0),
+            color_scheme: ColorScheme::Light,
+            scale_factor: 1.0,
+            threshold: 0.1,
+            max_different_pixels: 0,
+            update: std::env::var(UPDATE_ENV).is_ok_and(|value| value != "0"),
+        }
+    }
+
+    /// Tell apart several snapshots taken in one test.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = format!("{}__{name}", self.name);
+        self
+    }
+
+    /// The window size in logical pixels, 800 x 600 unless set.
+    pub fn size<W: Into<f64>, H: Into<f64>>(mut self, (width, height): (W, H)) -> Self {
+        self.size = (width.into(), height.into());
+        self
+    }
+
+    pub fn color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.color_scheme = color_scheme;
+        self
+    }
+
+    pub fn scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// How different a pixel has to look to count, see [`compare`].
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// How many pixels may differ before the snapshot fails, none unless set.
+    pub fn max_different_pixels(mut self, max_different_pixels: usize) -> Self {
+        self.max_different_pixels = max_different_pixels;
+        self
+    }
+
+    pub fn directory(mut self, directory: impl AsRef<Path>) -> Self {
+        self.directory = directory.as_ref().to_path_buf();
+        self
+    }
+
+    /// Write the golden instead of comparing with it, like running with
+    /// [`UPDATE_ENV`] set does.
+    pub fn update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    pub fn golden_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.png", self.name))
+    }
+
+    pub fn actual_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.actual.png", self.name))
+    }
+
+    pub fn diff_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.diff.png", self.name))
+    }
+
+    /// Paint `subject` and compare it with the golden, or record it as the
+    /// golden when updating.
+    ///
+    /// # Panics
+    ///
+    /// If there is no golden or the picture differs from it.
+    pub fn assert(self, subject: impl Subject) {
+        let context = Context::new(self.color_scheme).with_scale_factor(self.scale_factor);
+        let (width, height) = self.size;
+        let actual = subject.snapshot(&context, width, height);
+        let golden = self.golden_path();
+        // Whatever the last failure left behind is stale now
+        let _ = fs::remove_file(self.actual_path());
+        let _ = fs::remove_file(self.diff_path());
+
+        if self.update {
+            fs::create_dir_all(&self.directory).unwrap();
+            save(&actual, &golden);
+            return;
+        }
+
+        let expected = match fs::read(&golden) {
+            Ok(data) => ImageSource::bytes(data).load(1.0).unwrap_or_else(|err| {
+                panic!("snapshot `{}`: {err} ({})", self.name, golden.display())
+            }),
+            Err(_) => {
+                self.save_actual(&actual);
+                panic!(
+                    "snapshot `{}` has no golden image at {}, run with {UPDATE_ENV}=1 to record it",
+                    self.name,
+                    golden.display()
+                );
+            }
+        };
+
+        let Some(comparison) = compare(&expected, &actual, self.threshold) else {
+            self.save_actual(&actual);
+            panic!(
+                "snapshot `{}` is {}x{} but its golden is {}x{}, see {}",
+                self.name,
+                actual.width(),
+                actual.height(),
+                expected.width(),
+                expected.height(),
+                self.actual_path().display()
+            );
+        };
+        if comparison.different_pixels > self.max_different_pixels {
+            self.save_actual(&actual);
+            save(&comparison.diff, &self.diff_path());
+            panic!(
+                "snapshot `{}` differs from {} in {} pixels, see {}, or run with {UPDATE_ENV}=1 to accept it",
+                self.name,
+                golden.display(),
+                comparison.different_pixels,
+                self.diff_path().display()
+            );
+        }
+    }
+
+    fn save_actual(&self, actual: &Bitmap) {
+        if fs::create_dir_all(&self.directory).is_ok() {
+            save(actual, &self.actual_path());
+        }
+    }
+}
+
+fn save(bitmap: &Bitmap, path: &Path) {
+    if let Err(err) = bitmap.save_png(path) {
+        panic!("could not write {}: {err}", path.display());
+    }
+}
+
+#[test]
+fn test_snapshots() {
+    use crate::{
+        color::Color,
+        composition::HStack,
+        elements::{Circle, Modifiers, Rectangle},
+        postprocessing::fill,
+    };
+
+    let row = |color: Color| -> Box<dyn Element> {
+        let modifiers = Modifiers::default();
+        let circle = fill(
+            color,
+            Box::new(Circle::new(modifiers.fork())),
+            modifiers.fork(),
+        );
+        let rectangle = fill(
+            Color::SystemBlue,
+            Box::new(Rectangle::new(modifiers.fork())),
+            modifiers.fork(),
+        );
+        Box::new(HStack::new(modifiers, vec![circle, rectangle]))
+    };
+
+    // Checked in under tests/snapshots
+    crate::assert_snapshot!(row(Color::SystemRed), size = (120, 60), scale_factor = 2.0);
+
+    // Goldens are named after the test
+    let snapshot = Snapshot::new("turubai::testing::test_snapshots::snapshot", "snapshots");
+    assert_eq!(
+        snapshot.golden_path(),
+        Path::new("snapshots/testing__test_snapshots.png")
+    );
+    let snapshot = snapshot.name("dark");
+    assert_eq!(
+        snapshot.diff_path(),
+        Path::new("snapshots/testing__test_snapshots__dark.diff.png")
+    );
+
+    let directory = std::env::temp_dir().join(format!("turubai-snapshots-{}", std::process::id()));
+    let snapshot = || {
+        Snapshot::new("turubai::test_row::snapshot", &directory)
+            .size((120, 60))
+            .update(false)
+    };
+    snapshot().update(true).assert(row(Color::SystemRed));
+    snapshot().assert(row(Color::SystemRed));
+
+    // A changed picture fails and leaves the actual picture and the diff
+    let result = std::panic::catch_unwind(|| snapshot().assert(row(Color::SystemGreen)));
+    assert!(result.is_err());
+    let diff = ImageSource::path(snapshot().diff_path()).load(1.0).unwrap();
+    assert_eq!(diff.pixel(30, 30), Some([255, 0, 0, 255]));
+    assert_ne!(diff.pixel(90, 30), Some([255, 0, 0, 255]));
+    assert!(snapshot().actual_path().exists());
+
+    // Passing again cleans up
+    snapshot().assert(row(Color::SystemRed));
+    assert!(!snapshot().diff_path().exists());
+
+    // Sizes must match
+    let result =
+        std::panic::catch_unwind(|| snapshot().size((100, 60)).assert(row(Color::SystemRed)));
+    assert!(result.is_err());
+    let _ = fs::remove_dir_all(&directory);
+
+    // Close colors are within the default threshold
+    let gray = |level: u8| Bitmap::new(1, 1, vec![level, level, level, 255]).unwrap();
+    assert_eq!(
+        compare(&gray(100), &gray(102), 0.1)
+            .unwrap()
+            .different_pixels,
+        0
+    );
+    assert_eq!(
+        compare(&gray(100), &gray(160), 0.1)
+            .unwrap()
+            .different_pixels,
+        1
+    );
+    assert_eq!(
+        compare(&gray(100), &gray(102), 0.0)
+            .unwrap()
+            .different_pixels,
+        1
+    );
+}
@@ -0,0 +1,256 @@
+use crate::{color::Color, font::Typography, Unit};
+
+/// Design tokens shared by every element in a window. Install one with
+/// [`Application::theme`](crate::Application::theme) or the
+/// `window_template::theme` modifier; elements read it from the
+/// [`Environment`](crate::environment::Environment) while the shadow tree
+/// is built.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Theme {
+    pub palette: Palette,
+    pub typography: Typography,
+    pub spacing: SpacingScale,
+    pub radii: RadiusScale,
+}
+
+impl Theme {
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn with_typography(mut self, typography: Typography) -> Self {
+        self.typography = typography;
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: SpacingScale) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_radii(mut self, radii: RadiusScale) -> Self {
+        self.radii = radii;
+        self
+    }
+}
+
+/// The colors semantic roles are painted with. The default palette maps
+/// every role to the matching system color, so it follows the platform's
+/// light and dark appearance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub text: Color,
+    pub secondary_text: Color,
+    pub background: Color,
+    pub separator: Color,
+    pub accent: Color,
+    pub control_fill: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            text: Color::Text,
+            secondary_text: Color::SecondaryText,
+            background: Color::WindowBackground,
+            separator: Color::Separator,
+            accent: Color::Accent,
+            control_fill: Color::ControlFill,
+        }
+    }
+}
+
+impl Palette {
+    /// Replace a semantic color with this palette's color for the role.
+    /// Any other color is returned unchanged.
+    pub fn apply(&self, color: &Color) -> Color {
+        match color {
+            Color::Text => self.text,
+            Color::SecondaryText => self.secondary_text,
+            Color::WindowBackground => self.background,
+            Color::Separator => self.separator,
+            Color::Accent => self.accent,
+            Color::ControlFill => self.control_fill,
+            other => *other,
+        }
+    }
+}
+
+/// Spacing steps, from tightest to loosest, and the spacing elements fall
+/// back to when they don't set their own. Padding and stack spacing refer
+/// to a step with [`Unit::Space`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpacingScale {
+    pub xxs: Unit,
+    pub xs: Unit,
+    pub sm: Unit,
+    pub md: Unit,
+    pub lg: Unit,
+    pub xl: Unit,
+    /// Spacing between the children of stacks that don't set their own
+    pub stack: Unit,
+}
+
+impl Default for SpacingScale {
+    fn default() -> Self {
+        Self {
+            xxs: Unit::Pixels(2.0),
+            xs: Unit::Pixels(4.0),
+            sm: Unit::Pixels(8.0),
+            md: Unit::Pixels(16.0),
+            lg: Unit::Pixels(24.0),
+            xl: Unit::Pixels(32.0),
+            stack: Unit::Pixels(0.0),
+        }
+    }
+}
+
+impl SpacingScale {
+    pub fn step(&self, space: Space) -> Unit {
+        match space {
+            Space::Xxs => self.xxs,
+            Space::Xs => self.xs,
+            Space::Sm => self.sm,
+            Space::Md => self.md,
+            Space::Lg => self.lg,
+            Space::Xl => self.xl,
+        }
+    }
+
+    /// Replace a [`Unit::Space`] with this scale's step. Any other unit is
+    /// returned unchanged.
+    pub fn resolve(&self, unit: Unit) -> Unit {
+        match unit {
+            Unit::Space(space) => self.step(space),
+            unit => unit,
+        }
+    }
+}
+
+/// A step of the theme's [`SpacingScale`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Space {
+    Xxs,
+    Xs,
+    Sm,
+    Md,
+    Lg,
+    Xl,
+}
+
+/// Corner radius steps in logical pixels, from subtle to pronounced, and
+/// the radii elements fall back to when they don't set their own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadiusScale {
+    pub small: f64,
+    pub medium: f64,
+    pub large: f64,
+    /// Radius of borders that don't set their own
+    pub border: f64,
+    /// Radius of `.corner_radii()` that doesn't set any corner
+    pub corner: f64,
+}
+
+impl Default for RadiusScale {
+    fn default() -> Self {
+        Self {
+            small: 4.0,
+            medium: 8.0,
+            large: 16.0,
+            border: 0.0,
+            corner: 8.0,
+        }
+    }
+}
+
+#[test]
+fn test_theme_swap() {
+    use crate::{
+        color::ColorScheme,
+        composition::VStack,
+        elements::{Modifiers, Text},
+        pal::headless::{Context, Window},
+        shadow::NodeKind,
+    };
+
+    let modifiers = Modifiers::default();
+    let stack = VStack::new(
+        modifiers.clone(),
+        vec![
+            Box::new(Text::new("One", modifiers.clone())),
+            Box::new(Text::new("Two", modifiers.clone())),
+        ],
+    );
+    let mut window = Context::new(ColorScheme::Light).render(Box::new(stack), 400.0, 300.0);
+    let height = |window: &Window| window.layout(&window.root).unwrap().size.height;
+    let text_color = |window: &Window| match &window.root.children[0].kind {
+        NodeKind::Text { color, .. } => *color,
+        _ => unreachable!(),
+    };
+    let before = height(&window);
+    assert_eq!(text_color(&window), Color::Text);
+
+    let theme = Theme::default()
+        .with_palette(Palette {
+            text: Color::SystemRed,
+            ..Palette::default()
+        })
+        .with_spacing(SpacingScale {
+            stack: crate::Unit::Pixels(10.0),
+            ..SpacingScale::default()
+        });
+    window.set_theme(theme);
+
+    assert_eq!(text_color(&window), Color::SystemRed);
+    assert_eq!(height(&window), before + 10.0);
+}
+
+#[test]
+fn test_theme_tokens() {
+    use crate::{
+        color::ColorScheme,
+        elements::{Modifiers, Text},
+        pal::headless::{Context, Window},
+        postprocessing::{border, corner_radii, padding, BorderStyle, CornerRadii},
+        shadow::NodeKind,
+    };
+
+    let modifiers = Modifiers::default();
+    let text = Box::new(Text::new("Card", modifiers.fork()));
+    let padding_modifiers = modifiers.fork();
+    padding_modifiers.lock().unwrap().padding.all = Unit::Space(Space::Md);
+    let padded = padding(text, padding_modifiers);
+    let rounded = corner_radii(Box::new(padded), modifiers.fork());
+    let bordered = border(1.0, Color::Text, BorderStyle::Solid, Box::new(rounded), modifiers);
+    let mut window = Context::new(ColorScheme::Light).render(Box::new(bordered), 400.0, 300.0);
+
+    let radii = |window: &Window| match (&window.root.kind, &window.root.children[0].kind) {
+        (NodeKind::Border { radii: border, .. }, NodeKind::CornerRadius { radii: corner }) => {
+            (*border, *corner)
+        }
+        _ => unreachable!(),
+    };
+    let inset = |window: &Window| match window.root.children[0].children[0].kind {
+        NodeKind::Padding { top, .. } => top,
+        _ => unreachable!(),
+    };
+    assert_eq!(radii(&window), (CornerRadii::all(0.0), CornerRadii::all(8.0)));
+    assert_eq!(inset(&window), 16.0);
+
+    let theme = Theme::default()
+        .with_spacing(SpacingScale {
+            md: Unit::Pixels(12.0),
+            ..SpacingScale::default()
+        })
+        .with_radii(RadiusScale {
+            border: 6.0,
+            corner: 4.0,
+            ..RadiusScale::default()
+        });
+    window.set_theme(theme);
+
+    assert_eq!(radii(&window), (CornerRadii::all(6.0), CornerRadii::all(4.0)));
+    assert_eq!(inset(&window), 12.0);
+}
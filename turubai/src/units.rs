@@ -1,12 +1,18 @@
 use core::f64;
 
+use crate::theme::{Space, SpacingScale};
+
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Unit {
     #[default]
     Auto,
     Pixels(f64),
     Percent(f64),
     Em(f64),
+    /// A step of the theme's spacing scale. Elements resolve it through
+    /// the environment; where there is none, the default scale is used.
+    Space(Space),
 }
 
 impl Eq for Unit {}
@@ -20,6 +26,7 @@ impl Unit {
                 .map(|available| available * v)
                 .unwrap_or(f64::INFINITY),
             Unit::Em(v) => v * 16.0,
+            Unit::Space(space) => SpacingScale::default().step(*space).to_pixels(available),
         }
     }
 
@@ -34,6 +41,7 @@ impl Into<taffy::Dimension> for Unit {
             Unit::Pixels(v) => taffy::Dimension::length(v as f32),
             Unit::Percent(v) => taffy::Dimension::percent(v as f32),
             Unit::Em(v) => taffy::Dimension::length(v as f32 * 16.0),
+            Unit::Space(space) => SpacingScale::default().step(space).into(),
             Unit::Auto => taffy::Dimension::auto(),
         }
     }
@@ -45,6 +53,7 @@ impl Into<taffy::LengthPercentage> for Unit {
             Unit::Pixels(v) => taffy::LengthPercentage::length(v as f32),
             Unit::Percent(v) => taffy::LengthPercentage::percent(v as f32),
             Unit::Em(v) => taffy::LengthPercentage::length(v as f32 * 16.0),
+            Unit::Space(space) => SpacingScale::default().step(space).into(),
             Unit::Auto => taffy::LengthPercentage::length(0.0),
         }
     }
@@ -0,0 +1,150 @@
+//! A command line client for the inspector of a running turubai
+//! application, see `turubai::inspector`.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use turubai::inspector::{
+    format_path, parse_path, socket_dir, socket_path, Client, InspectedNode, NodePath, Request,
+    Response, INSPECTOR_ENV,
+};
+
+const USAGE: &str = "\
+usage: turubai-inspect [--socket PATH | --app ID] COMMAND
+
+commands:
+    tree [PATH]                    print the shadow tree, or the subtree at PATH
+    node PATH                      print a node's kind, style and layout as JSON
+    highlight [PATH]               outline the node at PATH, or nothing
+    set PATH MODIFIER VALUE        set a modifier, e.g. `set 0.1 text::color SystemRed`
+
+Paths are indices among siblings separated by dots, like `0.1`, with `.`
+for the window's content. Without --socket or --app, the socket in
+$TURUBAI_INSPECTOR is used, or the only one of a running application.";
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("turubai-inspect: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut args: Vec<String>) -> Result<(), String> {
+    let mut socket = None;
+    while let Some(option) = args.first().filter(|arg| arg.starts_with("--")) {
+        let path = match option.as_str() {
+            "--socket" if args.len() > 1 => PathBuf::from(&args[1]),
+            "--app" if args.len() > 1 => {
+                socket_path(&args[1]).map_err(|err| format!("can't find the socket: {err}"))?
+            }
+            "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => return Err(USAGE.into()),
+        };
+        socket = Some(path);
+        args.drain(..2);
+    }
+
+    let request = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["tree"] => Request::Tree { path: vec![] },
+        ["tree", path] | ["node", path] => Request::Tree {
+            path: path_argument(path)?,
+        },
+        ["highlight"] => Request::Highlight { path: None },
+        ["highlight", path] => Request::Highlight {
+            path: Some(path_argument(path)?),
+        },
+        ["set", path, modifier, value] => Request::Set {
+            path: path_argument(path)?,
+            modifier: modifier.into(),
+            value: value.into(),
+        },
+        _ => return Err(USAGE.into()),
+    };
+
+    let socket = match socket {
+        Some(socket) => socket,
+        None => find_socket()?,
+    };
+    let mut client = Client::connect(&socket)
+        .map_err(|err| format!("can't connect to {}: {err}", socket.display()))?;
+    let response = client
+        .request(&request)
+        .map_err(|err| format!("{}: {err}", socket.display()))?;
+
+    match response {
+        Response::Tree(node) if args[0] == "node" => {
+            let node = InspectedNode {
+                children: vec![],
+                ..*node
+            };
+            println!("{}", serde_json::to_string_pretty(&node).unwrap());
+        }
+        Response::Tree(node) => print_tree(&node, 0),
+        Response::Ok => {}
+        Response::Error(message) => return Err(message),
+    }
+    Ok(())
+}
+
+fn path_argument(path: &str) -> Result<NodePath, String> {
+    parse_path(path).ok_or_else(|| format!("invalid path `{path}`"))
+}
+
+/// The socket named by the environment, or else the only one in the
+/// socket directory.
+fn find_socket() -> Result<PathBuf, String> {
+    if let Some(path) = std::env::var_os(INSPECTOR_ENV).filter(|path| path != "1" && path != "0") {
+        return Ok(path.into());
+    }
+    let dir = socket_dir().map_err(|err| format!("can't find the socket directory: {err}"))?;
+    let mut sockets: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|err| format!("can't read {}: {err}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with("turubai-") && name.ends_with(".sock")
+        })
+        .collect();
+    match sockets.len() {
+        0 => Err(format!(
+            "no application is listening in {}, run it with {INSPECTOR_ENV}=1",
+            dir.display()
+        )),
+        1 => Ok(sockets.remove(0)),
+        _ => {
+            let names: Vec<_> = sockets.iter().map(|path| path.display().to_string()).collect();
+            Err(format!(
+                "several applications are listening, pick one with --socket:\n  {}",
+                names.join("\n  ")
+            ))
+        }
+    }
+}
+
+/// Print `node` and its descendants like `ShadowTree::dump`, followed by
+/// their paths.
+fn print_tree(node: &InspectedNode, depth: usize) {
+    let layout = match node.layout {
+        Some(layout) => format!(
+            "[{},{} {}x{}]",
+            layout.location.x, layout.location.y, layout.size.width, layout.size.height
+        ),
+        None => "[not laid out]".into(),
+    };
+    println!(
+        "{:indent$}{} {layout}  {}",
+        "",
+        node.kind.label(),
+        format_path(&node.path),
+        indent = depth * 2
+    );
+    for child in &node.children {
+        print_tree(child, depth + 1);
+    }
+}